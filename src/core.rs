@@ -1,5 +1,11 @@
 //! Core parsing functionality.
 
+pub mod assets;
 pub mod constants;
+pub mod document;
+pub mod encoding;
 pub mod errors;
+pub mod options;
 pub mod parsers;
+pub mod provenance;
+pub mod tables;
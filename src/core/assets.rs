@@ -0,0 +1,127 @@
+//! Asset extraction module.
+//!
+//! This module provides functionality for extracting embedded binary assets, such as
+//! images and attached files, from container formats. Each container format is
+//! handled in its own submodule.
+
+mod ole;
+mod ooxml;
+mod pdf;
+
+use self::{ole::extract_ole_assets, ooxml::extract_ooxml_assets, pdf::extract_pdf_assets};
+
+use super::{
+    constants::{
+        APPLICATION_DOC, APPLICATION_DOCX, APPLICATION_MSG, APPLICATION_OLE_STORAGE,
+        APPLICATION_PDF, APPLICATION_PPT, APPLICATION_PPTX, APPLICATION_XLS, APPLICATION_XLSX,
+    },
+    errors::ParserError,
+    parsers::determine_mime_type,
+};
+use mime_guess::from_path;
+
+/// A binary asset embedded in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Asset {
+    /// Original name of the asset, or a generated one if the container stores none
+    pub name: String,
+    /// MIME type of the asset data
+    pub mime_type: String,
+    /// Where the asset was found in the container (a page, a part or a storage path)
+    pub position: String,
+    /// Raw asset data
+    pub data: Vec<u8>,
+}
+
+impl Asset {
+    /// Creates an asset, detecting its MIME type from the data or, as a fallback, the name.
+    fn new(name: String, position: String, data: Vec<u8>) -> Self {
+        let mime_type = determine_mime_type(&data).map_or_else(
+            || from_path(&name).first_or_octet_stream().to_string(),
+            |mime| mime.to_string(),
+        );
+
+        Self {
+            name,
+            mime_type,
+            position,
+            data,
+        }
+    }
+}
+
+/// Extracts the embedded images and attachments from the given data.
+///
+/// This function detects the container format from the provided byte data and
+/// delegates the extraction to the appropriate specialized extractor.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the file data to be processed
+///
+/// # Returns
+///
+/// * `Ok(Vec<Asset>)` - The assets found in the file, possibly empty
+/// * `Err(ParserError)` - If the file type is unsupported, unrecognized, or an error occurs during extraction
+///
+/// # Supported Containers
+///
+/// * PDF: page images and embedded file attachments
+/// * DOCX, XLSX, PPTX: files in the `media` folders and the `embeddings` folders
+/// * OLE2 compound files (DOC, XLS, PPT): embedded `Ole10Native` packages
+///
+/// # Examples
+///
+/// ```no_run
+/// use parser::extract_assets;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("presentation.pptx")?;
+/// for asset in extract_assets(&data)? {
+///     println!("{} ({}) from {}", asset.name, asset.mime_type, asset.position);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ParserError::InvalidFormat`] if the file type cannot hold assets or is unrecognized.
+/// May return other [`ParserError`] variants if an error occurs during extraction.
+pub fn extract_assets(data: &[u8]) -> Result<Vec<Asset>, ParserError> {
+    match determine_mime_type(data) {
+        Some(mime) if mime == APPLICATION_PDF => extract_pdf_assets(data),
+        Some(mime)
+            if mime == APPLICATION_DOCX || mime == APPLICATION_XLSX || mime == APPLICATION_PPTX =>
+        {
+            extract_ooxml_assets(data)
+        }
+        Some(mime)
+            if mime == APPLICATION_DOC
+                || mime == APPLICATION_MSG
+                || mime == APPLICATION_XLS
+                || mime == APPLICATION_PPT
+                || mime == APPLICATION_OLE_STORAGE =>
+        {
+            extract_ole_assets(data)
+        }
+        Some(mime) => Err(ParserError::InvalidFormat(format!(
+            "Unsupported file type for asset extraction: {mime}"
+        ))),
+        None => Err(ParserError::InvalidFormat(
+            "Could not determine file type.".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_assets_unsupported() {
+        let result = extract_assets(b"Hello, world!");
+
+        assert!(matches!(result, Err(ParserError::InvalidFormat(_))));
+    }
+}
@@ -0,0 +1,143 @@
+//! OLE asset extractor module.
+//!
+//! This module provides functionality for extracting embedded files from OLE2
+//! compound files, either standalone (DOC, XLS, PPT) or embedded in OOXML packages,
+//! using the `cfb` library.
+
+use super::super::errors::ParserError;
+use super::Asset;
+use cfb::CompoundFile;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+
+/// Stream holding a file embedded with the Object Packager.
+const OLE10_NATIVE_STREAM: &str = "\u{1}Ole10Native";
+
+/// Streams holding the raw data of an embedded document (OOXML package or PDF).
+const PACKAGE_STREAMS: &[&str] = &["Package", "CONTENTS"];
+
+/// Extracts embedded files from an OLE2 compound file.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the compound file data
+///
+/// # Returns
+///
+/// * `Ok(Vec<Asset>)` - The embedded files found in the compound file
+/// * `Err(ParserError)` - If the compound file cannot be read
+///
+/// # Implementation Notes
+///
+/// * Walks every storage of the compound file
+/// * `Ole10Native` streams are decoded to recover the original file name and data
+/// * `Package` and `CONTENTS` streams are returned as is, named after their storage
+/// * The position of an asset is the path of the storage holding it
+pub(super) fn extract_ole_assets(data: &[u8]) -> Result<Vec<Asset>, ParserError> {
+    let mut compound = CompoundFile::open(Cursor::new(data))?;
+
+    let streams: Vec<PathBuf> = compound
+        .walk()
+        .filter(|entry| {
+            entry.is_stream()
+                && (entry.name() == OLE10_NATIVE_STREAM || PACKAGE_STREAMS.contains(&entry.name()))
+        })
+        .map(|entry| entry.path().to_path_buf())
+        .collect();
+
+    let mut assets = Vec::new();
+    for path in streams {
+        let mut content = Vec::new();
+        compound.open_stream(&path)?.read_to_end(&mut content)?;
+
+        let storage = path.parent().unwrap_or(Path::new("/"));
+        let position = storage.to_string_lossy().to_string();
+
+        if path.ends_with(OLE10_NATIVE_STREAM) {
+            if let Some((name, native)) = parse_ole10_native(&content) {
+                assets.push(Asset::new(name, position, native));
+            }
+        } else {
+            let name = storage.file_name().map_or_else(
+                || "embedded".to_string(),
+                |n| n.to_string_lossy().to_string(),
+            );
+            assets.push(Asset::new(name, position, content));
+        }
+    }
+
+    Ok(assets)
+}
+
+/// Decodes an `Ole10Native` stream into the original file name and data.
+///
+/// The stream layout is: total size (u32), flags (u16), label (C string),
+/// original path (C string), reserved (u32), temporary path (length-prefixed
+/// C string), data size (u32) and finally the data itself.
+fn parse_ole10_native(stream: &[u8]) -> Option<(String, Vec<u8>)> {
+    let mut position = 6;
+
+    let label = read_c_string(stream, &mut position)?;
+    let original_path = read_c_string(stream, &mut position)?;
+    position += 4;
+    let temp_path_length = read_u32(stream, &mut position)?;
+    position = position.checked_add(temp_path_length)?;
+    let data_size = read_u32(stream, &mut position)?;
+    let data = stream.get(position..position.checked_add(data_size)?)?;
+
+    // Prefer the label, falling back to the file name of the original path
+    let name = if label.is_empty() {
+        original_path
+            .rsplit(['\\', '/'])
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    } else {
+        label
+    };
+
+    Some((name, data.to_vec()))
+}
+
+/// Reads a NUL-terminated string, advancing the position past the terminator.
+fn read_c_string(stream: &[u8], position: &mut usize) -> Option<String> {
+    let rest = stream.get(*position..)?;
+    let end = rest.iter().position(|&b| b == 0)?;
+    *position += end + 1;
+    Some(String::from_utf8_lossy(&rest[..end]).to_string())
+}
+
+/// Reads a little-endian u32, advancing the position past it.
+fn read_u32(stream: &[u8], position: &mut usize) -> Option<usize> {
+    let bytes = stream.get(*position..*position + 4)?;
+    *position += 4;
+    usize::try_from(u32::from_le_bytes(bytes.try_into().ok()?)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ole10_native_success() {
+        let mut stream = vec![0, 0, 0, 0, 2, 0];
+        stream.extend_from_slice(b"notes.txt\0C:\\Users\\me\\notes.txt\0");
+        stream.extend_from_slice(&[0, 0, 3, 0]);
+        stream.extend_from_slice(&4u32.to_le_bytes());
+        stream.extend_from_slice(b"tmp\0");
+        stream.extend_from_slice(&5u32.to_le_bytes());
+        stream.extend_from_slice(b"Hello");
+
+        let (name, data) = parse_ole10_native(&stream).unwrap();
+
+        assert_eq!(name, "notes.txt");
+        assert_eq!(data, b"Hello");
+    }
+
+    #[test]
+    fn parse_ole10_native_truncated() {
+        let stream = vec![0, 0, 0, 0, 2, 0, b'a', 0];
+
+        assert!(parse_ole10_native(&stream).is_none());
+    }
+}
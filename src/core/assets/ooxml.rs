@@ -0,0 +1,188 @@
+//! OOXML asset extractor module.
+//!
+//! This module provides functionality for extracting media files and embedded objects
+//! from DOCX, XLSX and PPTX packages. Like the PPTX parser, it treats the package as a
+//! ZIP archive and uses regex to read the relationship parts.
+
+use super::super::{
+    errors::ParserError,
+    parsers::package::{Package, directory, open_package, resolve},
+};
+use super::{Asset, ole::extract_ole_assets};
+use regex::Regex;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+/// Signature of OLE2 compound files, used for embedded objects.
+const OLE_SIGNATURE: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// Extracts media files and embedded objects from an OOXML package.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the DOCX, XLSX or PPTX data
+///
+/// # Returns
+///
+/// * `Ok(Vec<Asset>)` - The assets found in the package
+/// * `Err(ParserError)` - If an error occurs while reading the package
+///
+/// # Implementation Notes
+///
+/// * Every file under a `media` folder is returned as is
+/// * Files under an `embeddings` folder are unwrapped when they are OLE containers,
+///   otherwise (e.g. an embedded XLSX) or when the container can't be read they are
+///   returned as is
+/// * The position of an asset is the part referencing it, found through the
+///   `_rels` relationship files, or the asset path when nothing references it
+pub(super) fn extract_ooxml_assets(data: &[u8]) -> Result<Vec<Asset>, ParserError> {
+    let mut archive = open_package(data)?;
+    let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+
+    let owners = relationship_owners(&mut archive, &names)?;
+
+    let mut assets = Vec::new();
+    for name in &names {
+        let in_folder = |folder: &str| name.split('/').any(|part| part == folder);
+        let is_media = in_folder("media");
+        let is_embedding = in_folder("embeddings");
+        if name.ends_with('/') || !(is_media || is_embedding) {
+            continue;
+        }
+
+        let mut content = Vec::new();
+        archive.by_name(name)?.read_to_end(&mut content)?;
+
+        let position = owners.get(name).unwrap_or(name).clone();
+        let file_name = name.rsplit('/').next().unwrap_or(name).to_string();
+
+        if is_embedding && content.starts_with(OLE_SIGNATURE) {
+            // Unwrap the OLE container, keeping the raw object if it can't be read or
+            // holds no known package
+            let embedded = extract_ole_assets(&content).unwrap_or_default();
+            if embedded.is_empty() {
+                assets.push(Asset::new(file_name, position, content));
+            } else {
+                assets.extend(embedded.into_iter().map(|asset| Asset {
+                    position: position.clone(),
+                    ..asset
+                }));
+            }
+        } else {
+            assets.push(Asset::new(file_name, position, content));
+        }
+    }
+
+    Ok(assets)
+}
+
+/// Maps every internal relationship target to the part that declares it.
+fn relationship_owners(
+    archive: &mut Package,
+    names: &[String],
+) -> Result<HashMap<String, String>, ParserError> {
+    let relationship_pattern = Regex::new(r"<Relationship\b[^>]*>")?;
+    let target_pattern = Regex::new(r#"\bTarget="([^"]+)""#)?;
+
+    let mut owners = HashMap::new();
+    let is_rels = |name: &&String| {
+        Path::new(name.as_str())
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("rels"))
+    };
+
+    for name in names.iter().filter(is_rels) {
+        let mut content = String::new();
+        archive.by_name(name)?.read_to_string(&mut content)?;
+
+        let source = relationship_source(name);
+        let base = directory(&source);
+
+        for relationship in relationship_pattern.find_iter(&content) {
+            let relationship = relationship.as_str();
+            if relationship.contains(r#"TargetMode="External""#) {
+                continue;
+            }
+            if let Some(target) = target_pattern
+                .captures(relationship)
+                .and_then(|cap| cap.get(1))
+            {
+                owners
+                    .entry(resolve(base, target.as_str()))
+                    .or_insert_with(|| source.clone());
+            }
+        }
+    }
+
+    Ok(owners)
+}
+
+/// Returns the part described by a relationship file,
+/// e.g. `word/_rels/document.xml.rels` describes `word/document.xml`.
+fn relationship_source(rels_name: &str) -> String {
+    let part = rels_name.strip_suffix(".rels").unwrap_or(rels_name);
+    match part.rsplit_once("_rels/") {
+        // The package relationships (`_rels/.rels`) belong to the package root
+        Some((dir, ".")) => dir.trim_end_matches('/').to_string(),
+        Some((dir, file)) => format!("{dir}{file}"),
+        None => part.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn extract_ooxml_assets_success() {
+        let data = read_test_file("test_docx_3.docx");
+        let result = extract_ooxml_assets(&data).unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, "image1.png");
+        assert_eq!(result[0].mime_type, "image/png");
+        assert_eq!(result[0].position, "word/document.xml");
+        assert_eq!(result[0].data, read_test_file("test_png_1.png"));
+    }
+
+    #[test]
+    fn extract_ooxml_assets_empty() {
+        let data = read_test_file("test_pptx_1.pptx");
+        let result = extract_ooxml_assets(&data).unwrap();
+
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn extract_ooxml_assets_corrupt_embedding() {
+        let mut embedding = OLE_SIGNATURE.to_vec();
+        embedding.extend_from_slice(b"not a compound file");
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file(
+                "word/embeddings/oleObject1.bin",
+                SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer.write_all(&embedding).unwrap();
+        let data = writer.finish().unwrap().into_inner();
+        let result = extract_ooxml_assets(&data).unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, "oleObject1.bin");
+        assert_eq!(result[0].data, embedding);
+    }
+}
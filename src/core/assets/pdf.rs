@@ -0,0 +1,240 @@
+//! PDF asset extractor module.
+//!
+//! This module provides functionality for extracting images and file attachments
+//! from PDF documents using the `lopdf` library.
+
+use super::super::errors::ParserError;
+use super::Asset;
+use lopdf::{Dictionary, Document, Object, Stream, decode_text_string};
+use png::{BitDepth, ColorType, Encoder};
+
+/// Maximum depth followed in the embedded files name tree.
+const MAX_NAME_TREE_DEPTH: usize = 32;
+
+/// Extracts page images and embedded files from a PDF document.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the PDF data
+///
+/// # Returns
+///
+/// * `Ok(Vec<Asset>)` - The images and attachments found in the document
+/// * `Err(ParserError)` - If the PDF cannot be loaded
+///
+/// # Implementation Notes
+///
+/// * JPEG (`DCTDecode`) and JPEG 2000 (`JPXDecode`) images are returned unchanged
+/// * 8-bit RGB and grayscale images are re-encoded as PNG
+/// * Images in other encodings (e.g. CCITT, JBIG2, indexed colors) are skipped
+/// * Images are named after their resource name and positioned by page number
+/// * Attachments come from the `EmbeddedFiles` name tree and from file attachment
+///   annotations, keeping their original file name
+pub(super) fn extract_pdf_assets(data: &[u8]) -> Result<Vec<Asset>, ParserError> {
+    let document = Document::load_mem(data)?;
+    let mut assets = Vec::new();
+
+    for (page_number, page_id) in document.get_pages() {
+        let position = format!("page {page_number}");
+
+        for (resource_name, stream) in page_images(&document, page_id) {
+            if let Some((extension, image)) = encode_image(stream) {
+                let name = format!("page{page_number}_{resource_name}.{extension}");
+                assets.push(Asset::new(name, position.clone(), image));
+            }
+        }
+
+        for annotation in document.get_page_annotations(page_id).unwrap_or_default() {
+            let is_attachment = annotation
+                .get(b"Subtype")
+                .and_then(Object::as_name)
+                .is_ok_and(|subtype| subtype == b"FileAttachment");
+            if !is_attachment {
+                continue;
+            }
+            if let Some(asset) = annotation
+                .get(b"FS")
+                .ok()
+                .and_then(|spec| file_specification(&document, spec, &position))
+            {
+                assets.push(asset);
+            }
+        }
+    }
+
+    if let Some(tree) = embedded_files_tree(&document) {
+        collect_embedded_files(&document, tree, 0, &mut assets);
+    }
+
+    Ok(assets)
+}
+
+/// Returns the image `XObject`s of a page along with their resource names.
+fn page_images(document: &Document, page_id: lopdf::ObjectId) -> Vec<(String, &Stream)> {
+    let Ok((direct, inherited)) = document.get_page_resources(page_id) else {
+        return Vec::new();
+    };
+
+    direct
+        .into_iter()
+        .chain(
+            inherited
+                .into_iter()
+                .filter_map(|id| document.get_dictionary(id).ok()),
+        )
+        .filter_map(|resources| resolve_dictionary(document, resources.get(b"XObject").ok()?))
+        .flat_map(Dictionary::iter)
+        .filter_map(|(name, object)| {
+            let stream = document.dereference(object).ok()?.1.as_stream().ok()?;
+            let is_image = stream
+                .dict
+                .get(b"Subtype")
+                .and_then(Object::as_name)
+                .is_ok_and(|subtype| subtype == b"Image");
+            is_image.then(|| (String::from_utf8_lossy(name).to_string(), stream))
+        })
+        .collect()
+}
+
+/// Converts an image stream into a standalone image file, returning its extension and data.
+fn encode_image(stream: &Stream) -> Option<(&'static str, Vec<u8>)> {
+    let filters = stream.filters().unwrap_or_default();
+
+    match filters.as_slice() {
+        [b"DCTDecode"] => Some(("jpg", stream.content.clone())),
+        [b"JPXDecode"] => Some(("jp2", stream.content.clone())),
+        [] | [b"FlateDecode" | b"LZWDecode"] => {
+            let dict = &stream.dict;
+            let width = u32::try_from(dict.get(b"Width").ok()?.as_i64().ok()?).ok()?;
+            let height = u32::try_from(dict.get(b"Height").ok()?.as_i64().ok()?).ok()?;
+            let bits = dict.get(b"BitsPerComponent").ok()?.as_i64().ok()?;
+            let color = match dict.get(b"ColorSpace").ok()?.as_name().ok()? {
+                b"DeviceRGB" => ColorType::Rgb,
+                b"DeviceGray" => ColorType::Grayscale,
+                _ => return None,
+            };
+            if bits != 8 {
+                return None;
+            }
+
+            let pixels = if filters.is_empty() {
+                stream.content.clone()
+            } else {
+                stream.decompressed_content().ok()?
+            };
+            let mut png = Vec::new();
+            let mut encoder = Encoder::new(&mut png, width, height);
+            encoder.set_color(color);
+            encoder.set_depth(BitDepth::Eight);
+            encoder
+                .write_header()
+                .ok()?
+                .write_image_data(&pixels)
+                .ok()?;
+
+            Some(("png", png))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the root of the `EmbeddedFiles` name tree, if the document has one.
+fn embedded_files_tree(document: &Document) -> Option<&Dictionary> {
+    let names = resolve_dictionary(document, document.catalog().ok()?.get(b"Names").ok()?)?;
+    resolve_dictionary(document, names.get(b"EmbeddedFiles").ok()?)
+}
+
+/// Walks a name tree node, collecting the files referenced by its leaves.
+fn collect_embedded_files(
+    document: &Document,
+    node: &Dictionary,
+    depth: usize,
+    assets: &mut Vec<Asset>,
+) {
+    if depth > MAX_NAME_TREE_DEPTH {
+        return;
+    }
+
+    if let Ok(names) = node.get(b"Names").and_then(Object::as_array) {
+        // Leaves alternate between a key and a file specification
+        for spec in names.iter().skip(1).step_by(2) {
+            if let Some(asset) = file_specification(document, spec, "attachment") {
+                assets.push(asset);
+            }
+        }
+    }
+
+    if let Ok(kids) = node.get(b"Kids").and_then(Object::as_array) {
+        for kid in kids {
+            if let Some(kid) = resolve_dictionary(document, kid) {
+                collect_embedded_files(document, kid, depth + 1, assets);
+            }
+        }
+    }
+}
+
+/// Reads the file embedded in a file specification dictionary.
+fn file_specification(document: &Document, spec: &Object, position: &str) -> Option<Asset> {
+    let spec = resolve_dictionary(document, spec)?;
+
+    let name = spec
+        .get(b"UF")
+        .or_else(|_| spec.get(b"F"))
+        .ok()
+        .and_then(|name| decode_text_string(document.dereference(name).ok()?.1).ok())
+        .unwrap_or_else(|| "attachment".to_string());
+
+    let embedded = resolve_dictionary(document, spec.get(b"EF").ok()?)?;
+    let file = embedded.get(b"F").or_else(|_| embedded.get(b"UF")).ok()?;
+    let stream = document.dereference(file).ok()?.1.as_stream().ok()?;
+    let data = stream
+        .decompressed_content()
+        .unwrap_or_else(|_| stream.content.clone());
+
+    Some(Asset::new(name, position.to_string(), data))
+}
+
+/// Follows references until reaching a dictionary.
+fn resolve_dictionary<'a>(document: &'a Document, object: &'a Object) -> Option<&'a Dictionary> {
+    document.dereference(object).ok()?.1.as_dict().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn extract_pdf_assets_success() {
+        let data = read_test_file("test_pdf_3.pdf");
+        let result = extract_pdf_assets(&data).unwrap();
+
+        assert_eq!(result.len(), 2);
+
+        assert_eq!(result[0].name, "page1_Im0.jpg");
+        assert_eq!(result[0].mime_type, "image/jpeg");
+        assert_eq!(result[0].position, "page 1");
+        assert_eq!(result[0].data, read_test_file("test_jpg_1.jpg"));
+
+        assert_eq!(result[1].name, "notes.txt");
+        assert_eq!(result[1].mime_type, "text/plain");
+        assert_eq!(result[1].position, "attachment");
+        assert_eq!(result[1].data, b"Hello, this is an attachment.");
+    }
+
+    #[test]
+    fn extract_pdf_assets_empty() {
+        let data = read_test_file("test_pdf_1.pdf");
+        let result = extract_pdf_assets(&data).unwrap();
+
+        assert!(result.is_empty());
+    }
+}
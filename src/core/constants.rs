@@ -16,3 +16,69 @@ pub const APPLICATION_XLSX: &str =
 /// MIME type for PPTX (Microsoft `PowerPoint`) presentations
 pub const APPLICATION_PPTX: &str =
     "application/vnd.openxmlformats-officedocument.presentationml.presentation";
+
+/// MIME type for ODT (`OpenDocument` Text) documents
+pub const APPLICATION_ODT: &str = "application/vnd.oasis.opendocument.text";
+
+/// MIME type for ODP (`OpenDocument` Presentation) presentations
+pub const APPLICATION_ODP: &str = "application/vnd.oasis.opendocument.presentation";
+
+/// MIME type for EPUB e-books
+pub const APPLICATION_EPUB: &str = "application/epub+zip";
+
+/// MIME type for Apple Pages documents
+pub const APPLICATION_PAGES: &str = "application/vnd.apple.pages";
+
+/// MIME type for Apple Numbers spreadsheets
+pub const APPLICATION_NUMBERS: &str = "application/vnd.apple.numbers";
+
+/// MIME type for Apple Keynote presentations
+pub const APPLICATION_KEYNOTE: &str = "application/vnd.apple.keynote";
+
+/// MIME type for XPS documents
+pub const APPLICATION_XPS: &str = "application/vnd.ms-xpsdocument";
+
+/// MIME type for `OpenXPS` documents
+pub const APPLICATION_OXPS: &str = "application/oxps";
+
+/// MIME type for RTF (Rich Text Format) documents
+pub const APPLICATION_RTF: &str = "application/rtf";
+
+/// MIME type for legacy DOC (Microsoft Word 97-2003) documents
+pub const APPLICATION_DOC: &str = "application/msword";
+
+/// MIME type for legacy XLS (Microsoft Excel 97-2003) spreadsheets
+pub const APPLICATION_XLS: &str = "application/vnd.ms-excel";
+
+/// MIME type for legacy PPT (Microsoft `PowerPoint` 97-2003) presentations
+pub const APPLICATION_PPT: &str = "application/vnd.ms-powerpoint";
+
+/// MIME type for generic OLE2 compound files that are not a known Office format
+pub const APPLICATION_OLE_STORAGE: &str = "application/x-ole-storage";
+
+/// MIME type for Outlook (MSG) messages
+pub const APPLICATION_MSG: &str = "application/vnd.ms-outlook";
+
+/// MIME type for MIME (EML) email messages
+pub const MESSAGE_RFC822: &str = "message/rfc822";
+
+/// MIME type for MBOX mailboxes
+pub const APPLICATION_MBOX: &str = "application/mbox";
+
+/// MIME type for ZIP archives
+pub const APPLICATION_ZIP: &str = "application/zip";
+
+/// MIME type for TAR archives
+pub const APPLICATION_TAR: &str = "application/x-tar";
+
+/// MIME type for gzip compressed files
+pub const APPLICATION_GZIP: &str = "application/gzip";
+
+/// MIME type for bzip2 compressed files
+pub const APPLICATION_BZIP2: &str = "application/x-bzip2";
+
+/// MIME type for xz compressed files
+pub const APPLICATION_XZ: &str = "application/x-xz";
+
+/// MIME type for 7z archives
+pub const APPLICATION_7Z: &str = "application/x-7z-compressed";
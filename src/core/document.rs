@@ -0,0 +1,59 @@
+//! Parsed document representation.
+//!
+//! This module defines the result returned by
+//! [`parse_with_options`](super::parsers::parse_with_options), which carries the
+//! extracted text along with what was learned about the document while parsing it.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Metadata key holding the character encoding a text file was decoded from
+pub const METADATA_ENCODING: &str = "encoding";
+
+/// Metadata key holding the format recognised for a text file (e.g. `csv`)
+pub const METADATA_FORMAT: &str = "format";
+
+/// Metadata key holding the delimiter of a CSV/TSV file
+pub const METADATA_DELIMITER: &str = "delimiter";
+
+/// Metadata key telling whether the first row of a table was detected as a header
+pub const METADATA_HEADER: &str = "header";
+
+/// Metadata key holding the title of a document
+pub const METADATA_TITLE: &str = "title";
+
+/// Metadata key holding the author of a document
+pub const METADATA_AUTHOR: &str = "author";
+
+/// Metadata key holding the language of a document (e.g. `en`)
+pub const METADATA_LANGUAGE: &str = "language";
+
+/// Metadata key holding the programming language of a source file (e.g. `rust`)
+pub const METADATA_CODE_LANGUAGE: &str = "code_language";
+
+/// The result of parsing a document.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ParsedDocument {
+    /// Text extracted from the document
+    pub text: String,
+    /// Information gathered while parsing, such as the detected encoding
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl ParsedDocument {
+    /// Adds a metadata entry to the document.
+    #[must_use]
+    pub fn with_metadata(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.to_string(), value.into());
+        self
+    }
+}
+
+impl From<String> for ParsedDocument {
+    fn from(text: String) -> Self {
+        Self {
+            text,
+            metadata: BTreeMap::new(),
+        }
+    }
+}
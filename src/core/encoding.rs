@@ -0,0 +1,210 @@
+//! Character encoding detection.
+//!
+//! This module provides functionality for detecting the character encoding of text
+//! data and transcoding it to UTF-8. Detection relies on byte order marks, a UTF-16
+//! heuristic and the `chardetng` statistical detector for legacy encodings.
+
+use super::errors::ParserError;
+use chardetng::{EncodingDetector, Iso2022JpDetection, Utf8Detection};
+use encoding_rs::{Encoding, UTF_8, UTF_16BE, UTF_16LE};
+
+/// Number of leading bytes inspected by the UTF-16 and binary heuristics
+const SAMPLE_SIZE: usize = 8192;
+
+/// Detects the character encoding of text data.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the data to be analyzed
+///
+/// # Returns
+///
+/// * `Some(&Encoding)` - The detected encoding if the data looks like text
+/// * `None` - If the data looks like binary content
+///
+/// # Implementation Details
+///
+/// - A byte order mark (UTF-8, UTF-16LE, UTF-16BE) always wins
+/// - UTF-16 without BOM is recognised by the zero bytes of its ASCII characters
+/// - Valid UTF-8 is reported as such
+/// - Data containing NUL or too many control characters is considered binary
+/// - Anything else is handed to `chardetng` to guess a legacy encoding
+pub(crate) fn detect_encoding(data: &[u8]) -> Option<&'static Encoding> {
+    if let Some((encoding, _)) = Encoding::for_bom(data) {
+        return Some(encoding);
+    }
+
+    let sample = &data[..data.len().min(SAMPLE_SIZE)];
+
+    // ASCII text in UTF-16 is also valid UTF-8, so it must be checked first
+    if let Some(encoding) = detect_utf16(sample) {
+        return Some(encoding);
+    }
+
+    if std::str::from_utf8(data).is_ok() {
+        return Some(UTF_8);
+    }
+
+    if looks_binary(sample) {
+        return None;
+    }
+
+    let mut detector = EncodingDetector::new(Iso2022JpDetection::Allow);
+    detector.feed(data, true);
+    Some(detector.guess(None, Utf8Detection::Allow))
+}
+
+/// Decodes text data to UTF-8.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the text data
+/// * `label` - An optional encoding label (e.g. `windows-1252`) overriding detection
+///
+/// # Returns
+///
+/// * `Ok((String, &Encoding))` - The decoded text and the encoding it was decoded from
+/// * `Err(ParserError)` - If the label is unknown or the data does not look like text
+///
+/// # Implementation Notes
+///
+/// * A byte order mark matching the encoding is removed from the text
+/// * Malformed sequences are replaced with U+FFFD rather than failing
+pub(crate) fn decode_text(
+    data: &[u8],
+    label: Option<&str>,
+) -> Result<(String, &'static Encoding), ParserError> {
+    let encoding = match label {
+        Some(label) => Encoding::for_label(label.trim().as_bytes())
+            .ok_or_else(|| ParserError::InvalidFormat(format!("Unknown encoding: {label}")))?,
+        None => detect_encoding(data).ok_or_else(|| {
+            ParserError::InvalidFormat("Could not detect text encoding.".to_string())
+        })?,
+    };
+
+    let (text, _) = encoding.decode_with_bom_removal(data);
+    Ok((text.into_owned(), encoding))
+}
+
+/// Recognises UTF-16 without BOM from the distribution of zero bytes.
+///
+/// Text made mostly of ASCII characters has a zero high byte in every code unit,
+/// so one byte position out of two is overwhelmingly zero while the other is not.
+fn detect_utf16(sample: &[u8]) -> Option<&'static Encoding> {
+    let units = sample.len() / 2;
+    if units == 0 {
+        return None;
+    }
+
+    let zeros_at = |offset: usize| {
+        sample
+            .iter()
+            .skip(offset)
+            .step_by(2)
+            .take(units)
+            .filter(|&&b| b == 0)
+            .count()
+    };
+    let (even, odd) = (zeros_at(0), zeros_at(1));
+
+    // At least 30% of code units must be zero on one side and nearly none on the other
+    let mostly = |zeros: usize| zeros * 10 >= units * 3;
+    let rarely = |zeros: usize| zeros * 20 <= units;
+
+    if mostly(odd) && rarely(even) {
+        Some(UTF_16LE)
+    } else if mostly(even) && rarely(odd) {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Checks whether data looks like binary content rather than text.
+fn looks_binary(sample: &[u8]) -> bool {
+    let control = sample
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0C | 0x1B))
+        .count();
+
+    // NUL never appears in legacy text encodings, other controls only sporadically
+    sample.contains(&0) || control * 100 > sample.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn detect_encoding_success() {
+        assert_eq!(detect_encoding(b"Hello, world!"), Some(UTF_8));
+        assert_eq!(detect_encoding(b"\xEF\xBB\xBFHello"), Some(UTF_8));
+        assert_eq!(
+            detect_encoding(&read_test_file("test_txt_3.txt")).map(Encoding::name),
+            Some("windows-1252")
+        );
+        assert_eq!(
+            detect_encoding(&read_test_file("test_txt_4.txt")),
+            Some(UTF_16LE)
+        );
+    }
+
+    #[test]
+    fn detect_utf16_without_bom() {
+        let le: Vec<u8> = "Hello, world!"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let be: Vec<u8> = "Hello, world!"
+            .encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect();
+
+        assert_eq!(detect_encoding(&le), Some(UTF_16LE));
+        assert_eq!(detect_encoding(&be), Some(UTF_16BE));
+    }
+
+    #[test]
+    fn detect_encoding_binary() {
+        assert_eq!(
+            detect_encoding(&[0x00, 0x01, 0x02, 0xFF, 0xFE, 0x00, 0x80]),
+            None
+        );
+        assert_eq!(detect_encoding(&read_test_file("test_png_1.png")), None);
+    }
+
+    #[test]
+    fn decode_text_success() {
+        let (text, encoding) = decode_text(&read_test_file("test_txt_4.txt"), None).unwrap();
+
+        assert_eq!(encoding, UTF_16LE);
+        assert_eq!(
+            text,
+            "Hello, this is a UTF-16 test txt for the parsing API."
+        );
+    }
+
+    #[test]
+    fn decode_text_with_label() {
+        let (text, encoding) = decode_text(b"Fran\xE7ois", Some("latin1")).unwrap();
+
+        assert_eq!(encoding.name(), "windows-1252");
+        assert_eq!(text, "François");
+    }
+
+    #[test]
+    fn decode_text_unknown_label() {
+        let result = decode_text(b"Hello", Some("not-an-encoding"));
+
+        assert!(matches!(result, Err(ParserError::InvalidFormat(_))));
+    }
+}
@@ -60,9 +60,14 @@ impl_from_error!(
     tesseract::plumbing::TessBaseApiGetUtf8TextError,
     ParserError::IoError
 );
+impl_from_error!(
+    tesseract::plumbing::TessBaseApiGetTsvTextError,
+    ParserError::IoError
+);
 
 // Parse errors
 impl_from_error!(pdf_extract::OutputError, ParserError::ParseError);
+impl_from_error!(lopdf::Error, ParserError::ParseError);
 impl_from_error!(docx_rs::ReaderError, ParserError::ParseError);
 impl_from_error!(std::string::FromUtf8Error, ParserError::ParseError);
 impl_from_error!(std::str::Utf8Error, ParserError::ParseError);
@@ -70,6 +75,16 @@ impl_from_error!(zip::result::ZipError, ParserError::ParseError);
 impl_from_error!(regex::Error, ParserError::ParseError);
 impl_from_error!(std::process::ExitStatus, ParserError::ParseError);
 impl_from_error!(calamine::XlsxError, ParserError::ParseError);
+impl_from_error!(csv::Error, ParserError::ParseError);
+impl_from_error!(serde_json::Error, ParserError::ParseError);
+impl_from_error!(serde_yaml_ng::Error, ParserError::ParseError);
+impl_from_error!(roxmltree::Error, ParserError::ParseError);
+impl_from_error!(toml::de::Error, ParserError::ParseError);
+impl_from_error!(toml::ser::Error, ParserError::ParseError);
+impl_from_error!(base64::DecodeError, ParserError::ParseError);
+impl_from_error!(snap::Error, ParserError::ParseError);
+impl_from_error!(resvg::usvg::Error, ParserError::ParseError);
+impl_from_error!(sevenz_rust2::Error, ParserError::ParseError);
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,275 @@
+//! Parsing options.
+//!
+//! This module defines the options that callers can pass to
+//! [`parse_with_options`](super::parsers::parse_with_options) to tune how documents
+//! are parsed. Every option has a default matching the behavior of
+//! [`parse`](super::parsers::parse).
+
+use serde::Deserialize;
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, AtomicUsize},
+};
+
+/// Options controlling how documents are parsed.
+///
+/// # Examples
+///
+/// ```
+/// use parser::{OutputFormat, ParseOptions, StructuredMode, TableFormat};
+///
+/// let options = ParseOptions {
+///     file_name: Some("export.csv".to_string()),
+///     encoding: Some("windows-1252".to_string()),
+///     table_format: TableFormat::Markdown,
+///     structured_mode: StructuredMode::Flatten,
+///     output_format: OutputFormat::Markdown,
+///     ..ParseOptions::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)] // Each flag is an independent option
+pub struct ParseOptions {
+    /// Name of the parsed file, used as a hint to recognise formats whose content
+    /// alone is ambiguous (e.g. `.csv`, `.tsv`)
+    pub file_name: Option<String>,
+    /// Character encoding of text files (e.g. `windows-1252`, `shift_jis`, `utf-16le`),
+    /// overriding the automatic detection
+    pub encoding: Option<String>,
+    /// How tabular content (CSV/TSV files, spreadsheets) is rendered
+    pub table_format: TableFormat,
+    /// How structured data (JSON, NDJSON, YAML, TOML) is rendered
+    pub structured_mode: StructuredMode,
+    /// Whether invalid structured data is reported as an error rather than
+    /// returned as is
+    pub strict: bool,
+    /// Whether the text is output as plain text or Markdown
+    pub output_format: OutputFormat,
+    /// Whether link targets are kept next to the link text (HTML, Markdown,
+    /// reStructuredText, AsciiDoc)
+    pub keep_links: bool,
+    /// Whether code blocks are dropped from Markdown, reStructuredText and AsciiDoc
+    /// documents
+    pub skip_code_blocks: bool,
+    /// Whether the paragraphs of subtitle files (SRT, `WebVTT`, SBV) start with the
+    /// time they are spoken at
+    pub timestamps: bool,
+    /// How source code files are extracted: whole, reduced to their comments, or
+    /// without them
+    pub code_mode: CodeMode,
+    /// How the formulas of LaTeX documents are rendered: as their source or as
+    /// placeholders
+    pub math_mode: MathMode,
+    /// Whether SVG images are also rendered and their text recognised with OCR, to
+    /// read text converted to outlines
+    pub svg_ocr: bool,
+    /// Selection of the text extracted from XML documents
+    pub xml: XmlOptions,
+    /// Outputs extracted from Jupyter notebooks
+    pub notebook: NotebookOptions,
+    /// Limits applied while unpacking archives
+    pub archive: ArchiveOptions,
+    /// Where the document is nested in other documents, left to its default by
+    /// callers
+    pub nesting: Nesting,
+}
+
+/// Options selecting the text extracted from XML documents.
+///
+/// # Examples
+///
+/// ```
+/// use parser::{ParseOptions, XmlOptions};
+///
+/// // Extract only the descriptions of the items, without their links
+/// let options = ParseOptions {
+///     xml: XmlOptions {
+///         select: Some("//item/description".to_string()),
+///         exclude: vec!["link".to_string()],
+///         ..XmlOptions::default()
+///     },
+///     ..ParseOptions::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XmlOptions {
+    /// XPath-like path selecting the extracted elements, e.g. `//item/description`,
+    /// `/feed/entry[2]/title`, `//item[@lang='en']` or `//link/@href`
+    pub select: Option<String>,
+    /// Local names of the elements whose text is extracted, all when empty
+    pub include: Vec<String>,
+    /// Local names of the elements skipped along with their content
+    pub exclude: Vec<String>,
+    /// Whether attribute values are extracted along with the text
+    pub attributes: bool,
+}
+
+/// Selection of the outputs of code cells extracted from Jupyter notebooks.
+///
+/// # Examples
+///
+/// ```
+/// use parser::{NotebookOptions, ParseOptions};
+///
+/// // Extract the printed results and the text of plotted figures
+/// let options = ParseOptions {
+///     notebook: NotebookOptions {
+///         outputs: true,
+///         ocr_images: true,
+///     },
+///     ..ParseOptions::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotebookOptions {
+    /// Whether text outputs (printed text, results and errors) are extracted
+    pub outputs: bool,
+    /// Whether text is recognised with OCR in the PNG and JPEG outputs
+    pub ocr_images: bool,
+}
+
+/// Limits applied while unpacking archives, guarding against archive bombs.
+///
+/// Exceeding the member count or total size fails the whole archive, while archives
+/// nested deeper than allowed are reported as members that failed to parse.
+///
+/// # Examples
+///
+/// ```
+/// use parser::{ArchiveOptions, ParseOptions};
+///
+/// // Only unpack the uploaded archive, not the archives it contains
+/// let options = ParseOptions {
+///     archive: ArchiveOptions {
+///         max_depth: 1,
+///         ..ArchiveOptions::default()
+///     },
+///     ..ParseOptions::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveOptions {
+    /// Maximum nesting level of archives, the parsed archive being level 1
+    pub max_depth: usize,
+    /// Maximum number of members, nested archives and their members included
+    pub max_members: usize,
+    /// Maximum number of bytes decompressed, across all nesting levels
+    pub max_size: u64,
+}
+
+impl Default for ArchiveOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 5,
+            max_members: 10_000,
+            max_size: 512 * 1024 * 1024,
+        }
+    }
+}
+
+/// Where a document is nested in other documents, such as an archive member or an
+/// email attachment.
+///
+/// Callers leave it to its default. Nested documents carry it over, so that the
+/// archives found in them share the limits of the archive they come from and
+/// documents cannot be nested indefinitely.
+#[derive(Debug, Clone, Default)]
+pub struct Nesting {
+    /// Number of documents the document is nested in
+    pub(crate) depth: usize,
+    /// Members and bytes read from the enclosing archives, if any
+    pub(crate) archive: Option<Arc<ArchiveUsage>>,
+}
+
+impl Nesting {
+    /// Returns the nesting of a document found in this one.
+    pub(crate) fn nested(&self) -> Self {
+        Self {
+            depth: self.depth + 1,
+            archive: self.archive.clone(),
+        }
+    }
+}
+
+impl PartialEq for Nesting {
+    fn eq(&self, other: &Self) -> bool {
+        self.depth == other.depth
+            && match (&self.archive, &other.archive) {
+                (Some(usage), Some(other)) => Arc::ptr_eq(usage, other),
+                (usage, other) => usage.is_none() && other.is_none(),
+            }
+    }
+}
+
+impl Eq for Nesting {}
+
+/// Members and bytes read from an archive and from the archives nested in it,
+/// counted against the limits of [`ArchiveOptions`].
+#[derive(Debug, Default)]
+pub(crate) struct ArchiveUsage {
+    /// Number of members read
+    pub(crate) members: AtomicUsize,
+    /// Number of bytes decompressed
+    pub(crate) size: AtomicU64,
+}
+
+/// Rendering of tabular content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableFormat {
+    /// Comma-separated values with standard quoting
+    #[default]
+    Csv,
+    /// Markdown tables
+    Markdown,
+    /// JSON array of records keyed by the column headers
+    Json,
+}
+
+/// Rendering of structured data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StructuredMode {
+    /// Validated and pretty-printed in its original format
+    #[default]
+    Pretty,
+    /// One `path.to.key: value` line per value
+    Flatten,
+    /// Only the string values, one per line
+    Strings,
+}
+
+/// Extraction of source code files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CodeMode {
+    /// The whole source, unchanged
+    #[default]
+    Full,
+    /// Only the comments and docstrings, one paragraph each
+    Comments,
+    /// The source without its comments and docstrings
+    Strip,
+}
+
+/// Rendering of the formulas of LaTeX documents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MathMode {
+    /// The LaTeX source of the formulas, without their delimiters
+    #[default]
+    Raw,
+    /// A `[math]` placeholder
+    Placeholder,
+}
+
+/// Syntax of the extracted text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Plain text
+    #[default]
+    Text,
+    /// Markdown, with headings, emphasis, lists and tables
+    Markdown,
+}
@@ -4,30 +4,84 @@
 //! providing a unified interface for different file formats like PDF, CSV, etc.
 //! Each specific parser is implemented in its own submodule.
 
+mod archive;
+mod code;
+pub(crate) mod csv;
 mod docx;
-mod image;
-mod pdf;
-mod pptx;
+mod email;
+mod epub;
+pub(crate) mod html;
+pub(crate) mod image;
+mod iwork;
+mod markup;
+mod mbox;
+mod notebook;
+mod odf;
+mod ole;
+pub(crate) mod package;
+pub(crate) mod pdf;
+mod pim;
+pub(crate) mod pptx;
+mod rtf;
+mod structured;
+mod subtitle;
+mod svg;
+pub(crate) mod table;
 mod text;
 mod xlsx;
+mod xml;
+mod xps;
+
+pub use self::{
+    archive::{ArchiveMember, parse_archive},
+    mbox::{MailboxMessage, MboxMessages, parse_mbox},
+    pim::{CalendarEvent, Contact, parse_contacts, parse_events},
+    subtitle::{Cue, parse_subtitles},
+};
 
 use self::{
-    docx::parse_docx, image::parse_image, pdf::parse_pdf, pptx::parse_pptx, text::parse_text,
+    archive::{is_archive, parse_archive_document},
+    docx::parse_docx,
+    email::{is_eml, is_msg, parse_eml, parse_msg},
+    epub::parse_epub,
+    html::parse_html,
+    image::parse_image,
+    iwork::{is_iwork, iwork_type, parse_iwork},
+    mbox::{is_mbox, parse_mailbox},
+    odf::{parse_odp, parse_odt},
+    ole::parse_ole,
+    pdf::parse_pdf,
+    pptx::parse_pptx,
+    rtf::{is_rtf, parse_rtf},
+    svg::{is_svg, parse_svg},
+    text::parse_text,
     xlsx::parse_xlsx,
+    xps::{parse_xps, xps_type},
 };
 
 use super::{
-    constants::{APPLICATION_DOCX, APPLICATION_PDF, APPLICATION_PPTX, APPLICATION_XLSX},
+    constants::{
+        APPLICATION_DOC, APPLICATION_DOCX, APPLICATION_EPUB, APPLICATION_MBOX, APPLICATION_MSG,
+        APPLICATION_ODP, APPLICATION_ODT, APPLICATION_OLE_STORAGE, APPLICATION_OXPS,
+        APPLICATION_PDF, APPLICATION_PPT, APPLICATION_PPTX, APPLICATION_RTF, APPLICATION_XLSX,
+        APPLICATION_XPS, APPLICATION_ZIP, MESSAGE_RFC822,
+    },
+    document::ParsedDocument,
+    encoding::detect_encoding,
     errors::ParserError,
+    options::ParseOptions,
 };
 use infer::Infer;
-use mime::{IMAGE, Mime, TEXT, TEXT_PLAIN};
-use std::str;
+use mime::{IMAGE, IMAGE_SVG, Mime, TEXT, TEXT_HTML, TEXT_PLAIN};
 use std::sync::LazyLock;
 
 // Create a static infer instance to avoid recreating it on every call
 static INFER: LazyLock<Infer> = LazyLock::new(Infer::new);
 
+/// Deepest nesting of documents in documents, such as messages attached to
+/// messages, bounding the recursion
+const MAX_NESTING_DEPTH: usize = 16;
+
 /// Parses the given data into plain text.
 ///
 /// This function is the main entry point for the parser library. It automatically
@@ -78,13 +132,91 @@ static INFER: LazyLock<Infer> = LazyLock::new(Infer::new);
 /// Returns [`ParserError::InvalidFormat`] if the file type is unsupported or unrecognized.
 /// May return other [`ParserError`] variants if an error occurs during parsing.
 pub fn parse(data: &[u8]) -> Result<String, ParserError> {
-    match determine_mime_type(data) {
-        Some(mime) if mime == APPLICATION_PDF => parse_pdf(data),
-        Some(mime) if mime == APPLICATION_DOCX => parse_docx(data),
-        Some(mime) if mime == APPLICATION_XLSX => parse_xlsx(data),
-        Some(mime) if mime == APPLICATION_PPTX => parse_pptx(data),
-        Some(mime) if mime.type_() == TEXT => parse_text(data),
-        Some(mime) if mime.type_() == IMAGE => parse_image(data),
+    parse_with_options(data, &ParseOptions::default()).map(|document| document.text)
+}
+
+/// Parses the given data with the given options.
+///
+/// This function behaves like [`parse`], but lets the caller tune the parsing
+/// through [`ParseOptions`] and returns a [`ParsedDocument`] holding the text
+/// along with the metadata gathered while parsing, such as the detected encoding
+/// of text files.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the file data to be parsed
+/// * `options` - The options to parse the data with
+///
+/// # Returns
+///
+/// * `Ok(ParsedDocument)` - The extracted text content and metadata of the file
+/// * `Err(ParserError)` - If the file type is unsupported, unrecognized, or an error occurs during parsing
+///
+/// # Examples
+///
+/// ```
+/// use parser::{ParseOptions, parse_with_options};
+///
+/// // "François" encoded in Windows-1252
+/// let data = b"Fran\xE7ois";
+/// let options = ParseOptions {
+///     encoding: Some("windows-1252".to_string()),
+///     ..ParseOptions::default()
+/// };
+///
+/// let document = parse_with_options(data, &options).expect("Failed to parse text data");
+///
+/// assert_eq!(document.text, "François");
+/// assert_eq!(document.metadata["encoding"], "windows-1252");
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ParserError::InvalidFormat`] if the file type is unsupported or unrecognized.
+/// May return other [`ParserError`] variants if an error occurs during parsing.
+pub fn parse_with_options(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParserError> {
+    if options.nesting.depth > MAX_NESTING_DEPTH {
+        return Err(ParserError::InvalidFormat(format!(
+            "Document nested deeper than {MAX_NESTING_DEPTH} levels"
+        )));
+    }
+
+    match determine_mime_type_with_options(data, options) {
+        Some(mime) if mime == APPLICATION_PDF => parse_pdf(data, options).map(ParsedDocument::from),
+        Some(mime) if mime == APPLICATION_DOCX => {
+            parse_docx(data, options).map(ParsedDocument::from)
+        }
+        Some(mime) if mime == APPLICATION_XLSX => parse_xlsx(data, options),
+        Some(mime) if mime == APPLICATION_PPTX => {
+            parse_pptx(data, options).map(ParsedDocument::from)
+        }
+        Some(mime) if mime == APPLICATION_ODT => parse_odt(data).map(ParsedDocument::from),
+        Some(mime) if mime == APPLICATION_ODP => parse_odp(data).map(ParsedDocument::from),
+        Some(mime) if mime == APPLICATION_EPUB => parse_epub(data, options),
+        Some(mime) if mime == APPLICATION_MSG => parse_msg(data, options),
+        Some(mime)
+            if mime == APPLICATION_DOC
+                || mime == APPLICATION_PPT
+                || mime == APPLICATION_OLE_STORAGE =>
+        {
+            parse_ole(data).map(ParsedDocument::from)
+        }
+        Some(mime) if mime == APPLICATION_RTF => parse_rtf(data),
+        Some(mime) if mime == MESSAGE_RFC822 => parse_eml(data, options),
+        Some(mime) if mime == APPLICATION_MBOX => Ok(parse_mailbox(data, options)),
+        Some(mime) if mime == APPLICATION_XPS || mime == APPLICATION_OXPS => {
+            parse_xps(data).map(ParsedDocument::from)
+        }
+        Some(mime) if is_iwork(&mime) => parse_iwork(data),
+        Some(mime) if is_archive(&mime) => parse_archive_document(data, options),
+        Some(mime) if mime == TEXT_HTML => parse_html(data, options),
+        Some(mime) if mime.type_() == TEXT => parse_text(data, options),
+        // SVG images are XML, their text can't be recognised from their source
+        Some(mime) if mime == IMAGE_SVG => parse_svg(data, options),
+        Some(mime) if mime.type_() == IMAGE => parse_image(data).map(ParsedDocument::from),
         Some(mime) => Err(ParserError::InvalidFormat(format!(
             "Unsupported file type: {mime}"
         ))),
@@ -97,7 +229,7 @@ pub fn parse(data: &[u8]) -> Result<String, ParserError> {
 /// Determines the MIME type of data from its binary content.
 ///
 /// This function uses file signatures (magic bytes) to detect the type of the data
-/// and as a fallback, checks if the data is valid UTF-8 text.
+/// and as a fallback, checks if the data looks like text in any supported encoding.
 ///
 /// # Arguments
 ///
@@ -110,29 +242,77 @@ pub fn parse(data: &[u8]) -> Result<String, ParserError> {
 ///
 /// # Implementation Details
 ///
-/// - First tries to identify the file type based on its binary signature
-/// - As a fallback, checks if the content is valid UTF-8 text
+/// - Recognises RTF documents, which are text, by their `{\rtf` signature
+/// - Recognises SVG images, which are XML, by their `svg` root element
+/// - Recognises email messages and mailboxes, which are text, by their leading
+///   header fields
+/// - Tells Outlook messages apart from other OLE2 compound files, and XPS and
+///   iWork documents apart from other ZIP archives
+/// - Then tries to identify the file type based on its binary signature
+/// - As a fallback, checks if the content is text (UTF-8, UTF-16 or a legacy encoding)
 /// - Uses a static infer instance to improve performance
-fn determine_mime_type(data: &[u8]) -> Option<Mime> {
+pub(crate) fn determine_mime_type(data: &[u8]) -> Option<Mime> {
+    // RTF is text, recognised by its signature even after a BOM or whitespace
+    if is_rtf(data) {
+        return APPLICATION_RTF.parse().ok();
+    }
+
+    // SVG images are XML text, recognised by their root element
+    if is_svg(data) {
+        return Some(IMAGE_SVG);
+    }
+
+    // Email messages are text, recognised by their leading header fields
+    if is_eml(data) {
+        return MESSAGE_RFC822.parse().ok();
+    }
+    if is_mbox(data) {
+        return APPLICATION_MBOX.parse().ok();
+    }
+
     // Use the static infer instance
     // Try to detect using file signatures
     if let Some(kind) = INFER.get(data)
-        && let Ok(mime) = kind.mime_type().parse()
+        && let Ok(mime) = kind.mime_type().parse::<Mime>()
     {
+        // Outlook messages are compound files without a class identifier known to infer
+        if mime == APPLICATION_OLE_STORAGE && is_msg(data) {
+            return APPLICATION_MSG.parse().ok();
+        }
+        // XPS and iWork documents are ZIP archives without a signature of their own
+        if mime == APPLICATION_ZIP
+            && let Some(package) = xps_type(data).or_else(|| iwork_type(data))
+        {
+            return package.parse().ok();
+        }
         return Some(mime);
     }
 
-    // Finally, check if it could be plain text (if it's UTF-8 decodable)
-    if str::from_utf8(data).is_ok() {
+    // Finally, check if it could be plain text (if an encoding can be detected)
+    if detect_encoding(data).is_some() {
         return Some(TEXT_PLAIN);
     }
 
     None
 }
 
+/// Determines the MIME type of data parsed with the given options, data of unknown
+/// type being text when an encoding is set, since the caller knows it is text.
+pub(crate) fn determine_mime_type_with_options(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Option<Mime> {
+    determine_mime_type(data).or_else(|| options.encoding.is_some().then_some(TEXT_PLAIN))
+}
+
 #[cfg(test)]
 mod tests {
+    use super::super::constants::{
+        APPLICATION_7Z, APPLICATION_GZIP, APPLICATION_KEYNOTE, APPLICATION_NUMBERS,
+        APPLICATION_PAGES,
+    };
     use super::*;
+    use mime::TEXT_XML;
 
     #[test]
     fn parse_success() {
@@ -169,15 +349,39 @@ mod tests {
         assert_mime_type_from_data("test_docx_1.docx", APPLICATION_DOCX, false);
         assert_mime_type_from_data("test_xlsx_1.xlsx", APPLICATION_XLSX, false);
         assert_mime_type_from_data("test_pptx_1.pptx", APPLICATION_PPTX, false);
+        assert_mime_type_from_data("test_odt_1.odt", APPLICATION_ODT, false);
+        assert_mime_type_from_data("test_odp_1.odp", APPLICATION_ODP, false);
+        assert_mime_type_from_data("test_epub_1.epub", APPLICATION_EPUB, false);
+        assert_mime_type_from_data("test_eml_1.eml", MESSAGE_RFC822, false);
+        assert_mime_type_from_data("test_msg_1.msg", APPLICATION_MSG, false);
+        assert_mime_type_from_data("test_mbox_1.mbox", APPLICATION_MBOX, false);
+        assert_mime_type_from_data("test_doc_1.doc", APPLICATION_DOC, false);
+        assert_mime_type_from_data("test_ppt_1.ppt", APPLICATION_PPT, false);
+        assert_mime_type_from_data("test_xps_1.xps", APPLICATION_XPS, false);
+        assert_mime_type_from_data("test_oxps_1.oxps", APPLICATION_OXPS, false);
+        assert_mime_type_from_data("test_pages_1.pages", APPLICATION_PAGES, false);
+        assert_mime_type_from_data("test_key_1.key", APPLICATION_KEYNOTE, false);
+        assert_mime_type_from_data("test_numbers_1.numbers", APPLICATION_NUMBERS, false);
+
+        // Archives
+        assert_mime_type_from_data("test_zip_1.zip", APPLICATION_ZIP, false);
+        assert_mime_type_from_data("test_tar_1.tar.gz", APPLICATION_GZIP, false);
+        assert_mime_type_from_data("test_7z_1.7z", APPLICATION_7Z, false);
 
         // Text files
         assert_mime_type_from_data("test_txt_1.txt", TEXT.into(), true);
         assert_mime_type_from_data("test_csv_1.csv", TEXT.into(), true);
         assert_mime_type_from_data("test_json_1.json", TEXT.into(), true);
+        assert_mime_type_from_data("test_html_1.html", TEXT_HTML.as_ref(), false);
+        assert_mime_type_from_data("test_xml_1.xml", TEXT_XML.as_ref(), false);
+        assert_mime_type_from_data("test_rtf_1.rtf", APPLICATION_RTF, false);
+        assert_mime_type_from_data("test_txt_3.txt", TEXT.into(), true);
+        assert_mime_type_from_data("test_txt_4.txt", TEXT.into(), true);
 
         // Images
         assert_mime_type_from_data("test_png_1.png", IMAGE.into(), true);
         assert_mime_type_from_data("test_jpg_1.jpg", IMAGE.into(), true);
         assert_mime_type_from_data("test_webp_1.webp", IMAGE.into(), true);
+        assert_mime_type_from_data("test_svg_1.svg", IMAGE_SVG.as_ref(), false);
     }
 }
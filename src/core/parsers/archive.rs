@@ -0,0 +1,642 @@
+//! Archive parser module.
+//!
+//! This module provides functionality for unpacking archives (ZIP, TAR, 7z) and
+//! compressed files (gzip, bzip2, xz), every member being parsed like any other
+//! document. Archives found among the members are unpacked in turn, within the limits
+//! of [`ArchiveOptions`](super::super::options::ArchiveOptions) guarding against
+//! archive bombs.
+
+use super::super::{
+    constants::{
+        APPLICATION_7Z, APPLICATION_BZIP2, APPLICATION_GZIP, APPLICATION_TAR, APPLICATION_XZ,
+        APPLICATION_ZIP,
+    },
+    document::{METADATA_FORMAT, ParsedDocument},
+    errors::ParserError,
+    options::{ArchiveOptions, ArchiveUsage, Nesting, ParseOptions},
+};
+use super::{determine_mime_type, parse_with_options};
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+use lzma_rust2::XzReader;
+use mime::Mime;
+use sevenz_rust2::{ArchiveReader, Password};
+use std::io::{Cursor, Read};
+use std::sync::{Arc, atomic::Ordering};
+use zip::ZipArchive;
+
+/// Extensions of compressed files, with the extension of the decompressed file
+const COMPRESSED_EXTENSIONS: [(&str, &str); 7] = [
+    (".tgz", ".tar"),
+    (".tbz2", ".tar"),
+    (".tbz", ".tar"),
+    (".txz", ".tar"),
+    (".gz", ""),
+    (".bz2", ""),
+    (".xz", ""),
+];
+
+/// A file read from an archive.
+#[derive(Debug)]
+pub struct ArchiveMember {
+    /// Path of the file in the archive, the paths of the archives it is nested in
+    /// coming first (e.g. `docs/nested.zip/report.pdf`)
+    pub path: String,
+    /// Text and metadata of the file, or the error that occurred while parsing it
+    pub document: Result<ParsedDocument, ParserError>,
+}
+
+/// Kinds of archives and compressed files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Zip,
+    Tar,
+    SevenZ,
+    Gzip,
+    Bzip2,
+    Xz,
+}
+
+impl Kind {
+    /// Returns the kind of archive of a MIME type, if it is one.
+    fn from_mime(mime: &Mime) -> Option<Self> {
+        match mime.essence_str() {
+            APPLICATION_ZIP => Some(Self::Zip),
+            APPLICATION_TAR => Some(Self::Tar),
+            APPLICATION_7Z => Some(Self::SevenZ),
+            APPLICATION_GZIP => Some(Self::Gzip),
+            APPLICATION_BZIP2 => Some(Self::Bzip2),
+            APPLICATION_XZ => Some(Self::Xz),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of archive of data, if it is one.
+    fn detect(data: &[u8]) -> Option<Self> {
+        determine_mime_type(data).and_then(|mime| Self::from_mime(&mime))
+    }
+
+    /// Returns the name of the format, as reported in the metadata.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Zip => "zip",
+            Self::Tar => "tar",
+            Self::SevenZ => "7z",
+            Self::Gzip => "gzip",
+            Self::Bzip2 => "bzip2",
+            Self::Xz => "xz",
+        }
+    }
+}
+
+/// Unpacks archives recursively, keeping track of the limits.
+struct Extractor<'a> {
+    /// Options the members are parsed with
+    options: &'a ParseOptions,
+    /// Members read so far
+    members: Vec<ArchiveMember>,
+    /// Members and bytes read so far, shared with the archives nested in members
+    usage: Arc<ArchiveUsage>,
+}
+
+impl Extractor<'_> {
+    /// Unpacks an archive, listing its members under a path prefix.
+    fn extract(
+        &mut self,
+        data: &[u8],
+        prefix: &str,
+        kind: Kind,
+        depth: usize,
+    ) -> Result<(), ParserError> {
+        match kind {
+            Kind::Zip => self.extract_zip(data, prefix, depth),
+            Kind::Tar => self.extract_tar(data, prefix, depth),
+            Kind::SevenZ => self.extract_7z(data, prefix, depth),
+            Kind::Gzip => {
+                let mut decoder = MultiGzDecoder::new(data);
+                let content = self.read(&mut decoder)?;
+                let original = decoder
+                    .header()
+                    .and_then(|header| header.filename())
+                    .map(String::from_utf8_lossy);
+                self.decompressed(&content, prefix, original.as_deref(), depth)
+            }
+            Kind::Bzip2 => {
+                let content = self.read(MultiBzDecoder::new(data))?;
+                self.decompressed(&content, prefix, None, depth)
+            }
+            Kind::Xz => {
+                let content = self.read(XzReader::new(data, true))?;
+                self.decompressed(&content, prefix, None, depth)
+            }
+        }
+    }
+
+    /// Unpacks the members of a ZIP archive.
+    fn extract_zip(&mut self, data: &[u8], prefix: &str, depth: usize) -> Result<(), ParserError> {
+        let mut archive = ZipArchive::new(Cursor::new(data))?;
+        for index in 0..archive.len() {
+            let path = join(prefix, archive.name_for_index(index).unwrap_or_default());
+            let content = match archive.by_index(index) {
+                Ok(file) if !file.is_file() => continue,
+                Ok(mut file) => self.read(&mut file)?,
+                // Encrypted or unsupported members don't prevent reading the others
+                Err(error) => {
+                    self.push(path, Err(error.into()))?;
+                    continue;
+                }
+            };
+            self.member(path, &content, depth)?;
+        }
+        Ok(())
+    }
+
+    /// Unpacks the regular files of a TAR archive.
+    fn extract_tar(&mut self, data: &[u8], prefix: &str, depth: usize) -> Result<(), ParserError> {
+        let mut archive = tar::Archive::new(data);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = join(prefix, &entry.path()?.to_string_lossy());
+            // Members are slices of the archive, already counted when decompressed
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            self.member(path, &content, depth)?;
+        }
+        Ok(())
+    }
+
+    /// Unpacks the files of a 7z archive.
+    fn extract_7z(&mut self, data: &[u8], prefix: &str, depth: usize) -> Result<(), ParserError> {
+        let mut reader = ArchiveReader::new(Cursor::new(data), Password::empty())?;
+
+        // Entries are decoded in a callback, which can't parse them in turn
+        let mut files = Vec::new();
+        let mut failure = None;
+        reader.for_each_entries(|entry, content| {
+            if entry.is_directory() {
+                return Ok(true);
+            }
+            match self.read(content) {
+                Ok(content) => {
+                    files.push((join(prefix, entry.name()), content));
+                    Ok(true)
+                }
+                Err(error) => {
+                    failure = Some(error);
+                    Ok(false)
+                }
+            }
+        })?;
+        if let Some(error) = failure {
+            return Err(error);
+        }
+
+        for (path, content) in files {
+            self.member(path, &content, depth)?;
+        }
+        Ok(())
+    }
+
+    /// Handles the content of a compressed file.
+    ///
+    /// A compressed archive (e.g. `.tar.gz`) is unpacked at the same nesting level,
+    /// while another file, compressed files included, is named after the compressed
+    /// file without its extension and handled as a member.
+    fn decompressed(
+        &mut self,
+        content: &[u8],
+        prefix: &str,
+        original: Option<&str>,
+        depth: usize,
+    ) -> Result<(), ParserError> {
+        // Compressed files nested in compressed files are nesting levels, so that a
+        // chain of them (or a file decompressing to itself) ends at the depth limit
+        if let Some(kind @ (Kind::Zip | Kind::Tar | Kind::SevenZ)) = Kind::detect(content) {
+            return self.extract(content, prefix, kind, depth);
+        }
+
+        let name = if prefix.is_empty() {
+            self.options.file_name.as_deref().map(base_name)
+        } else {
+            Some(prefix)
+        };
+        let path = name
+            .and_then(strip_extension)
+            .unwrap_or_else(|| join(prefix, original.map_or("data", base_name)));
+        self.member(path, content, depth)
+    }
+
+    /// Handles a member of an archive, unpacked if it is an archive and parsed
+    /// otherwise.
+    fn member(&mut self, path: String, content: &[u8], depth: usize) -> Result<(), ParserError> {
+        match Kind::detect(content) {
+            Some(_) if depth >= self.options.archive.max_depth => {
+                let error = ParserError::InvalidFormat(format!(
+                    "Archive nested deeper than {} levels",
+                    self.options.archive.max_depth
+                ));
+                self.push(path, Err(error))
+            }
+            Some(kind) => {
+                self.count()?;
+                self.extract(content, &path, kind, depth + 1)
+            }
+            None => {
+                let document = parse_with_options(content, &self.member_options(&path, depth));
+                self.push(path, document)
+            }
+        }
+    }
+
+    /// Adds a member to the results.
+    fn push(
+        &mut self,
+        path: String,
+        document: Result<ParsedDocument, ParserError>,
+    ) -> Result<(), ParserError> {
+        self.count()?;
+        self.members.push(ArchiveMember { path, document });
+        Ok(())
+    }
+
+    /// Returns the options a member is parsed with, the archives found while parsing
+    /// it (e.g. attached to an email) being unpacked within the remaining nesting
+    /// levels and sharing the member count and size of this archive.
+    fn member_options(&self, path: &str, depth: usize) -> ParseOptions {
+        ParseOptions {
+            file_name: Some(base_name(path).to_string()),
+            archive: ArchiveOptions {
+                max_depth: self.options.archive.max_depth.saturating_sub(depth),
+                ..self.options.archive.clone()
+            },
+            nesting: Nesting {
+                depth: self.options.nesting.depth + 1,
+                archive: Some(Arc::clone(&self.usage)),
+            },
+            ..self.options.clone()
+        }
+    }
+
+    /// Counts a member, failing once there are too many.
+    fn count(&mut self) -> Result<(), ParserError> {
+        let count = self.usage.members.fetch_add(1, Ordering::Relaxed) + 1;
+        if count > self.options.archive.max_members {
+            return Err(ParserError::InvalidFormat(format!(
+                "Archive has more than {} members",
+                self.options.archive.max_members
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reads decompressed data, failing once too many bytes were decompressed.
+    fn read(&mut self, reader: impl Read) -> Result<Vec<u8>, ParserError> {
+        let max_size = self.options.archive.max_size;
+        let mut content = Vec::new();
+        // One byte past the limit tells whether the limit is exceeded
+        let size = self.usage.size.load(Ordering::Relaxed);
+        reader
+            .take(max_size.saturating_sub(size).saturating_add(1))
+            .read_to_end(&mut content)?;
+
+        let read = content.len() as u64;
+        let size = self.usage.size.fetch_add(read, Ordering::Relaxed) + read;
+        if size > max_size {
+            return Err(ParserError::InvalidFormat(format!(
+                "Archive exceeds {max_size} bytes once decompressed"
+            )));
+        }
+        Ok(content)
+    }
+}
+
+/// Checks whether a MIME type is the type of an archive or compressed file.
+pub(crate) fn is_archive(mime: &Mime) -> bool {
+    Kind::from_mime(mime).is_some()
+}
+
+/// Unpacks an archive and parses its members.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the archive (ZIP, TAR, 7z) or compressed file
+///   (gzip, bzip2, xz, including `.tar.gz`, `.tar.bz2` and `.tar.xz`)
+/// * `options` - The options the members are parsed with, including the limits of
+///   [`ArchiveOptions`](crate::ArchiveOptions)
+///
+/// # Returns
+///
+/// * `Ok(Vec<ArchiveMember>)` - The members of the archive in order, each with its
+///   path and the result of its parsing
+/// * `Err(ParserError)` - If the data is not an archive, can't be unpacked, or
+///   exceeds the member count or size limits
+///
+/// # Implementation Notes
+///
+/// * Archives among the members are unpacked in turn, their members' paths starting
+///   with the archive's path
+/// * Archives nested deeper than allowed are members that failed to parse
+/// * Compressed files don't count as a nesting level: the archive of a `.tar.gz` is
+///   unpacked directly, while other files are named after the compressed file
+///   without its extension, a compressed file in a compressed file being a nested
+///   archive
+/// * Archives found while parsing a member, e.g. attached to an email, are unpacked
+///   within the remaining nesting levels, their members and bytes counting against
+///   the limits of the archive
+/// * Directories, links and other special entries are skipped
+///
+/// # Examples
+///
+/// ```no_run
+/// use parser::{ParseOptions, parse_archive};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let data = std::fs::read("documents.zip")?;
+///     for member in parse_archive(&data, &ParseOptions::default())? {
+///         match member.document {
+///             Ok(document) => println!("{}: {}", member.path, document.text),
+///             Err(error) => println!("{}: {error}", member.path),
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ParserError::InvalidFormat`] if the data is not an archive or a limit is
+/// exceeded. May return other [`ParserError`] variants if the archive is corrupted.
+pub fn parse_archive(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<Vec<ArchiveMember>, ParserError> {
+    let kind = Kind::detect(data)
+        .ok_or_else(|| ParserError::InvalidFormat("Not an archive".to_string()))?;
+    // No level is left for an archive found in a member nested at the depth limit
+    if options.archive.max_depth == 0 {
+        return Err(ParserError::InvalidFormat(
+            "Archive nested deeper than allowed".to_string(),
+        ));
+    }
+
+    let mut extractor = Extractor {
+        options,
+        members: Vec::new(),
+        usage: options.nesting.archive.clone().unwrap_or_default(),
+    };
+    extractor.extract(data, "", kind, 1)?;
+    Ok(extractor.members)
+}
+
+/// Parses an archive as a single document.
+///
+/// # Implementation Notes
+///
+/// * Each member starts with a `--- File: <path> ---` separator
+/// * Members that can't be parsed are listed without text
+pub(crate) fn parse_archive_document(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParserError> {
+    let format = Kind::detect(data).map_or("archive", Kind::name);
+    let sections: Vec<String> = parse_archive(data, options)?
+        .into_iter()
+        .map(|member| {
+            let text = member
+                .document
+                .map(|document| document.text)
+                .unwrap_or_default();
+            format!("--- File: {} ---\n{text}", member.path)
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+
+    Ok(ParsedDocument::from(sections.join("\n\n")).with_metadata(METADATA_FORMAT, format))
+}
+
+/// Joins a member path to the path of its archive.
+fn join(prefix: &str, path: &str) -> String {
+    let path = path.trim_start_matches("./").trim_start_matches('/');
+    if prefix.is_empty() {
+        path.to_string()
+    } else {
+        format!("{prefix}/{path}")
+    }
+}
+
+/// Returns the last component of a path.
+fn base_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+/// Returns the name of a compressed file without its compression extension.
+fn strip_extension(name: &str) -> Option<String> {
+    let lowercase = name.to_lowercase();
+    COMPRESSED_EXTENSIONS
+        .iter()
+        .find(|(extension, _)| lowercase.ends_with(extension) && name.len() > extension.len())
+        .map(|(extension, replacement)| {
+            format!("{}{replacement}", &name[..name.len() - extension.len()])
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{Engine, prelude::BASE64_STANDARD};
+    use flate2::{Compression, write::GzEncoder};
+    use std::io::Write;
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    fn texts(members: &[ArchiveMember]) -> Vec<(&str, &str)> {
+        members
+            .iter()
+            .map(|member| {
+                let text = member
+                    .document
+                    .as_ref()
+                    .map_or("", |document| document.text.trim());
+                (member.path.as_str(), text)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_archive_zip() {
+        let data = read_test_file("test_zip_1.zip");
+        let members = parse_archive(&data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            texts(&members),
+            [
+                (
+                    "readme.txt",
+                    "Hello, this is a test zip for the parsing API."
+                ),
+                (
+                    "docs/notes.md",
+                    "Notes\n\nArchives are unpacked recursively."
+                ),
+                (
+                    "docs/nested.tar.gz/inner/hello.txt",
+                    "Hello from a nested archive."
+                ),
+                ("docs/report.txt", "A compressed report."),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_archive_tar_gz() {
+        let data = read_test_file("test_tar_1.tar.gz");
+        let members = parse_archive(&data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            texts(&members),
+            [
+                (
+                    "report.txt",
+                    "Hello, this is a test tar for the parsing API."
+                ),
+                ("data.csv", "name,value\nalpha,1\nbeta,2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_archive_7z() {
+        let data = read_test_file("test_7z_1.7z");
+        let members = parse_archive(&data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            texts(&members),
+            [("hello.txt", "Hello, this is a test 7z for the parsing API.")]
+        );
+    }
+
+    #[test]
+    fn parse_archive_limits() {
+        let data = read_test_file("test_zip_1.zip");
+        let limited = |archive| ParseOptions {
+            archive,
+            ..ParseOptions::default()
+        };
+
+        let members = parse_archive(
+            &data,
+            &limited(ArchiveOptions {
+                max_depth: 1,
+                ..ArchiveOptions::default()
+            }),
+        )
+        .unwrap();
+        assert_eq!(members.len(), 4);
+        assert!(members[2].document.is_err());
+        assert!(members[3].document.is_err());
+
+        let result = parse_archive(
+            &data,
+            &limited(ArchiveOptions {
+                max_members: 3,
+                ..ArchiveOptions::default()
+            }),
+        );
+        assert!(matches!(result, Err(ParserError::InvalidFormat(_))));
+
+        let result = parse_archive(
+            &data,
+            &limited(ArchiveOptions {
+                max_size: 64,
+                ..ArchiveOptions::default()
+            }),
+        );
+        assert!(matches!(result, Err(ParserError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn parse_archive_nested_gzip() {
+        let mut data = b"Hello from the innermost layer.".to_vec();
+        for _ in 0..64 {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+            encoder.write_all(&data).unwrap();
+            data = encoder.finish().unwrap();
+        }
+        let members = parse_archive(&data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(members.len(), 1);
+        assert!(matches!(
+            members[0].document,
+            Err(ParserError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn parse_archive_shared_limits() {
+        fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+            let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+            for (name, content) in files {
+                writer
+                    .start_file(*name, SimpleFileOptions::default())
+                    .unwrap();
+                writer.write_all(content).unwrap();
+            }
+            writer.finish().unwrap().into_inner()
+        }
+
+        // Messages attaching an archive that decompresses to 10 KB
+        let attachment = BASE64_STANDARD.encode(zip(&[("data.txt", &[b'a'; 10_000])]));
+        let message = format!(
+            "From: jane@example.com\r\nSubject: Data\r\nContent-Type: multipart/mixed; boundary=\"b\"\r\n\r\n--b\r\nContent-Type: application/zip; name=\"data.zip\"\r\nContent-Transfer-Encoding: base64\r\n\r\n{attachment}\r\n--b--\r\n"
+        );
+        let data = zip(&[
+            ("1.eml", message.as_bytes()),
+            ("2.eml", message.as_bytes()),
+            ("3.eml", message.as_bytes()),
+        ]);
+        let options = ParseOptions {
+            archive: ArchiveOptions {
+                max_size: 25_000,
+                ..ArchiveOptions::default()
+            },
+            ..ParseOptions::default()
+        };
+        let members = parse_archive(&data, &options).unwrap();
+
+        // The third attachment exceeds the size left by the first two
+        let unpacked = texts(&members)
+            .iter()
+            .map(|(_, text)| text.contains("--- File: data.txt ---"))
+            .collect::<Vec<_>>();
+        assert_eq!(unpacked, [true, true, false]);
+    }
+
+    #[test]
+    fn parse_archive_document_success() {
+        let data = read_test_file("test_tar_1.tar.gz");
+        let result = parse_archive_document(&data, &ParseOptions::default()).unwrap();
+
+        assert!(result.text.starts_with("--- File: report.txt ---\nHello"));
+        assert_eq!(result.metadata[METADATA_FORMAT], "gzip");
+    }
+
+    #[test]
+    fn strip_extension_success() {
+        assert_eq!(strip_extension("report.txt.gz").unwrap(), "report.txt");
+        assert_eq!(strip_extension("backup.TGZ").unwrap(), "backup.tar");
+        assert_eq!(strip_extension(".gz"), None);
+        assert_eq!(strip_extension("notes.txt"), None);
+    }
+}
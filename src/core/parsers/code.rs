@@ -0,0 +1,718 @@
+//! Source code parser module.
+//!
+//! This module provides functionality for recognising the programming language of
+//! source files, from their extension or their shebang line, and for telling their
+//! comments and docstrings apart from the code. Source files are returned unchanged
+//! by default, or reduced to their comments, or stripped of them.
+
+use super::super::{
+    document::{METADATA_CODE_LANGUAGE, METADATA_FORMAT, ParsedDocument},
+    options::{CodeMode, ParseOptions},
+};
+
+/// Programming languages whose comments are recognised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Language {
+    /// Rust, with nested block comments, raw strings and lifetimes
+    Rust,
+    /// Python, with docstrings
+    Python,
+    /// JavaScript, with template literals
+    JavaScript,
+    /// TypeScript, with template literals
+    TypeScript,
+    /// Java, with text blocks
+    Java,
+    /// C
+    C,
+    /// C++, with digit separators
+    Cpp,
+    /// Go, with raw strings
+    Go,
+    /// SQL
+    Sql,
+    /// Shell scripts (sh, Bash, Zsh, ...)
+    Shell,
+}
+
+impl Language {
+    /// Finds the language matching a file extension.
+    pub(crate) fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "rs" => Some(Self::Rust),
+            "py" | "pyw" | "pyi" => Some(Self::Python),
+            "js" | "mjs" | "cjs" | "jsx" => Some(Self::JavaScript),
+            "ts" | "mts" | "cts" | "tsx" => Some(Self::TypeScript),
+            "java" => Some(Self::Java),
+            "c" | "h" => Some(Self::C),
+            "cpp" | "cc" | "cxx" | "c++" | "hpp" | "hh" | "hxx" | "h++" => Some(Self::Cpp),
+            "go" => Some(Self::Go),
+            "sql" => Some(Self::Sql),
+            "sh" | "bash" | "zsh" | "ksh" => Some(Self::Shell),
+            _ => None,
+        }
+    }
+
+    /// Recognises the language of a script from the interpreter of its shebang line,
+    /// as in `#!/bin/bash` or `#!/usr/bin/env python3`.
+    pub(crate) fn sniff(text: &str) -> Option<Self> {
+        let line = text
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .next()?
+            .strip_prefix("#!")?;
+        let mut words = line.split_whitespace();
+        let mut program = words.next()?.rsplit('/').next()?;
+        if program == "env" {
+            // Skip the options and variables of `env`, as in `env -S deno run`
+            program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+        }
+
+        // Interpreters may be versioned, as in `python3.12`
+        match program
+            .trim_end_matches(|character: char| character.is_ascii_digit() || character == '.')
+        {
+            "python" | "pypy" => Some(Self::Python),
+            "sh" | "bash" | "zsh" | "ksh" | "dash" | "ash" => Some(Self::Shell),
+            "node" | "nodejs" => Some(Self::JavaScript),
+            "deno" | "ts-node" => Some(Self::TypeScript),
+            _ => None,
+        }
+    }
+
+    /// Name of the language, as reported in the metadata.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+            Self::TypeScript => "typescript",
+            Self::Java => "java",
+            Self::C => "c",
+            Self::Cpp => "cpp",
+            Self::Go => "go",
+            Self::Sql => "sql",
+            Self::Shell => "shell",
+        }
+    }
+
+    /// Comment and string syntax of the language.
+    fn syntax(self) -> Syntax {
+        const C_BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+        let (line_comment, block_comment, strings): (_, _, &[Quote]) = match self {
+            Self::Rust => ("//", C_BLOCK, &[("\"", true, true)]),
+            Self::Python => (
+                "#",
+                None,
+                &[
+                    ("\"\"\"", true, true),
+                    ("'''", true, true),
+                    ("\"", true, false),
+                    ("'", true, false),
+                ],
+            ),
+            Self::JavaScript | Self::TypeScript => (
+                "//",
+                C_BLOCK,
+                &[("\"", true, false), ("'", true, false), ("`", true, true)],
+            ),
+            Self::Java => (
+                "//",
+                C_BLOCK,
+                &[
+                    ("\"\"\"", true, true),
+                    ("\"", true, false),
+                    ("'", true, false),
+                ],
+            ),
+            Self::C | Self::Cpp => ("//", C_BLOCK, &[("\"", true, false), ("'", true, false)]),
+            Self::Go => (
+                "//",
+                C_BLOCK,
+                &[("\"", true, false), ("'", true, false), ("`", false, true)],
+            ),
+            Self::Sql => ("--", C_BLOCK, &[("'", false, true), ("\"", false, true)]),
+            Self::Shell => ("#", None, &[("\"", true, true), ("'", false, true)]),
+        };
+
+        Syntax {
+            line_comment,
+            block_comment,
+            strings,
+        }
+    }
+}
+
+/// A string delimiter, whether backslashes escape characters in the string, and
+/// whether the string may span several lines.
+type Quote = (&'static str, bool, bool);
+
+/// Comment and string syntax of a language.
+struct Syntax {
+    /// Marker starting a comment that runs to the end of the line
+    line_comment: &'static str,
+    /// Markers opening and closing a block comment, if the language has them
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Delimiters of string literals, the longest first
+    strings: &'static [Quote],
+}
+
+/// Kinds of the spans of a source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// Code, including string literals
+    Code,
+    /// The `#!` line naming the interpreter of a script
+    Shebang,
+    /// A comment running to the end of the line, with its marker
+    LineComment,
+    /// A block comment, with its markers
+    BlockComment,
+    /// A Python docstring, with its quotes
+    Docstring,
+}
+
+/// Extracts the content of a source file.
+///
+/// # Arguments
+///
+/// * `text` - The decoded content of the file
+/// * `language` - The programming language of the file
+/// * `options` - The parsing options, of which `code_mode` is used
+///
+/// # Returns
+///
+/// The source, its comments or its code, with the `code` format and the language in
+/// the metadata.
+///
+/// # Implementation Notes
+///
+/// * Comment markers inside string literals are not mistaken for comments
+/// * In the `comments` mode, each comment or docstring is a paragraph without its
+///   markers, consecutive line comments being joined
+/// * In the `strip` mode, lines left empty by a removed comment are dropped, while
+///   the shebang line of scripts is kept
+pub(crate) fn parse_code(text: &str, language: Language, options: &ParseOptions) -> ParsedDocument {
+    let text = match options.code_mode {
+        CodeMode::Full => text.to_string(),
+        CodeMode::Comments => extract_comments(&scan(text, language), language),
+        CodeMode::Strip => strip_comments(&scan(text, language)),
+    };
+
+    ParsedDocument::from(text)
+        .with_metadata(METADATA_FORMAT, "code")
+        .with_metadata(METADATA_CODE_LANGUAGE, language.name())
+}
+
+/// Splits a source file into spans of code, comments and docstrings.
+fn scan(text: &str, language: Language) -> Vec<(Kind, &str)> {
+    let syntax = language.syntax();
+    let mut spans = Vec::new();
+    let mut code_start = 0;
+    let mut position = 0;
+
+    if text.starts_with("#!") {
+        position = text.find('\n').unwrap_or(text.len());
+        spans.push((Kind::Shebang, &text[..position]));
+        code_start = position;
+    }
+
+    while position < text.len() {
+        let rest = &text[position..];
+
+        let comment = if rest.starts_with(syntax.line_comment)
+            // In shell scripts, `#` only starts a comment at the start of a word
+            && (language != Language::Shell || starts_word(text, position))
+        {
+            Some((
+                Kind::LineComment,
+                rest.find('\n').map_or(text.len(), |end| position + end),
+            ))
+        } else if let Some((open, close)) = syntax.block_comment
+            && rest.starts_with(open)
+        {
+            Some((
+                Kind::BlockComment,
+                block_comment_end(text, position, open, close, language == Language::Rust),
+            ))
+        } else {
+            None
+        };
+        if let Some((kind, end)) = comment {
+            push_code(&mut spans, &text[code_start..position]);
+            spans.push((kind, &text[position..end]));
+            position = end;
+            code_start = end;
+            continue;
+        }
+
+        if let Some(end) = string_end(text, position, language, &syntax) {
+            if language == Language::Python
+                && is_docstring(text, &spans, &text[code_start..position], position, end)
+            {
+                push_code(&mut spans, &text[code_start..position]);
+                spans.push((Kind::Docstring, &text[position..end]));
+                code_start = end;
+            }
+            position = end;
+            continue;
+        }
+
+        position += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    push_code(&mut spans, &text[code_start..]);
+
+    spans
+}
+
+/// Adds a span of code, unless it is empty.
+fn push_code<'a>(spans: &mut Vec<(Kind, &'a str)>, code: &'a str) {
+    if !code.is_empty() {
+        spans.push((Kind::Code, code));
+    }
+}
+
+/// Checks whether a position is at the start of a shell word.
+fn starts_word(text: &str, position: usize) -> bool {
+    text[..position]
+        .chars()
+        .next_back()
+        .is_none_or(|character| {
+            character.is_whitespace() || matches!(character, ';' | '&' | '|' | '(' | ')')
+        })
+}
+
+/// Returns the end of a block comment, after its closing marker or at the end of the
+/// text when it isn't closed.
+fn block_comment_end(text: &str, start: usize, open: &str, close: &str, nested: bool) -> usize {
+    let mut depth = 0;
+    let mut position = start;
+
+    while position < text.len() {
+        let rest = &text[position..];
+        if rest.starts_with(open) && (nested || depth == 0) {
+            depth += 1;
+            position += open.len();
+        } else if rest.starts_with(close) {
+            position += close.len();
+            depth -= 1;
+            if depth == 0 {
+                return position;
+            }
+        } else {
+            position += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    text.len()
+}
+
+/// Returns the end of the string literal starting at a position, if any.
+///
+/// Strings that aren't closed end with their line, or with the text for strings that
+/// may span several lines.
+fn string_end(text: &str, start: usize, language: Language, syntax: &Syntax) -> Option<usize> {
+    let rest = &text[start..];
+    let previous = text[..start].chars().next_back();
+
+    match language {
+        Language::Rust => {
+            if let Some(end) = rust_raw_string_end(text, start) {
+                return Some(end);
+            }
+            // A quote starts a character literal, or a lifetime or label otherwise
+            if rest.starts_with('\'') {
+                return rust_char_end(rest).map(|length| start + length);
+            }
+        }
+        // A quote after a digit or a letter is a digit separator, as in `1'000'000`
+        Language::Cpp if rest.starts_with('\'') && previous.is_some_and(char::is_alphanumeric) => {
+            return None;
+        }
+        _ => {}
+    }
+
+    let &(delimiter, escapes, multiline) = syntax
+        .strings
+        .iter()
+        .find(|(delimiter, _, _)| rest.starts_with(delimiter))?;
+    let mut position = start + delimiter.len();
+
+    while position < text.len() {
+        let rest = &text[position..];
+        if rest.starts_with(delimiter) {
+            return Some(position + delimiter.len());
+        }
+        let mut characters = rest.chars();
+        match characters.next() {
+            Some('\n') if !multiline => return Some(position),
+            Some('\\') if escapes => {
+                position += 1 + characters.next().map_or(0, char::len_utf8);
+            }
+            Some(character) => position += character.len_utf8(),
+            None => break,
+        }
+    }
+
+    Some(text.len())
+}
+
+/// Returns the end of the Rust raw string starting at a position, as in `r#"..."#`,
+/// if any.
+fn rust_raw_string_end(text: &str, start: usize) -> Option<usize> {
+    let previous = text[..start].chars().next_back();
+    if previous.is_some_and(|character| character.is_alphanumeric() || character == '_') {
+        return None;
+    }
+
+    let rest = text[start..].strip_prefix('b').unwrap_or(&text[start..]);
+    let rest = rest.strip_prefix('r')?;
+    let hashes = rest.len() - rest.trim_start_matches('#').len();
+    let body = rest[hashes..].strip_prefix('"')?;
+
+    let closing = format!("\"{}", "#".repeat(hashes));
+    let end = body
+        .find(&closing)
+        .map_or(body.len(), |end| end + closing.len());
+    Some(text.len() - body.len() + end)
+}
+
+/// Returns the length of the Rust character literal at the start of text, if it is
+/// one rather than a lifetime.
+fn rust_char_end(text: &str) -> Option<usize> {
+    let mut characters = text.char_indices().skip(1);
+    match characters.next()? {
+        (_, '\\') => {
+            // Escapes are short: `'\n'`, `'\x7f'` or `'\u{10ffff}'`
+            let end = text
+                .get(3..)?
+                .char_indices()
+                .take(10)
+                .find(|&(_, character)| character == '\'')?
+                .0;
+            Some(3 + end + 1)
+        }
+        (_, '\'' | '\n') => None,
+        (_, _) => {
+            let (index, character) = characters.next()?;
+            (character == '\'').then_some(index + 1)
+        }
+    }
+}
+
+/// Checks whether the Python string literal between two positions is a docstring: a
+/// string alone on its lines, first in the module or after a line ending with `:`.
+fn is_docstring(
+    text: &str,
+    spans: &[(Kind, &str)],
+    pending: &str,
+    start: usize,
+    end: usize,
+) -> bool {
+    let line_start = text[..start].rfind('\n').map_or(0, |index| index + 1);
+    let alone = text[line_start..start].trim().is_empty()
+        && text[end..]
+            .split('\n')
+            .next()
+            .is_none_or(|rest| rest.trim().is_empty() || rest.trim_start().starts_with('#'));
+
+    let previous = std::iter::once(pending)
+        .chain(
+            spans
+                .iter()
+                .rev()
+                .filter(|(kind, _)| matches!(kind, Kind::Code | Kind::Docstring))
+                .map(|(_, text)| *text),
+        )
+        .find_map(|code| code.trim_end().chars().next_back());
+
+    alone && previous.is_none_or(|character| character == ':')
+}
+
+/// Renders the comments and docstrings of a source file as paragraphs.
+fn extract_comments(spans: &[(Kind, &str)], language: Language) -> String {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut lines: Vec<String> = Vec::new();
+
+    for (index, &(kind, text)) in spans.iter().enumerate() {
+        match kind {
+            Kind::LineComment => {
+                // Line comments on consecutive lines form a single paragraph
+                let follows = index >= 2
+                    && spans[index - 2].0 == Kind::LineComment
+                    && spans[index - 1].1.trim().is_empty()
+                    && spans[index - 1].1.matches('\n').count() == 1;
+                if !follows {
+                    paragraphs.push(std::mem::take(&mut lines).join("\n"));
+                }
+                lines.push(line_comment_text(text, language));
+            }
+            Kind::BlockComment => {
+                paragraphs.push(std::mem::take(&mut lines).join("\n"));
+                paragraphs.push(block_comment_text(text));
+            }
+            Kind::Docstring => {
+                paragraphs.push(std::mem::take(&mut lines).join("\n"));
+                paragraphs.push(docstring_text(text));
+            }
+            Kind::Code | Kind::Shebang => {}
+        }
+    }
+    paragraphs.push(lines.join("\n"));
+
+    paragraphs
+        .into_iter()
+        .map(|paragraph| paragraph.trim_matches('\n').to_string())
+        .filter(|paragraph| !paragraph.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Returns the text of a line comment, without its marker.
+fn line_comment_text(comment: &str, language: Language) -> String {
+    let marker = language.syntax().line_comment;
+    let mut text = comment.strip_prefix(marker).unwrap_or(comment);
+    // Rust documentation comments: `///` and `//!`
+    if language == Language::Rust {
+        text = text.strip_prefix(['/', '!']).unwrap_or(text);
+    }
+    text.strip_prefix(' ')
+        .unwrap_or(text)
+        .trim_end()
+        .to_string()
+}
+
+/// Returns the text of a block comment, without its markers and the `*` starting its
+/// lines.
+fn block_comment_text(comment: &str) -> String {
+    let text = comment.strip_prefix("/*").unwrap_or(comment);
+    let text = text.strip_suffix("*/").unwrap_or(text);
+    // Documentation comments: `/**` and `/*!`
+    let text = text.strip_prefix(['*', '!']).unwrap_or(text);
+
+    text.lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line).to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the text of a docstring, without its quotes and with its indentation
+/// removed, as Python's `inspect.cleandoc` does.
+fn docstring_text(docstring: &str) -> String {
+    let quote = ["\"\"\"", "'''", "\"", "'"]
+        .into_iter()
+        .find(|quote| docstring.starts_with(quote))
+        .unwrap_or_default();
+    let text = docstring.strip_prefix(quote).unwrap_or(docstring);
+    let text = text.strip_suffix(quote).unwrap_or(text);
+
+    let mut lines = text.lines();
+    let first = lines.next().unwrap_or_default().trim();
+    let rest: Vec<&str> = lines.collect();
+    let indent = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    std::iter::once(first)
+        .chain(
+            rest.iter()
+                .map(|line| line.get(indent..).unwrap_or_default().trim_end()),
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Renders a source file without its comments and docstrings.
+fn strip_comments(spans: &[(Kind, &str)]) -> String {
+    let mut output = String::new();
+    let mut removed = false;
+
+    for &(kind, text) in spans {
+        if !matches!(kind, Kind::Code | Kind::Shebang) {
+            let length = output.trim_end_matches([' ', '\t']).len();
+            output.truncate(length);
+            removed = true;
+            continue;
+        }
+
+        let mut code = text;
+        if removed {
+            let line = &output[output.rfind('\n').map_or(0, |index| index + 1)..];
+            let rest = code.trim_start_matches([' ', '\t', '\r']);
+            if line.is_empty() && rest.starts_with('\n') {
+                // The line only held the removed comment
+                code = &rest[1..];
+            } else if !output.ends_with(char::is_whitespace)
+                && !output.is_empty()
+                && !code.starts_with(char::is_whitespace)
+            {
+                // Keep apart the tokens the comment separated
+                output.push(' ');
+            }
+        }
+        output.push_str(code);
+        removed = false;
+    }
+
+    output
+        .trim_start_matches(['\n', '\r'])
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> String {
+        std::fs::read_to_string(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    fn parse_with_mode(text: &str, language: Language, code_mode: CodeMode) -> String {
+        let options = ParseOptions {
+            code_mode,
+            ..ParseOptions::default()
+        };
+        parse_code(text, language, &options).text
+    }
+
+    #[test]
+    fn parse_code_success() {
+        let text = read_test_file("test_py_1.py");
+        let result = parse_code(&text, Language::Python, &ParseOptions::default());
+
+        assert_eq!(result.text, text);
+        assert_eq!(result.metadata[METADATA_FORMAT], "code");
+        assert_eq!(result.metadata[METADATA_CODE_LANGUAGE], "python");
+    }
+
+    #[test]
+    fn parse_code_comments() {
+        let text = read_test_file("test_py_1.py");
+
+        assert_eq!(
+            parse_with_mode(&text, Language::Python, CodeMode::Comments),
+            "Hello, this is a test py for the parsing API.
+
+Radius of the unit circle
+
+in meters
+
+Return the area of a circle.
+
+The radius must be positive.
+
+pi r squared"
+        );
+    }
+
+    #[test]
+    fn parse_code_strip() {
+        let text = read_test_file("test_py_1.py");
+
+        assert_eq!(
+            parse_with_mode(&text, Language::Python, CodeMode::Strip),
+            r#"#!/usr/bin/env python3
+
+import math
+
+RADIUS = 1
+
+
+def area(radius):
+    url = "http://example.com/#anchor"
+    return math.pi * radius**2"#
+        );
+    }
+
+    #[test]
+    fn parse_rust_comments() {
+        let text = r##"//! Crate documentation
+//! on two lines.
+
+/* Nested /* block */ comment */
+fn main<'a>(name: &'a str) {
+    let quote = '"'; // A quote
+    let url = r#"https://example.com/*"#;
+    println!("// {name}");
+}"##;
+
+        assert_eq!(
+            parse_with_mode(text, Language::Rust, CodeMode::Comments),
+            "Crate documentation\non two lines.\n\nNested /* block */ comment\n\nA quote"
+        );
+        assert_eq!(
+            parse_with_mode(text, Language::Rust, CodeMode::Strip),
+            r##"fn main<'a>(name: &'a str) {
+    let quote = '"';
+    let url = r#"https://example.com/*"#;
+    println!("// {name}");
+}"##
+        );
+    }
+
+    #[test]
+    fn parse_c_comments() {
+        let text = "/**\n * Adds two numbers.\n * @return the sum\n */\nint add(int a, int b) {\n    return a/* first */+b; // \"sum\"\n}";
+
+        assert_eq!(
+            parse_with_mode(text, Language::C, CodeMode::Comments),
+            "Adds two numbers.\n@return the sum\n\nfirst\n\n\"sum\""
+        );
+        assert_eq!(
+            parse_with_mode(text, Language::C, CodeMode::Strip),
+            "int add(int a, int b) {\n    return a +b;\n}"
+        );
+    }
+
+    #[test]
+    fn parse_shell_and_sql_comments() {
+        let script = "#!/bin/sh\n# Count the arguments\necho \"$# # not a comment\" ${#1} # done";
+        assert_eq!(
+            parse_with_mode(script, Language::Shell, CodeMode::Comments),
+            "Count the arguments\n\ndone"
+        );
+        assert_eq!(
+            parse_with_mode(script, Language::Shell, CodeMode::Strip),
+            "#!/bin/sh\necho \"$# # not a comment\" ${#1}"
+        );
+
+        let query =
+            "-- Active users\nSELECT name FROM users WHERE note = 'it''s -- fine'; /* end */";
+        assert_eq!(
+            parse_with_mode(query, Language::Sql, CodeMode::Comments),
+            "Active users\n\nend"
+        );
+    }
+
+    #[test]
+    fn detect_language_success() {
+        assert_eq!(Language::from_extension("tsx"), Some(Language::TypeScript));
+        assert_eq!(Language::from_extension("hpp"), Some(Language::Cpp));
+        assert_eq!(Language::from_extension("txt"), None);
+
+        assert_eq!(Language::sniff("#!/bin/bash\necho"), Some(Language::Shell));
+        assert_eq!(
+            Language::sniff("#!/usr/bin/env python3.12\n"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            Language::sniff("#!/usr/bin/env -S deno run\n"),
+            Some(Language::TypeScript)
+        );
+        assert_eq!(Language::sniff("#!/usr/bin/perl\n"), None);
+        assert_eq!(Language::sniff("print('hello')"), None);
+    }
+}
@@ -0,0 +1,245 @@
+//! CSV parser module.
+//!
+//! This module provides functionality for parsing delimited text files (CSV, TSV,
+//! semicolon or pipe separated) using the `csv` library. Rows are rendered with the
+//! same choices as spreadsheets: normalized CSV, Markdown table or JSON records.
+
+use super::super::{
+    document::{METADATA_DELIMITER, METADATA_FORMAT, METADATA_HEADER, ParsedDocument},
+    errors::ParserError,
+    options::ParseOptions,
+};
+use super::table::Table;
+use csv::{ReaderBuilder, Trim};
+
+/// Delimiters recognised when sniffing, by order of preference
+const DELIMITERS: &[u8] = b",;\t|";
+
+/// Number of records inspected when sniffing the delimiter
+const SNIFF_RECORDS: usize = 20;
+
+/// Parses delimited text and renders its rows.
+///
+/// # Arguments
+///
+/// * `text` - The decoded content of the file
+/// * `delimiter` - The field delimiter, usually found with [`sniff_delimiter`]
+/// * `options` - The parsing options, of which the `table_format` is used
+///
+/// # Returns
+///
+/// * `Ok(ParsedDocument)` - The rendered rows, with the format, delimiter and header
+///   detection in the metadata
+/// * `Err(ParserError)` - If an error occurs during CSV parsing or rendering
+///
+/// # Implementation Notes
+///
+/// * Handles quoted fields, including escaped quotes and embedded delimiters or newlines
+/// * Trims the whitespace around fields and accepts rows of different lengths
+/// * Detects whether the first row is a header
+pub(crate) fn parse_csv(
+    text: &str,
+    delimiter: u8,
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParserError> {
+    let table = Table::new(read_records(text, delimiter)?);
+    let format = if delimiter == b'\t' { "tsv" } else { "csv" };
+
+    Ok(ParsedDocument::from(table.render(options.table_format)?)
+        .with_metadata(METADATA_FORMAT, format)
+        .with_metadata(METADATA_DELIMITER, char::from(delimiter))
+        .with_metadata(METADATA_HEADER, table.has_header.to_string()))
+}
+
+/// Reads the records of delimited text, trimming the whitespace around fields.
+///
+/// # Errors
+///
+/// Returns [`ParserError`] if a record is malformed, e.g. is not valid UTF-8.
+pub(crate) fn read_records(text: &str, delimiter: u8) -> Result<Vec<Vec<String>>, ParserError> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .trim(Trim::All)
+        .from_reader(text.as_bytes());
+
+    Ok(reader
+        .records()
+        .map(|record| record.map(|record| record.iter().map(str::to_string).collect()))
+        .collect::<Result<Vec<Vec<String>>, csv::Error>>()?)
+}
+
+/// Guesses the delimiter of delimited text.
+///
+/// A delimiter qualifies when it appears the same number of times, outside quotes,
+/// in every record of the sample. Among qualifying delimiters the most frequent wins.
+///
+/// # Returns
+///
+/// * `Some(u8)` - The delimiter if the text looks like delimited data
+/// * `None` - If the text does not look like delimited data
+///
+/// # Implementation Notes
+///
+/// * At least two records are required, and three when the delimiter appears only
+///   once per record, so that ordinary prose is not mistaken for CSV
+pub(crate) fn sniff_delimiter(text: &str) -> Option<u8> {
+    DELIMITERS
+        .iter()
+        .filter_map(|&delimiter| {
+            let counts = delimiter_counts(text, char::from(delimiter));
+            let first = *counts.first()?;
+            let consistent = first > 0 && counts.iter().all(|&count| count == first);
+            let enough_records = counts.len() >= if first == 1 { 3 } else { 2 };
+            (consistent && enough_records).then_some((delimiter, first))
+        })
+        // Keep the first delimiter on ties, as `max_by_key` returns the last maximum
+        .rev()
+        .max_by_key(|&(_, count)| count)
+        .map(|(delimiter, _)| delimiter)
+}
+
+/// Counts the delimiters outside quotes in each non-empty record of the sample.
+fn delimiter_counts(text: &str, delimiter: char) -> Vec<usize> {
+    let mut counts = Vec::new();
+    let mut count = 0;
+    let mut in_quotes = false;
+    let mut empty = true;
+
+    for c in text.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '\n' if !in_quotes => {
+                if !empty {
+                    counts.push(count);
+                    if counts.len() == SNIFF_RECORDS {
+                        return counts;
+                    }
+                }
+                count = 0;
+                empty = true;
+                continue;
+            }
+            c if c == delimiter && !in_quotes => count += 1,
+            _ => {}
+        }
+        if !c.is_whitespace() {
+            empty = false;
+        }
+    }
+
+    if !empty {
+        counts.push(count);
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::options::TableFormat;
+
+    fn read_test_file(filename: &str) -> String {
+        std::fs::read_to_string(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_csv_success() {
+        let text = read_test_file("test_csv_1.csv");
+        let result = parse_csv(&text, b';', &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            result.text,
+            "Username,Identifier,First name,Last name
+booker12,9012,Rachel,Booker
+grey07,2070,Laura,Grey"
+        );
+        assert_eq!(result.metadata[METADATA_FORMAT], "csv");
+        assert_eq!(result.metadata[METADATA_DELIMITER], ";");
+        assert_eq!(result.metadata[METADATA_HEADER], "true");
+    }
+
+    #[test]
+    fn parse_csv_markdown_success() {
+        let text = read_test_file("test_csv_1.csv");
+        let options = ParseOptions {
+            table_format: TableFormat::Markdown,
+            ..ParseOptions::default()
+        };
+        let result = parse_csv(&text, b';', &options).unwrap();
+
+        assert_eq!(
+            result.text,
+            "| Username | Identifier | First name | Last name |
+| --- | --- | --- | --- |
+| booker12 | 9012 | Rachel | Booker |
+| grey07 | 2070 | Laura | Grey |"
+        );
+    }
+
+    #[test]
+    fn parse_csv_json_success() {
+        let text = read_test_file("test_csv_1.csv");
+        let options = ParseOptions {
+            table_format: TableFormat::Json,
+            ..ParseOptions::default()
+        };
+        let result = parse_csv(&text, b';', &options).unwrap();
+
+        assert_eq!(
+            result.text,
+            r#"[
+  {
+    "Username": "booker12",
+    "Identifier": "9012",
+    "First name": "Rachel",
+    "Last name": "Booker"
+  },
+  {
+    "Username": "grey07",
+    "Identifier": "2070",
+    "First name": "Laura",
+    "Last name": "Grey"
+  }
+]"#
+        );
+    }
+
+    #[test]
+    fn parse_tsv_with_quotes() {
+        let text = "id\tcomment\n1\t\"multi\nline\"\n2\t\"say \"\"hi\"\"\"";
+        let result = parse_csv(text, b'\t', &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            result.text,
+            "id,comment\n1,\"multi\nline\"\n2,\"say \"\"hi\"\"\""
+        );
+        assert_eq!(result.metadata[METADATA_FORMAT], "tsv");
+    }
+
+    #[test]
+    fn sniff_delimiter_success() {
+        assert_eq!(
+            sniff_delimiter(&read_test_file("test_csv_1.csv")),
+            Some(b';')
+        );
+        assert_eq!(sniff_delimiter("a,b\n1,2\n3,4"), Some(b','));
+        assert_eq!(sniff_delimiter("a\tb\tc\n1\t2\t3\n"), Some(b'\t'));
+        assert_eq!(sniff_delimiter("a|b\n\"x|y\"|z\n1|2"), Some(b'|'));
+        assert_eq!(sniff_delimiter("a;b,c\n1;2,3\n4;5,6"), Some(b','));
+    }
+
+    #[test]
+    fn sniff_delimiter_plain_text() {
+        assert_eq!(sniff_delimiter("Hello, world!"), None);
+        assert_eq!(sniff_delimiter("Hello, world!\nHow are you, friend?"), None);
+        assert_eq!(sniff_delimiter("One, two, three.\nFour and five."), None);
+    }
+}
@@ -1,10 +1,21 @@
 //! DOCX parser module.
 //!
 //! This module provides functionality for extracting text from Microsoft Word DOCX
-//! documents using the `docx_rs` library.
+//! documents using the `docx_rs` library. Markdown output renders the headings,
+//! lists, emphasis and tables of the document from its styles and numberings.
 
-use super::super::errors::ParserError;
-use docx_rs::read_docx;
+use super::super::{
+    errors::ParserError,
+    options::{OutputFormat, ParseOptions, TableFormat},
+};
+use super::table::Table;
+use docx_rs::{
+    Bold, DocumentChild, Docx, Italic, Name, Paragraph, ParagraphChild, Run, RunChild,
+    TableCellContent, TableChild, TableRowChild, read_docx,
+};
+
+/// Deepest level of a list, OOXML numberings defining levels 0 to 8
+const MAX_LIST_LEVEL: usize = 8;
 
 /// Parses a DOCX file and extracts text content.
 ///
@@ -14,6 +25,7 @@ use docx_rs::read_docx;
 /// # Arguments
 ///
 /// * `data` - A byte slice containing the DOCX data
+/// * `options` - The parsing options, of which the `output_format` is used
 ///
 /// # Returns
 ///
@@ -25,11 +37,17 @@ use docx_rs::read_docx;
 /// * Uses the `docx_rs` library for DOCX parsing
 /// * Extracts text by traversing document structure: documents → paragraphs → runs → text
 /// * Joins paragraphs with newlines and trims whitespace from the result
+/// * In Markdown output, heading and title styles become headings, numbered
+///   paragraphs list items, bold and italic runs emphasis, and tables Markdown tables
 /// * TODO: Consider simplifying the document traversal logic
-pub(crate) fn parse_docx(data: &[u8]) -> Result<String, ParserError> {
+pub(crate) fn parse_docx(data: &[u8], options: &ParseOptions) -> Result<String, ParserError> {
     // Parse the DOCX document directly from bytes
     let docx = read_docx(data)?;
 
+    if options.output_format == OutputFormat::Markdown {
+        return Ok(render_markdown(&docx));
+    }
+
     // Extract text from the document
     let text = docx
         .document
@@ -64,9 +82,188 @@ pub(crate) fn parse_docx(data: &[u8]) -> Result<String, ParserError> {
     Ok(text)
 }
 
+/// Renders the body of a document as Markdown blocks, consecutive list items being
+/// kept together.
+fn render_markdown(docx: &Docx) -> String {
+    let mut text = String::new();
+    let mut in_list = false;
+
+    for child in &docx.document.children {
+        let (block, is_item) = match child {
+            DocumentChild::Paragraph(paragraph) => render_paragraph(docx, paragraph),
+            DocumentChild::Table(table) => (render_table(table), false),
+            _ => continue,
+        };
+        if block.is_empty() {
+            continue;
+        }
+        if !text.is_empty() {
+            text.push_str(if in_list && is_item { "\n" } else { "\n\n" });
+        }
+        text.push_str(&block);
+        in_list = is_item;
+    }
+
+    text
+}
+
+/// Renders a paragraph as a heading, a list item or plain Markdown text, returning
+/// whether it is a list item.
+fn render_paragraph(docx: &Docx, paragraph: &Paragraph) -> (String, bool) {
+    let text = paragraph
+        .children
+        .iter()
+        .filter_map(|child| match child {
+            ParagraphChild::Run(run) => Some(render_run(run)),
+            _ => None,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string();
+    if text.is_empty() {
+        return (text, false);
+    }
+
+    let property = &paragraph.property;
+    if let Some(level) = property
+        .style
+        .as_ref()
+        .and_then(|style| heading_level(docx, &style.val))
+    {
+        return (format!("{} {text}", "#".repeat(level)), false);
+    }
+
+    let Some(numbering) = &property.numbering_property else {
+        return (text, false);
+    };
+    let (Some(id), level) = (&numbering.id, numbering.level.as_ref().map_or(0, |l| l.val)) else {
+        return (text, false);
+    };
+    // A numbering identifier of 0 removes the numbering of the paragraph style
+    if id.id == 0 {
+        return (text, false);
+    }
+    let marker = if is_bullet(docx, id.id, level) {
+        "-"
+    } else {
+        "1."
+    };
+    let indent = " ".repeat(level.min(MAX_LIST_LEVEL) * (marker.len() + 1));
+    (format!("{indent}{marker} {text}"), true)
+}
+
+/// Renders the text of a run, wrapped in emphasis markers when it is bold or italic.
+fn render_run(run: &Run) -> String {
+    let text = run
+        .children
+        .iter()
+        .filter_map(|child| match child {
+            RunChild::Text(text) => Some(text.text.as_str()),
+            RunChild::Tab(_) => Some("\t"),
+            _ => None,
+        })
+        .collect::<String>();
+    let content = text.trim();
+    if content.is_empty() {
+        return text;
+    }
+
+    let property = &run.run_property;
+    let marker = match (
+        property
+            .bold
+            .as_ref()
+            .is_some_and(|bold| *bold == Bold::new()),
+        property
+            .italic
+            .as_ref()
+            .is_some_and(|italic| *italic == Italic::new()),
+    ) {
+        (true, true) => "***",
+        (true, false) => "**",
+        (false, true) => "*",
+        (false, false) => return text,
+    };
+    // Emphasis markers can't be separated from the text by whitespace
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+    format!("{leading}{marker}{content}{marker}{trailing}")
+}
+
+/// Renders a table as a Markdown table, the paragraphs of each cell joined on one
+/// line.
+fn render_table(table: &docx_rs::Table) -> String {
+    let rows = table
+        .rows
+        .iter()
+        .map(|TableChild::TableRow(row)| {
+            row.cells
+                .iter()
+                .map(|TableRowChild::TableCell(cell)| {
+                    cell.children
+                        .iter()
+                        .filter_map(|content| match content {
+                            TableCellContent::Paragraph(paragraph) => {
+                                Some(paragraph.raw_text().trim().to_string())
+                            }
+                            _ => None,
+                        })
+                        .filter(|text| !text.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect()
+        })
+        .collect();
+
+    // Rendering only fails on serialization errors, which cannot happen with strings
+    Table::new(rows)
+        .render(TableFormat::Markdown)
+        .unwrap_or_default()
+}
+
+/// Finds the heading level of a paragraph style, from its name: `Title` and
+/// `heading 1` are the first level, `heading N` the `N`th level, capped at six.
+fn heading_level(docx: &Docx, style_id: &str) -> Option<usize> {
+    let style = docx
+        .styles
+        .styles
+        .iter()
+        .find(|style| style.style_id == style_id)?;
+
+    if style.name == Name::new("Title") {
+        Some(1)
+    } else if style.name.is_heading() {
+        style
+            .name
+            .get_heading_number()
+            .map(|number| number.clamp(1, 6))
+    } else {
+        None
+    }
+}
+
+/// Checks whether a level of a numbering is a bulleted list, rather than numbered.
+fn is_bullet(docx: &Docx, numbering_id: usize, level: usize) -> bool {
+    let numberings = &docx.numberings;
+    numberings
+        .numberings
+        .iter()
+        .find(|numbering| numbering.id == numbering_id)
+        .and_then(|numbering| {
+            numberings
+                .abstract_nums
+                .iter()
+                .find(|abstract_num| abstract_num.id == numbering.abstract_num_id)
+        })
+        .and_then(|abstract_num| abstract_num.levels.iter().find(|l| l.level == level))
+        .is_none_or(|level| level.format.val == "bullet")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use docx_rs::{IndentLevel, NumberingId};
 
     fn read_test_file(filename: &str) -> Vec<u8> {
         std::fs::read(
@@ -80,7 +277,7 @@ mod tests {
     #[test]
     fn parse_docx_success() {
         let data = read_test_file("test_docx_1.docx");
-        let result = parse_docx(&data).unwrap();
+        let result = parse_docx(&data, &ParseOptions::default()).unwrap();
 
         assert!(!result.is_empty());
         assert_eq!(
@@ -88,4 +285,50 @@ mod tests {
             "Hello, this is a test docx for the parsing API.".to_string()
         );
     }
+
+    #[test]
+    fn parse_docx_markdown_success() {
+        let data = read_test_file("test_docx_4.docx");
+        let options = ParseOptions {
+            output_format: OutputFormat::Markdown,
+            ..ParseOptions::default()
+        };
+        let result = parse_docx(&data, &options).unwrap();
+
+        assert_eq!(
+            result,
+            "# Test Document
+
+# Introduction
+
+Hello, this is a **test docx** with *structure*.
+
+- First item
+  - Nested item
+- Second item
+
+## Details
+
+1. First step
+1. Second step
+
+| name | value |
+| --- | --- |
+| alpha | 1 |
+| beta | 2 |
+
+The end."
+        );
+    }
+
+    #[test]
+    fn render_paragraph_deep_list_level() {
+        let paragraph = Paragraph::new()
+            .add_run(Run::new().add_text("Deep item"))
+            .numbering(NumberingId::new(1), IndentLevel::new(usize::MAX));
+        let (text, is_list) = render_paragraph(&Docx::new(), &paragraph);
+
+        assert_eq!(text, format!("{}- Deep item", " ".repeat(16)));
+        assert!(is_list);
+    }
 }
@@ -0,0 +1,309 @@
+//! Email parser module.
+//!
+//! This module provides functionality for extracting text from email messages, either
+//! MIME messages (EML) or Outlook messages (MSG). The main headers and the body of a
+//! message are rendered as text, followed by the text of its attachments, which are
+//! parsed like any other document.
+
+mod mime;
+mod msg;
+
+pub(crate) use self::{
+    mime::{decode_charset, decode_quoted_printable, is_eml},
+    msg::is_msg,
+};
+
+use super::super::{
+    document::{METADATA_AUTHOR, METADATA_FORMAT, METADATA_TITLE, ParsedDocument},
+    errors::ParserError,
+    options::ParseOptions,
+};
+use super::parse_with_options;
+
+/// An email message, read from its MIME or Outlook representation.
+#[derive(Default)]
+struct Message {
+    /// Sender of the message
+    from: Option<String>,
+    /// Primary recipients
+    to: Option<String>,
+    /// Carbon copy recipients
+    cc: Option<String>,
+    /// Date the message was sent
+    date: Option<String>,
+    /// Subject of the message
+    subject: Option<String>,
+    /// Text of the body parts, in order
+    body: Vec<String>,
+    /// Attached files and messages
+    attachments: Vec<Attachment>,
+}
+
+/// A file or message attached to a message.
+struct Attachment {
+    /// File name of the attachment
+    name: String,
+    /// Content of the attachment
+    content: AttachmentContent,
+}
+
+/// Content of an attachment.
+enum AttachmentContent {
+    /// A file, parsed according to its detected type
+    Data(Vec<u8>),
+    /// A message already read from the same container (MSG embedded messages)
+    Message(Box<Message>),
+    /// A file whose transfer encoding could not be decoded
+    Undecodable,
+}
+
+/// Parses a MIME message (EML) and extracts its headers, body and attachments.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the message data
+/// * `options` - The parsing options, used to render HTML bodies and to parse the
+///   attachments
+///
+/// # Returns
+///
+/// * `Ok(ParsedDocument)` - The text of the message, with its subject and sender in
+///   the metadata
+/// * `Err(ParserError)` - If a part of the message can't be decoded
+///
+/// # Implementation Notes
+///
+/// * RFC 2047 encoded words of the headers are decoded
+/// * Quoted-printable and base64 parts are decoded from their charset
+/// * Among alternative bodies, the plain text one is preferred, HTML bodies being
+///   converted otherwise
+pub(crate) fn parse_eml(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParserError> {
+    let message = mime::read_message(data, options)?;
+    Ok(render_document(&message, "eml", options))
+}
+
+/// Parses an Outlook message (MSG) and extracts its headers, body and attachments.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the message data
+/// * `options` - The parsing options, used to render HTML bodies and to parse the
+///   attachments
+///
+/// # Returns
+///
+/// * `Ok(ParsedDocument)` - The text of the message, with its subject and sender in
+///   the metadata
+/// * `Err(ParserError)` - If the compound file can't be read
+///
+/// # Implementation Notes
+///
+/// * The plain text body is preferred, the HTML body being converted otherwise
+/// * Attached messages are rendered like the message itself
+pub(crate) fn parse_msg(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParserError> {
+    let message = msg::read_message(data, options)?;
+    Ok(render_document(&message, "msg", options))
+}
+
+/// Renders a message as a document, with its subject and sender in the metadata.
+fn render_document(message: &Message, format: &str, options: &ParseOptions) -> ParsedDocument {
+    let mut document = ParsedDocument::from(render_message(message, options))
+        .with_metadata(METADATA_FORMAT, format);
+    if let Some(subject) = &message.subject {
+        document = document.with_metadata(METADATA_TITLE, subject.as_str());
+    }
+    if let Some(from) = &message.from {
+        document = document.with_metadata(METADATA_AUTHOR, from.as_str());
+    }
+    document
+}
+
+/// Renders the headers, body and attachments of a message as text.
+///
+/// Each attachment follows the body after a `--- Attachment: <name> ---` separator.
+/// Attachments that can't be decoded or parsed are listed without text.
+fn render_message(message: &Message, options: &ParseOptions) -> String {
+    let headers = [
+        ("From", &message.from),
+        ("To", &message.to),
+        ("Cc", &message.cc),
+        ("Date", &message.date),
+        ("Subject", &message.subject),
+    ];
+    let headers: Vec<String> = headers
+        .into_iter()
+        .filter_map(|(name, value)| Some(format!("{name}: {}", value.as_ref()?)))
+        .collect();
+
+    let mut sections = vec![headers.join("\n")];
+    sections.extend(message.body.iter().filter(|text| !text.is_empty()).cloned());
+
+    for attachment in &message.attachments {
+        let text = match &attachment.content {
+            AttachmentContent::Data(data) => {
+                let options = ParseOptions {
+                    file_name: Some(attachment.name.clone()),
+                    nesting: options.nesting.nested(),
+                    ..options.clone()
+                };
+                parse_with_options(data, &options)
+                    .map(|document| document.text)
+                    .unwrap_or_default()
+            }
+            AttachmentContent::Message(message) => render_message(message, options),
+            AttachmentContent::Undecodable => String::new(),
+        };
+        sections.push(
+            format!("--- Attachment: {} ---\n{text}", attachment.name)
+                .trim_end()
+                .to_string(),
+        );
+    }
+
+    sections.join("\n\n").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_eml_success() {
+        let data = read_test_file("test_eml_1.eml");
+        let result = parse_eml(&data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            result.text,
+            "From: Jane Doe <jane@example.com>
+To: John Smith <john@example.com>
+Date: Mon, 15 Jan 2024 10:30:00 +0000
+Subject: Café meeting notes
+
+Hello, this is a test eml for the parsing API.
+Prices start at 5 €.
+
+--- Attachment: notes.txt ---
+These are the attachment notes."
+        );
+        assert_eq!(result.metadata[METADATA_TITLE], "Café meeting notes");
+        assert_eq!(
+            result.metadata[METADATA_AUTHOR],
+            "Jane Doe <jane@example.com>"
+        );
+    }
+
+    #[test]
+    fn parse_eml_html_body() {
+        let data = b"From: jane@example.com\r
+Subject: =?UTF-8?Q?Hello?= =?UTF-8?B?IHdvcmxk?=\r
+Content-Type: text/html; charset=\"utf-8\"\r
+\r
+<html><body><p>Hello <b>world</b></p><script>x()</script></body></html>\r
+";
+        let result = parse_eml(data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            result.text,
+            "From: jane@example.com\nSubject: Hello world\n\nHello world"
+        );
+    }
+
+    #[test]
+    fn parse_eml_undecodable_attachment() {
+        let data = b"From: jane@example.com\r
+Content-Type: multipart/mixed; boundary=\"b\"\r
+\r
+--b\r
+Content-Type: text/plain\r
+\r
+Hello, the report is attached.\r
+--b\r
+Content-Type: application/pdf; name=\"report.pdf\"\r
+Content-Transfer-Encoding: base64\r
+\r
+JVBERi0x!!not*base64\r
+--b--\r
+";
+        let result = parse_eml(data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            result.text,
+            "From: jane@example.com\n\nHello, the report is attached.\n\n--- Attachment: report.pdf ---"
+        );
+    }
+
+    #[test]
+    fn parse_eml_deeply_nested() {
+        let depth = 100_000;
+        let mut data = b"From: jane@example.com\r\nContent-Type: multipart/mixed; boundary=\"b0\"\r\n\r\n--b0\r\nContent-Type: text/plain\r\n\r\nTop\r\n".to_vec();
+        for level in 0..depth {
+            data.extend_from_slice(
+                format!(
+                    "--b{level}\r\nContent-Type: multipart/mixed; boundary=\"b{}\"\r\n\r\n",
+                    level + 1
+                )
+                .as_bytes(),
+            );
+        }
+        data.extend_from_slice(
+            format!("--b{depth}\r\nContent-Type: text/plain\r\n\r\nDeep\r\n").as_bytes(),
+        );
+        let result = parse_eml(&data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(result.text, "From: jane@example.com\n\nTop");
+    }
+
+    #[test]
+    fn parse_eml_nested_messages() {
+        let depth = 1000;
+        let mut data = Vec::new();
+        for level in 0..depth {
+            data.extend_from_slice(
+                format!(
+                    "From: jane@example.com\r\nSubject: Level {level}\r\nContent-Type: message/rfc822\r\n\r\n"
+                )
+                .as_bytes(),
+            );
+        }
+        let result = parse_eml(&data, &ParseOptions::default()).unwrap();
+
+        // The message and the 16 levels of messages attached to it
+        assert_eq!(result.text.matches("Subject: Level").count(), 17);
+        assert!(result.text.contains("Subject: Level 16\n"));
+    }
+
+    #[test]
+    fn parse_msg_success() {
+        let data = read_test_file("test_msg_1.msg");
+        let result = parse_msg(&data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            result.text,
+            "From: Jane Doe <jane@example.com>
+To: John Smith
+Date: Mon, 15 Jan 2024 10:30:00 +0000
+Subject: Café meeting notes
+
+Hello, this is a test msg for the parsing API.
+
+--- Attachment: notes.txt ---
+These are the attachment notes."
+        );
+        assert_eq!(result.metadata[METADATA_TITLE], "Café meeting notes");
+    }
+}
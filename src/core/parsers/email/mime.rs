@@ -0,0 +1,543 @@
+//! MIME message (EML) reading module.
+//!
+//! A MIME message is a block of headers followed by a body, which is either a single
+//! part encoded with a transfer encoding, or a multipart body whose parts are
+//! themselves made of headers and a body.
+
+use super::super::super::{errors::ParserError, options::ParseOptions};
+use super::super::html::render_html;
+use super::{Attachment, AttachmentContent, Message};
+use base64::{
+    Engine,
+    alphabet::STANDARD,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+use encoding_rs::{Encoding, UTF_8};
+use std::collections::HashMap;
+
+/// Number of leading bytes searched for the headers of a message
+const SNIFF_SIZE: usize = 8192;
+
+/// Deepest nesting of multipart bodies read, bounding the recursion over parts
+const MAX_NESTING_DEPTH: usize = 32;
+
+/// Headers recognised as the headers of a message when sniffing
+const MESSAGE_HEADERS: &[&str] = &[
+    "from",
+    "to",
+    "cc",
+    "subject",
+    "date",
+    "message-id",
+    "mime-version",
+    "received",
+    "return-path",
+    "reply-to",
+    "content-type",
+];
+
+/// Base64 engine tolerating missing padding, as written by some mailers
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &STANDARD,
+    GeneralPurposeConfig::new()
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+        .with_decode_allow_trailing_bits(true),
+);
+
+/// Checks whether data starts with the headers of an email message.
+///
+/// The data must start with well-formed header fields, including a `From` field and
+/// at least another common message header.
+pub(crate) fn is_eml(data: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&data[..data.len().min(SNIFF_SIZE)]);
+    let mut lines: Vec<&str> = head.lines().collect();
+    if data.len() > SNIFF_SIZE {
+        // The last line may be cut in the middle
+        lines.pop();
+    }
+
+    let mut names = Vec::new();
+    for line in lines {
+        if line.is_empty() {
+            break;
+        }
+        if line.starts_with([' ', '\t']) && !names.is_empty() {
+            continue;
+        }
+        let Some((name, _)) = line.split_once(':') else {
+            return false;
+        };
+        if name.is_empty() || !name.bytes().all(|byte| byte.is_ascii_graphic()) {
+            return false;
+        }
+        names.push(name.to_ascii_lowercase());
+    }
+
+    names.iter().any(|name| name == "from")
+        && names
+            .iter()
+            .filter(|name| MESSAGE_HEADERS.contains(&name.as_str()))
+            .count()
+            >= 2
+}
+
+/// Reads a MIME message: its main headers, body text and attachments.
+pub(super) fn read_message(data: &[u8], options: &ParseOptions) -> Result<Message, ParserError> {
+    let part = Part::parse(data);
+    let header = |name| part.header(name).map(decode_words);
+
+    let mut message = Message {
+        from: header("from"),
+        to: header("to"),
+        cc: header("cc"),
+        date: header("date"),
+        subject: header("subject"),
+        ..Message::default()
+    };
+    walk(&part, &mut message, options, 0)?;
+
+    Ok(message)
+}
+
+/// A part of a message: its header fields, unfolded, and its raw body.
+struct Part<'a> {
+    /// Header fields, names in lowercase
+    headers: Vec<(String, String)>,
+    /// Body, still encoded with the transfer encoding
+    body: &'a [u8],
+}
+
+impl<'a> Part<'a> {
+    /// Splits a part into its headers and its body at the first empty line.
+    fn parse(data: &'a [u8]) -> Self {
+        let mut headers: Vec<(String, String)> = Vec::new();
+        let mut position = 0;
+
+        while position < data.len() {
+            let end = data[position..]
+                .iter()
+                .position(|&byte| byte == b'\n')
+                .map_or(data.len(), |index| position + index);
+            let line = String::from_utf8_lossy(&data[position..end]);
+            let line = line.trim_end_matches('\r');
+            position = end + 1;
+
+            if line.is_empty() {
+                break;
+            }
+            if line.starts_with([' ', '\t']) {
+                // Folded line, continuing the previous field
+                if let Some((_, value)) = headers.last_mut() {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+            } else if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+            }
+        }
+
+        Self {
+            headers,
+            body: data.get(position..).unwrap_or_default(),
+        }
+    }
+
+    /// Returns the value of the first field with a name, compared in lowercase.
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Decodes the body from its transfer encoding.
+    fn decoded_body(&self) -> Result<Vec<u8>, ParserError> {
+        let encoding = self
+            .header("content-transfer-encoding")
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match encoding.as_str() {
+            "base64" => {
+                let compact: Vec<u8> = self
+                    .body
+                    .iter()
+                    .copied()
+                    .filter(|byte| !byte.is_ascii_whitespace())
+                    .collect();
+                Ok(BASE64.decode(compact)?)
+            }
+            "quoted-printable" => Ok(decode_quoted_printable(self.body)),
+            _ => Ok(self.body.to_vec()),
+        }
+    }
+}
+
+/// Walks the parts of a message at a nesting depth, adding the text parts to the
+/// body and the other parts to the attachments. Multipart bodies nested deeper than
+/// [`MAX_NESTING_DEPTH`] are skipped.
+fn walk(
+    part: &Part,
+    message: &mut Message,
+    options: &ParseOptions,
+    depth: usize,
+) -> Result<(), ParserError> {
+    let (mime_type, parameters) = parse_field(part.header("content-type").unwrap_or("text/plain"));
+    let (disposition, disposition_parameters) =
+        parse_field(part.header("content-disposition").unwrap_or_default());
+    let file_name = disposition_parameters
+        .get("filename")
+        .or_else(|| parameters.get("name"))
+        .map(|name| decode_words(name));
+
+    if let Some(subtype) = mime_type.strip_prefix("multipart/") {
+        let Some(boundary) = parameters
+            .get("boundary")
+            .filter(|_| depth < MAX_NESTING_DEPTH)
+        else {
+            return Ok(());
+        };
+        let parts: Vec<Part> = split_multipart(part.body, boundary)
+            .into_iter()
+            .map(Part::parse)
+            .collect();
+
+        if subtype == "alternative" {
+            // Prefer plain text, otherwise the last alternative is the richest one
+            let plain = parts.iter().find(|part| {
+                parse_field(part.header("content-type").unwrap_or("text/plain")).0 == "text/plain"
+            });
+            if let Some(chosen) = plain.or(parts.last()) {
+                walk(chosen, message, options, depth + 1)?;
+            }
+        } else {
+            for child in &parts {
+                walk(child, message, options, depth + 1)?;
+            }
+        }
+        return Ok(());
+    }
+
+    // A part that can't be decoded doesn't prevent reading the others: body text is
+    // skipped, and attachments are listed without content
+    let body = part.decoded_body();
+    let is_text = mime_type == "text/plain" || mime_type == "text/html";
+    if is_text && disposition != "attachment" && file_name.is_none() {
+        let Ok(body) = body else {
+            return Ok(());
+        };
+        let text = decode_charset(&body, parameters.get("charset").map(String::as_str));
+        let text = if mime_type == "text/html" {
+            render_html(&text, options).text
+        } else {
+            text.replace("\r\n", "\n")
+        };
+        message.body.push(text.trim().to_string());
+    } else if mime_type == "message/rfc822" {
+        message.attachments.push(Attachment {
+            name: file_name.unwrap_or_else(|| "message.eml".to_string()),
+            content: body.map_or(AttachmentContent::Undecodable, AttachmentContent::Data),
+        });
+    } else if let Some(name) = file_name {
+        // Inline parts without a name are decorations such as logos
+        message.attachments.push(Attachment {
+            name,
+            content: body.map_or(AttachmentContent::Undecodable, AttachmentContent::Data),
+        });
+    }
+
+    Ok(())
+}
+
+/// Splits a multipart body into its parts, skipping the preamble and epilogue.
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut position = 0;
+
+    while position < body.len() {
+        let end = body[position..]
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .map_or(body.len(), |index| position + index);
+        let line = body[position..end].trim_ascii_end();
+
+        if let Some(rest) = line.strip_prefix(delimiter.as_bytes())
+            && (rest.is_empty() || rest == b"--")
+        {
+            if let Some(start) = start {
+                // The line break before a delimiter belongs to the delimiter
+                let content = &body[start..position];
+                let content = content.strip_suffix(b"\n").unwrap_or(content);
+                parts.push(content.strip_suffix(b"\r").unwrap_or(content));
+            }
+            if rest == b"--" {
+                return parts;
+            }
+            start = Some((end + 1).min(body.len()));
+        }
+        position = end + 1;
+    }
+
+    // Unterminated multipart body
+    if let Some(start) = start {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+/// Parses a structured header field (`Content-Type`, `Content-Disposition`) into its
+/// lowercase value and its parameters.
+///
+/// RFC 2231 parameters, split in sections or with a charset, are reassembled and
+/// decoded.
+fn parse_field(field: &str) -> (String, HashMap<String, String>) {
+    let mut segments = split_unquoted(field).into_iter();
+    let value = segments
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    // Raw bytes of each parameter, with the charset of extended parameters
+    let mut raw: Vec<(String, Vec<u8>, Option<String>)> = Vec::new();
+    for segment in segments {
+        let Some((name, value)) = segment.split_once('=') else {
+            continue;
+        };
+        let name = name.trim().to_ascii_lowercase();
+        let value = unquote(value.trim());
+        let extended = name.ends_with('*');
+        let base = name.split('*').next().unwrap_or_default().to_string();
+
+        let index = if let Some(index) = raw.iter().position(|(name, _, _)| *name == base) {
+            index
+        } else {
+            raw.push((base, Vec::new(), None));
+            raw.len() - 1
+        };
+        let (_, bytes, charset) = &mut raw[index];
+
+        if extended {
+            let mut value = value.as_str();
+            if bytes.is_empty()
+                && let Some((declared, rest)) = value.split_once('\'')
+                && let Some((_, rest)) = rest.split_once('\'')
+            {
+                *charset = Some(declared.to_string());
+                value = rest;
+            }
+            bytes.extend(decode_percent(value));
+        } else {
+            bytes.extend(value.as_bytes());
+        }
+    }
+
+    let parameters = raw
+        .into_iter()
+        .map(|(name, bytes, charset)| (name, decode_charset(&bytes, charset.as_deref())))
+        .collect();
+    (value, parameters)
+}
+
+/// Splits a header field on the semicolons outside quoted strings.
+fn split_unquoted(field: &str) -> Vec<String> {
+    let mut segments = vec![String::new()];
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for character in field.chars() {
+        let segment = segments.last_mut().expect("segments is never empty");
+        match character {
+            _ if escaped => {
+                segment.push(character);
+                escaped = false;
+            }
+            '\\' if quoted => {
+                segment.push(character);
+                escaped = true;
+            }
+            '"' => {
+                segment.push(character);
+                quoted = !quoted;
+            }
+            ';' if !quoted => segments.push(String::new()),
+            _ => segment.push(character),
+        }
+    }
+
+    segments
+}
+
+/// Removes the quotes and escapes of a quoted string.
+fn unquote(value: &str) -> String {
+    let Some(inner) = value
+        .strip_prefix('"')
+        .map(|inner| inner.strip_suffix('"').unwrap_or(inner))
+    else {
+        return value.to_string();
+    };
+
+    let mut result = String::new();
+    let mut characters = inner.chars();
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => result.extend(characters.next()),
+            character => result.push(character),
+        }
+    }
+    result
+}
+
+/// Decodes the RFC 2047 encoded words (`=?charset?B?...?=`) of a header field.
+///
+/// Whitespace between adjacent encoded words is removed, as required by the RFC.
+pub(super) fn decode_words(field: &str) -> String {
+    let mut result = String::new();
+    // Whitespace following an encoded word, dropped if another encoded word follows
+    let mut pending = String::new();
+    let mut after_word = false;
+    let mut rest = field;
+
+    while !rest.is_empty() {
+        if let Some((decoded, length)) = rest.strip_prefix("=?").and_then(decode_word) {
+            if !after_word {
+                result.push_str(&pending);
+            }
+            pending.clear();
+            result.push_str(&decoded);
+            after_word = true;
+            rest = &rest[2 + length..];
+            continue;
+        }
+
+        let character = rest.chars().next().expect("rest is not empty");
+        if character.is_whitespace() {
+            pending.push(character);
+        } else {
+            result.push_str(&pending);
+            pending.clear();
+            result.push(character);
+            after_word = false;
+        }
+        rest = &rest[character.len_utf8()..];
+    }
+
+    result.push_str(&pending);
+    result
+}
+
+/// Decodes an encoded word following its `=?` opening, returning the text and the
+/// length read up to and including the closing `?=`.
+fn decode_word(word: &str) -> Option<(String, usize)> {
+    let (charset, rest) = word.split_once('?')?;
+    let (encoding, rest) = rest.split_once('?')?;
+    let (text, _) = rest.split_once("?=")?;
+    if charset.contains(char::is_whitespace) || text.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let bytes = match encoding {
+        "B" | "b" => BASE64.decode(text).ok()?,
+        "Q" | "q" => decode_quoted_printable(text.replace('_', " ").as_bytes()),
+        _ => return None,
+    };
+    let length = charset.len() + encoding.len() + text.len() + 4;
+    // RFC 2231 allows a language after the charset
+    let charset = charset.split('*').next().unwrap_or_default();
+
+    Some((decode_charset(&bytes, Some(charset)), length))
+}
+
+/// Decodes quoted-printable data, removing soft line breaks.
+pub(crate) fn decode_quoted_printable(data: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(data.len());
+    let mut position = 0;
+
+    while let Some(&byte) = data.get(position) {
+        if byte != b'=' {
+            result.push(byte);
+            position += 1;
+            continue;
+        }
+
+        let rest = &data[position + 1..];
+        if let Some(after) = rest
+            .strip_prefix(b"\r\n")
+            .or_else(|| rest.strip_prefix(b"\n"))
+        {
+            position = data.len() - after.len();
+        } else if let Some(decoded) = rest
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            result.push(decoded);
+            position += 3;
+        } else {
+            result.push(byte);
+            position += 1;
+        }
+    }
+
+    result
+}
+
+/// Decodes the percent-escapes of an RFC 2231 parameter value.
+fn decode_percent(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut position = 0;
+
+    while let Some(&byte) = bytes.get(position) {
+        let decoded = (byte == b'%')
+            .then(|| bytes.get(position + 1..position + 3))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        if let Some(decoded) = decoded {
+            result.push(decoded);
+            position += 3;
+        } else {
+            result.push(byte);
+            position += 1;
+        }
+    }
+
+    result
+}
+
+/// Decodes text in a charset, UTF-8 when it is missing or unknown.
+pub(crate) fn decode_charset(data: &[u8], charset: Option<&str>) -> String {
+    let encoding = charset
+        .and_then(|label| Encoding::for_label(label.trim().as_bytes()))
+        .unwrap_or(UTF_8);
+    encoding.decode(data).0.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_field_success() {
+        let (value, parameters) = parse_field(
+            "Attachment; filename*0*=UTF-8''r%C3%A9sum; filename*1=\"\\\"final\\\".pdf\"; size=10",
+        );
+
+        assert_eq!(value, "attachment");
+        assert_eq!(parameters["filename"], "résum\"final\".pdf");
+        assert_eq!(parameters["size"], "10");
+    }
+
+    #[test]
+    fn split_multipart_success() {
+        let body = b"preamble\r\n--b\r\nfirst\r\n--b\r\nsecond\r\n--b--\r\nepilogue";
+
+        assert_eq!(
+            split_multipart(body, "b"),
+            vec![b"first".as_slice(), b"second".as_slice()]
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! Outlook message (MSG) reading module.
+//!
+//! An Outlook message is an OLE2 compound file of MAPI properties. Each
+//! variable-length property is stored in a `__substg1.0_IIIITTTT` stream named after
+//! its identifier and type, while fixed-length properties such as dates are listed in
+//! the `__properties_version1.0` stream. Attachments are storages holding properties
+//! of their own, embedded messages being storages laid out like the message.
+
+use super::super::super::{encoding::decode_text, errors::ParserError, options::ParseOptions};
+use super::super::html::render_html;
+use super::{Attachment, AttachmentContent, Message};
+use cfb::CompoundFile;
+use encoding_rs::WINDOWS_1252;
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+
+/// Stream listing the fixed-length properties of a storage
+const PROPERTIES_STREAM: &str = "__properties_version1.0";
+
+/// Prefix of the storages of attachments
+const ATTACHMENT_PREFIX: &str = "__attach_version1.0_#";
+
+/// Length of the header of the properties stream of the message
+const MESSAGE_HEADER_LENGTH: usize = 32;
+
+/// Length of the header of the properties stream of embedded messages
+const EMBEDDED_HEADER_LENGTH: usize = 24;
+
+/// Deepest nesting of embedded messages, bounding the recursion
+const MAX_NESTING_DEPTH: usize = 16;
+
+/// Property type of UTF-16 strings
+const PT_UNICODE: u16 = 0x001F;
+
+/// Property type of 8-bit strings
+const PT_STRING8: u16 = 0x001E;
+
+/// Property type of binary data
+const PT_BINARY: u16 = 0x0102;
+
+/// Property type of embedded objects, such as attached messages
+const PT_OBJECT: u16 = 0x000D;
+
+/// Property type of dates, as `FILETIME` values
+const PT_SYSTIME: u16 = 0x0040;
+
+/// Subject of the message
+const PR_SUBJECT: u16 = 0x0037;
+
+/// Date the message was sent
+const PR_CLIENT_SUBMIT_TIME: u16 = 0x0039;
+
+/// Display name of the sender
+const PR_SENDER_NAME: u16 = 0x0C1A;
+
+/// Address of the sender, which may be an Exchange address
+const PR_SENDER_EMAIL_ADDRESS: u16 = 0x0C1F;
+
+/// SMTP address of the sender
+const PR_SENDER_SMTP_ADDRESS: u16 = 0x5D01;
+
+/// Display names of the carbon copy recipients
+const PR_DISPLAY_CC: u16 = 0x0E03;
+
+/// Display names of the primary recipients
+const PR_DISPLAY_TO: u16 = 0x0E04;
+
+/// Date the message was received
+const PR_MESSAGE_DELIVERY_TIME: u16 = 0x0E06;
+
+/// Plain text body
+const PR_BODY: u16 = 0x1000;
+
+/// HTML body
+const PR_HTML: u16 = 0x1013;
+
+/// Display name of an attachment
+const PR_DISPLAY_NAME: u16 = 0x3001;
+
+/// Data of an attachment, or storage of an attached message
+const PR_ATTACH_DATA: u16 = 0x3701;
+
+/// Short (8.3) file name of an attachment
+const PR_ATTACH_FILENAME: u16 = 0x3704;
+
+/// Long file name of an attachment
+const PR_ATTACH_LONG_FILENAME: u16 = 0x3707;
+
+/// Checks whether data is an Outlook message, an OLE2 compound file with MAPI
+/// properties at its root.
+pub(crate) fn is_msg(data: &[u8]) -> bool {
+    CompoundFile::open(Cursor::new(data))
+        .is_ok_and(|compound| compound.is_stream(Path::new("/").join(PROPERTIES_STREAM)))
+}
+
+/// Reads an Outlook message: its main headers, body text and attachments.
+pub(super) fn read_message(data: &[u8], options: &ParseOptions) -> Result<Message, ParserError> {
+    let mut compound = CompoundFile::open(Cursor::new(data))?;
+    read_storage(
+        &mut compound,
+        Path::new("/"),
+        MESSAGE_HEADER_LENGTH,
+        options,
+        0,
+    )
+}
+
+/// Reads the message stored in a storage of the compound file, embedded messages
+/// nested deeper than [`MAX_NESTING_DEPTH`] being skipped.
+fn read_storage<F: Read + Seek>(
+    compound: &mut CompoundFile<F>,
+    storage: &Path,
+    header_length: usize,
+    options: &ParseOptions,
+    depth: usize,
+) -> Result<Message, ParserError> {
+    let sender_name = read_string(compound, storage, PR_SENDER_NAME)?;
+    // Exchange addresses are not email addresses
+    let sender_address = match read_string(compound, storage, PR_SENDER_SMTP_ADDRESS)? {
+        Some(address) => Some(address),
+        None => read_string(compound, storage, PR_SENDER_EMAIL_ADDRESS)?,
+    }
+    .filter(|address| address.contains('@'));
+    let from = match (sender_name, sender_address) {
+        (Some(name), Some(address)) if name != address => Some(format!("{name} <{address}>")),
+        (name, address) => address.or(name),
+    };
+
+    let date = match read_time(compound, storage, header_length, PR_CLIENT_SUBMIT_TIME)? {
+        Some(time) => Some(time),
+        None => read_time(compound, storage, header_length, PR_MESSAGE_DELIVERY_TIME)?,
+    };
+
+    let mut message = Message {
+        from,
+        to: read_string(compound, storage, PR_DISPLAY_TO)?,
+        cc: read_string(compound, storage, PR_DISPLAY_CC)?,
+        date: date.and_then(format_filetime),
+        subject: read_string(compound, storage, PR_SUBJECT)?,
+        ..Message::default()
+    };
+
+    if let Some(body) = read_string(compound, storage, PR_BODY)? {
+        message
+            .body
+            .push(body.replace("\r\n", "\n").trim().to_string());
+    } else if let Some(html) = read_property(compound, storage, PR_HTML, PT_BINARY)? {
+        let (html, _) = decode_text(&html, None)?;
+        message
+            .body
+            .push(render_html(&html, options).text.trim().to_string());
+    }
+
+    let mut attachments: Vec<PathBuf> = compound
+        .read_storage(storage)?
+        .filter(|entry| entry.is_storage() && entry.name().starts_with(ATTACHMENT_PREFIX))
+        .map(|entry| entry.path().to_path_buf())
+        .collect();
+    // Attachment storages are numbered in the order of the attachments
+    attachments.sort();
+
+    for attachment in attachments {
+        let mut name = None;
+        for id in [PR_ATTACH_LONG_FILENAME, PR_ATTACH_FILENAME, PR_DISPLAY_NAME] {
+            name = read_string(compound, &attachment, id)?;
+            if name.is_some() {
+                break;
+            }
+        }
+
+        let object = attachment.join(property_stream(PR_ATTACH_DATA, PT_OBJECT));
+        if compound.is_storage(&object) {
+            if depth >= MAX_NESTING_DEPTH {
+                continue;
+            }
+            let embedded = read_storage(
+                compound,
+                &object,
+                EMBEDDED_HEADER_LENGTH,
+                options,
+                depth + 1,
+            )?;
+            message.attachments.push(Attachment {
+                name: name
+                    .or_else(|| embedded.subject.clone())
+                    .unwrap_or_else(|| "message.msg".to_string()),
+                content: AttachmentContent::Message(Box::new(embedded)),
+            });
+        } else if let Some(data) = read_property(compound, &attachment, PR_ATTACH_DATA, PT_BINARY)?
+        {
+            message.attachments.push(Attachment {
+                name: name.unwrap_or_else(|| "attachment".to_string()),
+                content: AttachmentContent::Data(data),
+            });
+        }
+    }
+
+    Ok(message)
+}
+
+/// Returns the name of the stream of a variable-length property.
+fn property_stream(id: u16, kind: u16) -> String {
+    format!("__substg1.0_{id:04X}{kind:04X}")
+}
+
+/// Reads a variable-length property of a storage, if it is present.
+fn read_property<F: Read + Seek>(
+    compound: &mut CompoundFile<F>,
+    storage: &Path,
+    id: u16,
+    kind: u16,
+) -> Result<Option<Vec<u8>>, ParserError> {
+    let path = storage.join(property_stream(id, kind));
+    if !compound.is_stream(&path) {
+        return Ok(None);
+    }
+
+    let mut content = Vec::new();
+    compound.open_stream(&path)?.read_to_end(&mut content)?;
+    Ok(Some(content))
+}
+
+/// Reads a string property, stored as UTF-16 or 8-bit text, if it is present and not
+/// empty.
+fn read_string<F: Read + Seek>(
+    compound: &mut CompoundFile<F>,
+    storage: &Path,
+    id: u16,
+) -> Result<Option<String>, ParserError> {
+    let text = if let Some(data) = read_property(compound, storage, id, PT_UNICODE)? {
+        let units: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else if let Some(data) = read_property(compound, storage, id, PT_STRING8)? {
+        WINDOWS_1252
+            .decode_without_bom_handling(&data)
+            .0
+            .into_owned()
+    } else {
+        return Ok(None);
+    };
+
+    let text = text.trim_matches(|character: char| character == '\0' || character.is_whitespace());
+    Ok((!text.is_empty()).then(|| text.to_string()))
+}
+
+/// Reads a date property from the fixed-length properties of a storage.
+///
+/// Each entry of the properties stream is 16 bytes long: the property type and
+/// identifier, flags, and the 8-byte value.
+fn read_time<F: Read + Seek>(
+    compound: &mut CompoundFile<F>,
+    storage: &Path,
+    header_length: usize,
+    id: u16,
+) -> Result<Option<u64>, ParserError> {
+    let path = storage.join(PROPERTIES_STREAM);
+    if !compound.is_stream(&path) {
+        return Ok(None);
+    }
+    let mut content = Vec::new();
+    compound.open_stream(&path)?.read_to_end(&mut content)?;
+
+    Ok(content
+        .get(header_length..)
+        .unwrap_or_default()
+        .chunks_exact(16)
+        .find(|entry| {
+            u16::from_le_bytes([entry[0], entry[1]]) == PT_SYSTIME
+                && u16::from_le_bytes([entry[2], entry[3]]) == id
+        })
+        .and_then(|entry| Some(u64::from_le_bytes(entry[8..16].try_into().ok()?))))
+}
+
+/// Formats a `FILETIME` (100-nanosecond intervals since 1601) as an RFC 2822 date in
+/// UTC, like the `Date` header of MIME messages.
+fn format_filetime(filetime: u64) -> Option<String> {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    // Dates before the Unix epoch are not meaningful for email
+    let seconds = (filetime / 10_000_000).checked_sub(11_644_473_600)?;
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+
+    // Civil date from the number of days, shifted to start on March 1st, 0000
+    let shifted = days + 719_468;
+    let era = shifted / 146_097;
+    let day_of_era = shifted % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 2
+    } else {
+        shifted_month - 10
+    };
+    let year = era * 400 + year_of_era + u64::from(month < 2);
+
+    Some(format!(
+        "{}, {day} {} {year} {:02}:{:02}:{:02} +0000",
+        DAYS[usize::try_from(days % 7).ok()?],
+        MONTHS[usize::try_from(month).ok()?],
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn format_filetime_success() {
+        // 2024-02-29 13:45:30 UTC
+        assert_eq!(
+            format_filetime(133_536_879_300_000_000).unwrap(),
+            "Thu, 29 Feb 2024 13:45:30 +0000"
+        );
+        assert_eq!(format_filetime(0), None);
+    }
+
+    #[test]
+    fn read_message_deeply_nested() {
+        let depth = 100;
+        let mut compound = CompoundFile::create(Cursor::new(Vec::new())).unwrap();
+        compound
+            .create_stream(Path::new("/").join(PROPERTIES_STREAM))
+            .unwrap();
+        let mut storage = PathBuf::from("/");
+        for level in 0..depth {
+            let mut stream = compound
+                .create_stream(storage.join(property_stream(PR_SUBJECT, PT_UNICODE)))
+                .unwrap();
+            let subject: Vec<u8> = format!("Level {level}")
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect();
+            stream.write_all(&subject).unwrap();
+            drop(stream);
+
+            storage = storage.join(format!("{ATTACHMENT_PREFIX}00000000"));
+            compound.create_storage(&storage).unwrap();
+            storage = storage.join(property_stream(PR_ATTACH_DATA, PT_OBJECT));
+            compound.create_storage(&storage).unwrap();
+        }
+        let data = compound.into_inner().into_inner();
+
+        let mut message = read_message(&data, &ParseOptions::default()).unwrap();
+        let mut levels = 1;
+        while let Some(Attachment {
+            content: AttachmentContent::Message(embedded),
+            ..
+        }) = message.attachments.pop()
+        {
+            assert_eq!(embedded.subject, Some(format!("Level {levels}")));
+            message = *embedded;
+            levels += 1;
+        }
+        assert_eq!(levels, MAX_NESTING_DEPTH + 1);
+    }
+}
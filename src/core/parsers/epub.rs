@@ -0,0 +1,326 @@
+//! EPUB parser module.
+//!
+//! This module provides functionality for extracting text from EPUB 2 and EPUB 3
+//! e-books. The package document, located through `META-INF/container.xml`, gives the
+//! metadata of the book and the reading order of its XHTML chapters, which are
+//! rendered by the HTML parser. Chapter titles come from the EPUB 3 navigation
+//! document or the EPUB 2 NCX table of contents.
+
+use super::super::{
+    document::{
+        METADATA_AUTHOR, METADATA_FORMAT, METADATA_LANGUAGE, METADATA_TITLE, ParsedDocument,
+    },
+    errors::ParserError,
+    options::ParseOptions,
+};
+use super::html::render_html;
+use super::package::{
+    Package, directory, has_name, open_package, read_part, read_text_part, resolve,
+};
+use encoding_rs::UTF_8;
+use roxmltree::{Document, Node, ParsingOptions};
+use std::collections::HashMap;
+
+/// Path of the file locating the package document
+const CONTAINER_PATH: &str = "META-INF/container.xml";
+
+/// Namespace of the `epub:type` attribute
+const OPS_NS: &str = "http://www.idpf.org/2007/ops";
+
+/// Media type of EPUB 2 NCX tables of contents
+const NCX_MEDIA_TYPE: &str = "application/x-dtbncx+xml";
+
+/// Parses an EPUB file and extracts the text of its chapters.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the EPUB data
+/// * `options` - The parsing options, used to render the XHTML chapters
+///
+/// # Returns
+///
+/// * `Ok(ParsedDocument)` - The text of the chapters in reading order, with the
+///   title, author and language of the book in the metadata
+/// * `Err(ParserError)` - If the archive or its package document can't be read
+///
+/// # Implementation Notes
+///
+/// * Chapters follow the spine of the package document
+/// * Each chapter with a title in the table of contents starts with a
+///   `--- Chapter: <title> ---` separator
+/// * Multiple authors are joined with commas
+pub(crate) fn parse_epub(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParserError> {
+    let mut package = open_package(data)?;
+
+    let container = read_text_part(&mut package, CONTAINER_PATH)?;
+    let opf_path = parse_xml(&container)?
+        .descendants()
+        .find(|node| has_name(*node, "rootfile"))
+        .and_then(|node| node.attribute("full-path"))
+        .map(str::to_string)
+        .ok_or_else(|| {
+            ParserError::ParseError("Invalid EPUB: missing package document".to_string())
+        })?;
+
+    let opf = read_text_part(&mut package, &opf_path)?;
+    let book = Book::read(&parse_xml(&opf)?, directory(&opf_path));
+    let titles = book.read_titles(&mut package);
+
+    let mut chapters = Vec::new();
+    for path in &book.spine {
+        let content = read_part(&mut package, path)?;
+        let (xhtml, _, _) = UTF_8.decode(&content);
+        let text = render_html(&xhtml, options).text;
+
+        match titles.get(path) {
+            Some(title) => chapters.push(format!("--- Chapter: {title} ---\n{text}")),
+            // Untitled documents without text are covers and other images
+            None if text.trim().is_empty() => {}
+            None => chapters.push(text),
+        }
+    }
+
+    let mut document = ParsedDocument::from(chapters.join("\n\n").trim().to_string())
+        .with_metadata(METADATA_FORMAT, "epub");
+    if let Some(title) = book.title {
+        document = document.with_metadata(METADATA_TITLE, title);
+    }
+    if !book.authors.is_empty() {
+        document = document.with_metadata(METADATA_AUTHOR, book.authors.join(", "));
+    }
+    if let Some(language) = book.language {
+        document = document.with_metadata(METADATA_LANGUAGE, language);
+    }
+
+    Ok(document)
+}
+
+/// The content of the package document of a book.
+#[derive(Default)]
+struct Book {
+    /// Title of the book
+    title: Option<String>,
+    /// Authors of the book
+    authors: Vec<String>,
+    /// Language of the book
+    language: Option<String>,
+    /// Paths of the chapters, in reading order
+    spine: Vec<String>,
+    /// Path of the EPUB 3 navigation document
+    nav: Option<String>,
+    /// Path of the EPUB 2 NCX table of contents
+    ncx: Option<String>,
+}
+
+impl Book {
+    /// Reads the metadata, manifest and spine of a package document.
+    fn read(opf: &Document, base: &str) -> Self {
+        let mut book = Self::default();
+        // Manifest items by identifier: path and media type
+        let mut items = HashMap::new();
+
+        for node in opf.descendants().filter(Node::is_element) {
+            match node.tag_name().name() {
+                "title" if book.title.is_none() => book.title = element_text(node),
+                "creator" => book.authors.extend(element_text(node)),
+                "language" if book.language.is_none() => book.language = element_text(node),
+                "item" => {
+                    let (Some(id), Some(href)) = (node.attribute("id"), node.attribute("href"))
+                    else {
+                        continue;
+                    };
+                    let path = resolve(base, href);
+                    let properties = node.attribute("properties").unwrap_or_default();
+                    if properties
+                        .split_whitespace()
+                        .any(|property| property == "nav")
+                    {
+                        book.nav = Some(path.clone());
+                    }
+                    items.insert(id, (path, node.attribute("media-type").unwrap_or_default()));
+                }
+                "spine" => {
+                    book.ncx = node
+                        .attribute("toc")
+                        .and_then(|id| items.get(id))
+                        .map(|(path, _)| path.clone());
+                }
+                "itemref" => {
+                    if let Some((path, _)) = node.attribute("idref").and_then(|id| items.get(id)) {
+                        book.spine.push(path.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if book.ncx.is_none() {
+            book.ncx = items
+                .values()
+                .find(|(_, media_type)| *media_type == NCX_MEDIA_TYPE)
+                .map(|(path, _)| path.clone());
+        }
+
+        book
+    }
+
+    /// Reads the title of each chapter from the navigation document, or from the NCX
+    /// table of contents of older books.
+    ///
+    /// The titles are a convenience: a missing or malformed table of contents leaves
+    /// the chapters untitled rather than failing the whole book.
+    fn read_titles(&self, package: &mut Package) -> HashMap<String, String> {
+        let mut read =
+            |path: &Option<String>, read_links: fn(&Document, &str) -> Vec<(String, String)>| {
+                let path = path.as_deref()?;
+                let content = read_text_part(package, path).ok()?;
+                let document = parse_xml(&content).ok()?;
+                Some(read_links(&document, directory(path)))
+            };
+
+        let mut links = read(&self.nav, nav_links).unwrap_or_default();
+        if links.is_empty() {
+            links = read(&self.ncx, ncx_links).unwrap_or_default();
+        }
+
+        let mut titles = HashMap::new();
+        for (path, title) in links {
+            // The first entry of a file is the chapter itself, the next ones its sections
+            titles.entry(path).or_insert(title);
+        }
+        titles
+    }
+}
+
+/// Lists the entries of the table of contents of an EPUB 3 navigation document, as
+/// the path and title of each target.
+fn nav_links(nav: &Document, base: &str) -> Vec<(String, String)> {
+    let navs: Vec<Node> = nav
+        .descendants()
+        .filter(|node| has_name(*node, "nav"))
+        .collect();
+    let Some(toc) = navs
+        .iter()
+        .find(|node| node.attribute((OPS_NS, "type")) == Some("toc"))
+        .or(navs.first())
+    else {
+        return Vec::new();
+    };
+
+    toc.descendants()
+        .filter(|node| has_name(*node, "a"))
+        .filter_map(|link| Some((resolve(base, link.attribute("href")?), element_text(link)?)))
+        .collect()
+}
+
+/// Lists the navigation points of an EPUB 2 NCX table of contents, as the path and
+/// title of each target.
+fn ncx_links(ncx: &Document, base: &str) -> Vec<(String, String)> {
+    ncx.descendants()
+        .filter(|node| has_name(*node, "navPoint"))
+        .filter_map(|point| {
+            let children = || point.children().filter(Node::is_element);
+            let label = children().find(|node| has_name(*node, "navLabel"))?;
+            let title = element_text(label)?;
+            let source = children()
+                .find(|node| has_name(*node, "content"))?
+                .attribute("src")?;
+            Some((resolve(base, source), title))
+        })
+        .collect()
+}
+
+/// Parses an XML file of the book, allowing the DTD declarations of XHTML and NCX
+/// files.
+fn parse_xml(text: &str) -> Result<Document<'_>, ParserError> {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    Ok(Document::parse_with_options(
+        text.trim_start_matches('\u{feff}'),
+        options,
+    )?)
+}
+
+/// Collects the text of an element, whitespace collapsed, if it holds any.
+fn element_text(node: Node) -> Option<String> {
+    let text = node
+        .descendants()
+        .filter(Node::is_text)
+        .filter_map(|node| node.text())
+        .collect::<String>();
+    let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_epub_success() {
+        let data = read_test_file("test_epub_1.epub");
+        let result = parse_epub(&data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            result.text,
+            "--- Chapter: Introduction ---
+Hello, this is a test epub for the parsing API.
+
+--- Chapter: The Second Chapter ---
+The second chapter, with emphasis.
+
+- First item
+- Second item"
+        );
+        assert_eq!(result.metadata[METADATA_TITLE], "Test Book");
+        assert_eq!(result.metadata[METADATA_AUTHOR], "Jane Doe, John Smith");
+        assert_eq!(result.metadata[METADATA_LANGUAGE], "en");
+    }
+
+    #[test]
+    fn ncx_links_success() {
+        let ncx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE ncx PUBLIC "-//NISO//DTD ncx 2005-1//EN" "http://www.daisy.org/z3986/2005/ncx-2005-1.dtd">
+<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/" version="2005-1">
+  <navMap>
+    <navPoint id="p1" playOrder="1">
+      <navLabel><text>Chapter  One</text></navLabel>
+      <content src="text/chapter1.xhtml"/>
+      <navPoint id="p2" playOrder="2">
+        <navLabel><text>Section</text></navLabel>
+        <content src="text/chapter1.xhtml#section"/>
+      </navPoint>
+    </navPoint>
+  </navMap>
+</ncx>"#;
+        let document = parse_xml(ncx).unwrap();
+
+        assert_eq!(
+            ncx_links(&document, "OEBPS"),
+            vec![
+                (
+                    "OEBPS/text/chapter1.xhtml".to_string(),
+                    "Chapter One".to_string()
+                ),
+                (
+                    "OEBPS/text/chapter1.xhtml".to_string(),
+                    "Section".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,588 @@
+//! HTML parser module.
+//!
+//! This module provides functionality for extracting text from HTML and XHTML
+//! documents using the `scraper` library. Scripts and styles are dropped, entities
+//! decoded, and block elements, lists and tables laid out as plain text or Markdown.
+
+use super::super::{
+    document::{METADATA_ENCODING, METADATA_FORMAT, METADATA_TITLE, ParsedDocument},
+    encoding::decode_text,
+    errors::ParserError,
+    options::{OutputFormat, ParseOptions, TableFormat},
+};
+use super::table::Table;
+use ego_tree::NodeRef;
+use encoding_rs::Encoding;
+use regex::bytes::Regex;
+use scraper::{ElementRef, Html, Node};
+
+/// Elements whose content is never part of the text
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "head", "title", "iframe", "object", "embed", "svg",
+    "canvas", "select",
+];
+
+/// Elements laid out as blocks, separated from their surroundings by blank lines
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "body",
+    "caption",
+    "dd",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "header",
+    "hgroup",
+    "html",
+    "li",
+    "main",
+    "nav",
+    "p",
+    "section",
+    "summary",
+    "td",
+    "th",
+    "tr",
+];
+
+/// Deepest nesting of elements laid out, bounding the recursion
+const MAX_NESTING_DEPTH: usize = 256;
+
+/// Number of leading bytes searched for a `<meta charset>` declaration
+const CHARSET_SNIFF_SIZE: usize = 1024;
+
+/// Parses an HTML file and extracts its text.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the HTML data
+/// * `options` - The parsing options, of which the `encoding` override, the
+///   `output_format`, `keep_links` and `table_format` are used
+///
+/// # Returns
+///
+/// * `Ok(ParsedDocument)` - The text of the document, with its title and encoding
+///   in the metadata
+/// * `Err(ParserError)` - If the encoding is unknown or the data isn't text
+///
+/// # Implementation Notes
+///
+/// * Without an explicit encoding or byte order mark, the `<meta charset>`
+///   declaration is honored before falling back to detection
+pub(crate) fn parse_html(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParserError> {
+    let (text, encoding) = decode_html(data, options.encoding.as_deref())?;
+
+    Ok(render_html(&text, options).with_metadata(METADATA_ENCODING, encoding.name()))
+}
+
+/// Decodes an HTML file, honoring its `<meta charset>` declaration.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the HTML data
+/// * `label` - The label of an encoding overriding the detection, if any
+///
+/// # Returns
+///
+/// * `Ok((String, &Encoding))` - The decoded text and the encoding it was decoded from
+/// * `Err(ParserError)` - If the encoding is unknown or the data isn't text
+pub(crate) fn decode_html(
+    data: &[u8],
+    label: Option<&str>,
+) -> Result<(String, &'static Encoding), ParserError> {
+    let label = match label {
+        Some(label) => Some(label.to_string()),
+        None if Encoding::for_bom(data).is_none() => sniff_meta_charset(data)?,
+        None => None,
+    };
+    decode_text(data, label.as_deref())
+}
+
+/// Renders decoded HTML as plain text or Markdown.
+///
+/// # Implementation Notes
+///
+/// * `script`, `style`, `head` and other non-content elements are skipped
+/// * Whitespace is collapsed outside `pre` elements, `br` elements break lines
+/// * Headings, emphasis, code, quotes and links become Markdown syntax in Markdown output
+/// * Link targets are kept only with the `keep_links` option, as `text (url)` in
+///   plain text output
+/// * Lists are rendered with `-` bullets or numbers, nested lists indented
+/// * Tables are rendered in the `table_format` option, or as Markdown tables in
+///   Markdown output
+pub(crate) fn render_html(text: &str, options: &ParseOptions) -> ParsedDocument {
+    let html = Html::parse_document(text);
+
+    let renderer = Renderer {
+        markdown: options.output_format == OutputFormat::Markdown,
+        keep_links: options.keep_links,
+        table_format: options.table_format,
+    };
+    let body = renderer.render_container(*html.root_element(), "\n\n");
+
+    let document = ParsedDocument::from(body).with_metadata(METADATA_FORMAT, "html");
+
+    let title = html
+        .root_element()
+        .descendent_elements()
+        .find(|element| element.value().name() == "title")
+        .map(|title| collapse_whitespace(&title.text().collect::<String>()));
+
+    match title {
+        Some(title) if !title.is_empty() => document.with_metadata(METADATA_TITLE, title),
+        _ => document,
+    }
+}
+
+/// Checks whether decoded text is an XHTML document, which `infer` reports as XML.
+pub(crate) fn is_xhtml(text: &str) -> bool {
+    let head = text.chars().take(CHARSET_SNIFF_SIZE).collect::<String>();
+    let head = head.to_ascii_lowercase();
+    head.trim_start().starts_with("<?xml") && head.contains("<html")
+}
+
+/// Finds the encoding declared by a `<meta charset>` element, if it is known.
+fn sniff_meta_charset(data: &[u8]) -> Result<Option<String>, ParserError> {
+    let pattern = Regex::new(r#"(?i)<meta[^>]+charset\s*=\s*["']?\s*([a-z0-9_:.-]+)"#)?;
+    let head = &data[..data.len().min(CHARSET_SNIFF_SIZE)];
+
+    Ok(pattern
+        .captures(head)
+        .and_then(|captures| Encoding::for_label(&captures[1]))
+        .map(|encoding| encoding.name().to_string()))
+}
+
+/// Lays out the nodes of an HTML document.
+struct Renderer {
+    /// Whether Markdown syntax is emitted
+    markdown: bool,
+    /// Whether link targets are kept
+    keep_links: bool,
+    /// Rendering of tables in plain text output
+    table_format: TableFormat,
+}
+
+/// Blocks of text being laid out, along with the inline text of the current block.
+#[derive(Default)]
+struct Blocks {
+    /// Completed blocks
+    blocks: Vec<String>,
+    /// Inline text of the block being built, with `\n` for line breaks
+    inline: String,
+}
+
+impl Blocks {
+    /// Completes the current inline block, if it holds any text.
+    fn flush(&mut self) {
+        let text = self
+            .inline
+            .split('\n')
+            .map(collapse_whitespace)
+            .collect::<Vec<String>>()
+            .join("\n");
+        let text = text.trim_matches('\n');
+        if !text.is_empty() {
+            self.blocks.push(text.to_string());
+        }
+        self.inline.clear();
+    }
+
+    /// Adds a block after the current inline block.
+    fn push_block(&mut self, block: String) {
+        self.flush();
+        if !block.trim().is_empty() {
+            self.blocks.push(block);
+        }
+    }
+
+    /// Joins the blocks with the given separator.
+    fn finish(mut self, separator: &str) -> String {
+        self.flush();
+        self.blocks.join(separator)
+    }
+}
+
+impl Renderer {
+    /// Renders the children of a node as blocks joined with the given separator.
+    fn render_container(&self, node: NodeRef<'_, Node>, separator: &str) -> String {
+        let mut blocks = Blocks::default();
+        for child in node.children() {
+            self.walk(child, &mut blocks);
+        }
+        blocks.finish(separator)
+    }
+
+    /// Lays out a node in the blocks being built, nodes nested deeper than
+    /// [`MAX_NESTING_DEPTH`] being skipped.
+    fn walk(&self, node: NodeRef<'_, Node>, blocks: &mut Blocks) {
+        if node.ancestors().nth(MAX_NESTING_DEPTH).is_some() {
+            return;
+        }
+        match node.value() {
+            Node::Text(text) => {
+                // Whitespace, newlines included, only separates words outside `pre`
+                blocks.inline.extend(
+                    text.chars()
+                        .map(|c| if c.is_whitespace() { ' ' } else { c }),
+                );
+            }
+            Node::Element(_) => {
+                let Some(element) = ElementRef::wrap(node) else {
+                    return;
+                };
+                self.walk_element(element, element.value().name(), blocks);
+            }
+            Node::Document | Node::Fragment => {
+                for child in node.children() {
+                    self.walk(child, blocks);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Lays out an element in the blocks being built.
+    fn walk_element(&self, element: ElementRef<'_>, name: &str, blocks: &mut Blocks) {
+        match name {
+            name if SKIPPED_ELEMENTS.contains(&name) => {}
+            "br" => blocks.inline.push('\n'),
+            "hr" => blocks.push_block(if self.markdown { "---" } else { "" }.to_string()),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let text = collapse_whitespace(&self.render_container(*element, " "));
+                if self.markdown && !text.is_empty() {
+                    let level = usize::from(name.as_bytes()[1] - b'0');
+                    blocks.push_block(format!("{} {text}", "#".repeat(level)));
+                } else {
+                    blocks.push_block(text);
+                }
+            }
+            "ul" | "ol" | "menu" => blocks.push_block(self.render_list(element, name == "ol")),
+            "table" => blocks.push_block(self.render_table(element)),
+            "pre" => {
+                let text = element.text().collect::<String>();
+                let text = text.trim_matches('\n');
+                if self.markdown {
+                    blocks.push_block(format!("```\n{text}\n```"));
+                } else {
+                    blocks.push_block(text.to_string());
+                }
+            }
+            "blockquote" => {
+                let text = self.render_container(*element, "\n\n");
+                if self.markdown {
+                    blocks.push_block(prefix_lines(&text, "> ", "> "));
+                } else {
+                    blocks.push_block(text);
+                }
+            }
+            "a" => {
+                let href = element.attr("href").map(str::trim).filter(|href| {
+                    !href.is_empty() && !href.starts_with('#') && !href.starts_with("javascript:")
+                });
+                self.walk_inline(element, blocks, |text| match href {
+                    Some(href) if self.keep_links && self.markdown => format!("[{text}]({href})"),
+                    Some(href) if self.keep_links && text != href => format!("{text} ({href})"),
+                    _ => text.to_string(),
+                });
+            }
+            "img" if self.markdown && self.keep_links => {
+                if let Some(src) = element.attr("src") {
+                    let alt = element.attr("alt").unwrap_or_default();
+                    blocks
+                        .inline
+                        .push_str(&["![", alt, "](", src, ")"].concat());
+                }
+            }
+            "strong" | "b" if self.markdown => {
+                self.walk_inline(element, blocks, |text| format!("**{text}**"));
+            }
+            "em" | "i" if self.markdown => {
+                self.walk_inline(element, blocks, |text| format!("*{text}*"));
+            }
+            "code" | "kbd" | "samp" if self.markdown => {
+                self.walk_inline(element, blocks, |text| format!("`{text}`"));
+            }
+            name if BLOCK_ELEMENTS.contains(&name) => {
+                blocks.push_block(self.render_container(*element, "\n\n"));
+            }
+            _ => {
+                for child in element.children() {
+                    self.walk(child, blocks);
+                }
+            }
+        }
+    }
+
+    /// Lays out an inline element whose text is decorated, e.g. with Markdown emphasis.
+    ///
+    /// Elements holding blocks, such as links wrapping whole cards, are laid out
+    /// as blocks without decoration.
+    fn walk_inline(
+        &self,
+        element: ElementRef<'_>,
+        blocks: &mut Blocks,
+        decorate: impl Fn(&str) -> String,
+    ) {
+        let text = self.render_container(*element, "\n\n");
+        if text.contains("\n\n") {
+            blocks.push_block(text);
+            return;
+        }
+        if text.is_empty() {
+            return;
+        }
+
+        // Keep the spaces separating the element from the surrounding words
+        let raw = element.text().collect::<String>();
+        if raw.starts_with(char::is_whitespace) {
+            blocks.inline.push(' ');
+        }
+        blocks.inline.push_str(&decorate(&text));
+        if raw.ends_with(char::is_whitespace) {
+            blocks.inline.push(' ');
+        }
+    }
+
+    /// Renders a list with bullets or numbers, indenting the continuation lines.
+    fn render_list(&self, list: ElementRef<'_>, ordered: bool) -> String {
+        let start = list
+            .attr("start")
+            .and_then(|start| start.trim().parse::<i64>().ok())
+            .unwrap_or(1);
+
+        list.child_elements()
+            .filter(|item| item.value().name() == "li")
+            .zip(start..)
+            .filter_map(|(item, number)| {
+                let text = self.render_container(*item, "\n");
+                if text.trim().is_empty() {
+                    return None;
+                }
+                let marker = if ordered {
+                    format!("{number}. ")
+                } else {
+                    "- ".to_string()
+                };
+                let indent = " ".repeat(marker.len());
+                Some(prefix_lines(&text, &marker, &indent))
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Renders a table, with the `th` cells of its first row as header.
+    fn render_table(&self, table: ElementRef<'_>) -> String {
+        let rows = table
+            .child_elements()
+            .flat_map(|child| match child.value().name() {
+                "thead" | "tbody" | "tfoot" => child.child_elements().collect::<Vec<_>>(),
+                _ => vec![child],
+            })
+            .filter(|row| row.value().name() == "tr")
+            .map(|row| {
+                row.child_elements()
+                    .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cells| !cells.is_empty())
+            .collect::<Vec<_>>();
+
+        let has_header = rows
+            .first()
+            .is_some_and(|cells| cells.iter().all(|cell| cell.value().name() == "th"));
+        let rows = rows
+            .iter()
+            .map(|cells| {
+                cells
+                    .iter()
+                    .map(|cell| collapse_whitespace(&self.render_container(**cell, " ")))
+                    .collect()
+            })
+            .collect();
+
+        let table = Table { rows, has_header };
+        let format = if self.markdown {
+            TableFormat::Markdown
+        } else {
+            self.table_format
+        };
+        // Rendering only fails on serialization errors, which cannot happen with strings
+        table.render(format).unwrap_or_default()
+    }
+}
+
+/// Replaces runs of whitespace with a single space and trims the result.
+pub(crate) fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Prefixes the first line of a text with a marker and the following lines with an indent.
+fn prefix_lines(text: &str, marker: &str, indent: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let prefix = if i == 0 { marker } else { indent };
+            if line.is_empty() {
+                prefix.trim_end().to_string()
+            } else {
+                format!("{prefix}{line}")
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    fn markdown_options() -> ParseOptions {
+        ParseOptions {
+            output_format: OutputFormat::Markdown,
+            keep_links: true,
+            ..ParseOptions::default()
+        }
+    }
+
+    #[test]
+    fn parse_html_success() {
+        let data = read_test_file("test_html_1.html");
+        let result = parse_html(&data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            result.text,
+            "Test Page
+
+Hello, this is a test html for the parsing API.
+
+Features:
+
+- Scripts & styles are removed
+- Entities are decoded
+
+Name,Value
+alpha,1
+beta,2
+
+Visit the website for more."
+        );
+        assert_eq!(result.metadata[METADATA_TITLE], "Test HTML");
+        assert_eq!(result.metadata[METADATA_FORMAT], "html");
+    }
+
+    #[test]
+    fn parse_html_markdown_success() {
+        let data = read_test_file("test_html_1.html");
+        let result = parse_html(&data, &markdown_options()).unwrap();
+
+        assert_eq!(
+            result.text,
+            "# Test Page
+
+Hello, this is a **test html** for the *parsing* API.
+
+## Features:
+
+- Scripts & styles are removed
+- Entities are decoded
+
+| Name | Value |
+| --- | --- |
+| alpha | 1 |
+| beta | 2 |
+
+Visit the [website](https://example.com) for more."
+        );
+    }
+
+    #[test]
+    fn render_deeply_nested() {
+        let depth = 100_000;
+        let html = format!(
+            "<p>Top</p>{}deep{}<p>End</p>",
+            "<span>".repeat(depth),
+            "</span>".repeat(depth)
+        );
+        let result = render_html(&html, &ParseOptions::default());
+
+        assert_eq!(result.text, "Top\n\nEnd");
+    }
+
+    #[test]
+    fn render_links_in_text() {
+        let options = ParseOptions {
+            keep_links: true,
+            ..ParseOptions::default()
+        };
+        let result = render_html(
+            "<p>See <a href=\"https://example.com\">the docs</a> or \
+<a href=\"https://example.org\">https://example.org</a>.</p>",
+            &options,
+        );
+
+        assert_eq!(
+            result.text,
+            "See the docs (https://example.com) or https://example.org."
+        );
+    }
+
+    #[test]
+    fn render_nested_lists_and_breaks() {
+        let result = render_html(
+            "<ol start=\"3\"><li>First<ul><li>Nested</li></ul></li><li>Second</li></ol>\
+<p>Line one<br>Line   two</p><pre>  keep\n    spacing</pre>",
+            &ParseOptions::default(),
+        );
+
+        assert_eq!(
+            result.text,
+            "3. First
+   - Nested
+4. Second
+
+Line one
+Line two
+
+  keep
+    spacing"
+        );
+    }
+
+    #[test]
+    fn parse_html_meta_charset() {
+        let data = b"<html><head><meta charset=\"windows-1252\"></head><body><p>Fran\xE7ois</p></body></html>";
+        let result = parse_html(data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(result.text, "François");
+        assert_eq!(result.metadata[METADATA_ENCODING], "windows-1252");
+    }
+
+    #[test]
+    fn is_xhtml_success() {
+        assert!(is_xhtml(
+            "<?xml version=\"1.0\"?>\n<html xmlns=\"http://www.w3.org/1999/xhtml\"></html>"
+        ));
+        assert!(!is_xhtml("<?xml version=\"1.0\"?>\n<note></note>"));
+    }
+}
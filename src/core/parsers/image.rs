@@ -4,7 +4,7 @@
 //! Optical Character Recognition (OCR) via the Tesseract engine. It supports
 //! various image formats including PNG, JPEG, and WebP.
 
-use super::super::errors::ParserError;
+use super::super::{errors::ParserError, provenance::BoundingBox};
 use std::sync::LazyLock;
 use std::{fs, io::Write};
 use tempfile::{NamedTempFile, TempDir};
@@ -54,8 +54,7 @@ static TESSDATA_DIR: LazyLock<TempDir> = LazyLock::new(|| {
 /// * Training data is embedded in the binary for portability
 pub(crate) fn parse_image(data: &[u8]) -> Result<String, ParserError> {
     // Create a temporary file, from the data, to be used by the ocr engine
-    let mut temp_file = NamedTempFile::new()?;
-    temp_file.write_all(data)?;
+    let temp_file = write_temp_file(data)?;
     let temp_file_path = temp_file
         .path()
         .to_str()
@@ -67,6 +66,94 @@ pub(crate) fn parse_image(data: &[u8]) -> Result<String, ParserError> {
     Ok(text.trim().to_string())
 }
 
+/// Recognises the lines of text of an image, along with their bounding boxes.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the image data (PNG, JPEG, WebP, etc.)
+///
+/// # Returns
+///
+/// * `Ok(Vec<(String, BoundingBox)>)` - The lines in reading order, with their
+///   bounding boxes in pixels from the top left corner of the image
+/// * `Err(ParserError)` - If an error occurs during image processing or OCR
+pub(crate) fn recognize_lines(data: &[u8]) -> Result<Vec<(String, BoundingBox)>, ParserError> {
+    let temp_file = write_temp_file(data)?;
+    let temp_file_path = temp_file
+        .path()
+        .to_str()
+        .ok_or_else(|| ParserError::IoError("Invalid path string".to_string()))?;
+
+    let tsv = init_tesseract()?
+        .set_image(temp_file_path)?
+        .get_tsv_text(0)?;
+
+    Ok(read_tsv_lines(&tsv))
+}
+
+/// Writes data to a temporary file, to be read by the OCR engine.
+fn write_temp_file(data: &[u8]) -> Result<NamedTempFile, ParserError> {
+    let mut temp_file = NamedTempFile::new()?;
+    temp_file.write_all(data)?;
+    Ok(temp_file)
+}
+
+/// Groups the words of the TSV output of Tesseract into lines, their bounding boxes
+/// enclosing those of their words.
+///
+/// Each row holds the level, page, block, paragraph, line and word numbers, then the
+/// left, top, width and height of the element, its confidence and its text. Only
+/// word rows, of level 5, hold text.
+fn read_tsv_lines(tsv: &str) -> Vec<(String, BoundingBox)> {
+    let mut lines: Vec<((&str, &str, &str), String, BoundingBox)> = Vec::new();
+
+    for row in tsv.lines() {
+        let fields: Vec<&str> = row.split('\t').collect();
+        let [
+            level,
+            _,
+            block,
+            paragraph,
+            line,
+            _,
+            left,
+            top,
+            width,
+            height,
+            _,
+            text,
+        ] = fields[..]
+        else {
+            continue;
+        };
+        let text = text.trim();
+        if level != "5" || text.is_empty() {
+            continue;
+        }
+        let (Ok(left), Ok(top), Ok(width), Ok(height)) =
+            (left.parse(), top.parse(), width.parse(), height.parse())
+        else {
+            continue;
+        };
+        let word = BoundingBox::new(left, top, width, height);
+
+        let key = (block, paragraph, line);
+        match lines.last_mut() {
+            Some((last, line, bbox)) if *last == key => {
+                line.push(' ');
+                line.push_str(text);
+                *bbox = bbox.union(&word);
+            }
+            _ => lines.push((key, text.to_string(), word)),
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|(_, text, bbox)| (text, bbox))
+        .collect()
+}
+
 /// Internal function that performs OCR using Tesseract.
 ///
 /// # Arguments
@@ -78,18 +165,21 @@ pub(crate) fn parse_image(data: &[u8]) -> Result<String, ParserError> {
 /// * `Ok(String)` - The extracted text
 /// * `Err(ParserError)` - If an error occurs with Tesseract
 fn parse_with_tesseract(path: &str) -> Result<String, ParserError> {
+    // Perform OCR
+    let text = init_tesseract()?.set_image(path)?.get_text()?;
+
+    Ok(text)
+}
+
+/// Initializes Tesseract with the embedded English and French training data.
+fn init_tesseract() -> Result<Tesseract, ParserError> {
     // Get the path to the tessdata directory
     let tessdata_dir = TESSDATA_DIR.path().to_str().ok_or_else(|| {
         ParserError::IoError("Unable to find training data directory".to_string())
     })?;
 
     // Initialize Tesseract with English and French languages
-    let tes = Tesseract::new(Some(tessdata_dir), Some("eng+fra"))?;
-
-    // Perform OCR
-    let text = tes.set_image(path)?.get_text()?;
-
-    Ok(text)
+    Ok(Tesseract::new(Some(tessdata_dir), Some("eng+fra"))?)
 }
 
 #[cfg(test)]
@@ -149,4 +239,29 @@ mod tests {
                 .to_string()
         );
     }
+
+    #[test]
+    fn read_tsv_lines_success() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext
+1\t1\t0\t0\t0\t0\t0\t0\t640\t480\t-1\t
+4\t1\t1\t1\t1\t0\t10\t20\t200\t30\t-1\t
+5\t1\t1\t1\t1\t1\t10\t20\t80\t30\t96.5\tHello
+5\t1\t1\t1\t1\t2\t100\t22\t110\t28\t95.1\tWorld!
+5\t1\t1\t1\t2\t1\t10\t60\t90\t25\t93.0\t123456789
+5\t1\t1\t1\t2\t2\t110\t60\t10\t25\t12.0\t ";
+
+        assert_eq!(
+            read_tsv_lines(tsv),
+            vec![
+                (
+                    "Hello World!".to_string(),
+                    BoundingBox::new(10.0, 20.0, 200.0, 30.0)
+                ),
+                (
+                    "123456789".to_string(),
+                    BoundingBox::new(10.0, 60.0, 90.0, 25.0)
+                ),
+            ]
+        );
+    }
 }
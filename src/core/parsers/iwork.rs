@@ -0,0 +1,522 @@
+//! Apple iWork parser module.
+//!
+//! This module provides functionality for extracting text from Pages, Numbers and
+//! Keynote files. Since iWork 2013 their content is stored in IWA files: streams of
+//! Snappy compressed Protocol Buffers messages, whose text storages are decoded here
+//! without their schemas. Documents without decodable text, such as spreadsheets and
+//! files of older iWork versions, fall back to the preview embedded in the package,
+//! parsed by the PDF parser or recognised by OCR.
+
+use super::super::{
+    constants::{APPLICATION_KEYNOTE, APPLICATION_NUMBERS, APPLICATION_PAGES},
+    document::{METADATA_FORMAT, ParsedDocument},
+    errors::ParserError,
+    options::ParseOptions,
+};
+use super::image::parse_image;
+use super::package::{Package, open_package, read_part};
+use super::pdf::parse_pdf;
+use flate2::read::GzDecoder;
+use mime::Mime;
+use snap::raw::{Decoder, decompress_len};
+use std::io::Read;
+use std::path::Path;
+
+/// Path of the IWA file holding the main content of a document
+const DOCUMENT_PATH: &str = "Index/Document.iwa";
+
+/// Prefix of the paths of the IWA files holding the slides of a presentation
+const SLIDE_PREFIX: &str = "Index/Slide-";
+
+/// Previews of the document, by order of preference
+const PREVIEW_PATHS: [&str; 4] = [
+    "QuickLook/Preview.pdf",
+    "preview.jpg",
+    "QuickLook/Thumbnail.jpg",
+    "preview-web.jpg",
+];
+
+/// Message types of the `TSWP.StorageArchive` text storages
+const STORAGE_TYPES: [u64; 2] = [2001, 2005];
+
+/// Kind of the text storages holding speaker notes
+const NOTE_KIND: u64 = 4;
+
+/// Kind of the text storages holding tables of contents, which repeat the headings
+const CONTENTS_KIND: u64 = 7;
+
+/// Kind of a text storage without an explicit kind
+const DEFAULT_KIND: u64 = 3;
+
+/// Largest decompressed IWA file, the chunks declaring their own length
+const MAX_STREAM_SIZE: usize = 256 * 1024 * 1024;
+
+/// Applications of the iWork suite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Application {
+    Pages,
+    Numbers,
+    Keynote,
+}
+
+impl Application {
+    /// Recognises the application of a document from the files of its package.
+    ///
+    /// Documents of iWork 2013 and later have an `Index` directory of IWA files,
+    /// while those of iWork '09 have an `index.xml` (or `index.apxl` for Keynote)
+    /// file, optionally gzip compressed.
+    fn detect(package: &mut Package) -> Option<Self> {
+        let names: Vec<String> = package.file_names().map(str::to_string).collect();
+        let has = |prefix: &str| names.iter().any(|name| name.starts_with(prefix));
+
+        if has(SLIDE_PREFIX) || has("index.apxl") {
+            Some(Self::Keynote)
+        } else if has("Index/Tables/") || has("Index/CalculationEngine") {
+            Some(Self::Numbers)
+        } else if has(DOCUMENT_PATH) {
+            Some(Self::Pages)
+        } else if let Some(index) = names
+            .iter()
+            .find(|name| *name == "index.xml" || *name == "index.xml.gz")
+        {
+            // Pages and Numbers documents are told apart by their root element
+            let mut head = Vec::new();
+            let file = package.by_name(index).ok()?;
+            let reader: Box<dyn Read> = if index == "index.xml.gz" {
+                Box::new(GzDecoder::new(file))
+            } else {
+                Box::new(file)
+            };
+            reader.take(4096).read_to_end(&mut head).ok()?;
+            let head = String::from_utf8_lossy(&head);
+            Some(if head.contains("<ls:document") {
+                Self::Numbers
+            } else {
+                Self::Pages
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Finds the application whose documents have a MIME type.
+    fn from_mime(mime: &Mime) -> Option<Self> {
+        match mime.essence_str() {
+            APPLICATION_PAGES => Some(Self::Pages),
+            APPLICATION_NUMBERS => Some(Self::Numbers),
+            APPLICATION_KEYNOTE => Some(Self::Keynote),
+            _ => None,
+        }
+    }
+
+    /// MIME type of the documents of the application.
+    fn mime(self) -> &'static str {
+        match self {
+            Self::Pages => APPLICATION_PAGES,
+            Self::Numbers => APPLICATION_NUMBERS,
+            Self::Keynote => APPLICATION_KEYNOTE,
+        }
+    }
+
+    /// Name of the format, as reported in the metadata.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Pages => "pages",
+            Self::Numbers => "numbers",
+            Self::Keynote => "keynote",
+        }
+    }
+}
+
+/// Returns the MIME type of an iWork document, if a ZIP archive is one.
+pub(crate) fn iwork_type(data: &[u8]) -> Option<&'static str> {
+    let mut package = open_package(data).ok()?;
+    Application::detect(&mut package).map(Application::mime)
+}
+
+/// Checks whether a MIME type is the type of an iWork document.
+pub(crate) fn is_iwork(mime: &Mime) -> bool {
+    Application::from_mime(mime).is_some()
+}
+
+/// Parses a Pages, Numbers or Keynote file and extracts its text.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the iWork document
+///
+/// # Returns
+///
+/// * `Ok(ParsedDocument)` - The extracted text, with the application in the format
+///   metadata entry (`pages`, `numbers` or `keynote`)
+/// * `Err(ParserError)` - If the package can't be read, or the document has neither
+///   text nor preview
+///
+/// # Implementation Notes
+///
+/// * Pages documents are read from their `Index/Document.iwa` file, one paragraph
+///   per line, without their tables of contents
+/// * Keynote slides are read from their `Index/Slide-*.iwa` files, with the same
+///   separators as PPTX presentations and their speaker notes after a
+///   `--- Notes ---` separator
+/// * Numbers spreadsheets and documents without text are parsed from their preview:
+///   `QuickLook/Preview.pdf` through the PDF parser, `preview.jpg` or
+///   `QuickLook/Thumbnail.jpg` through OCR
+pub(crate) fn parse_iwork(data: &[u8]) -> Result<ParsedDocument, ParserError> {
+    let mut package = open_package(data)?;
+    let application = Application::detect(&mut package)
+        .ok_or_else(|| ParserError::InvalidFormat("Not an iWork document".to_string()))?;
+
+    let text = match application {
+        Application::Pages => read_pages(&mut package)?,
+        Application::Keynote => read_keynote(&mut package)?,
+        Application::Numbers => String::new(),
+    };
+    let text = if text.is_empty() {
+        read_preview(&mut package)?
+    } else {
+        text
+    };
+
+    Ok(ParsedDocument::from(text).with_metadata(METADATA_FORMAT, application.name()))
+}
+
+/// Reads the text storages of a Pages document.
+fn read_pages(package: &mut Package) -> Result<String, ParserError> {
+    if package.index_for_name(DOCUMENT_PATH).is_none() {
+        return Ok(String::new());
+    }
+    let stream = decompress(&read_part(package, DOCUMENT_PATH)?)?;
+
+    let paragraphs: Vec<String> = read_storages(&stream)?
+        .into_iter()
+        .filter(|(kind, _)| *kind != CONTENTS_KIND)
+        .map(|(_, text)| text)
+        .collect();
+
+    Ok(paragraphs.join("\n"))
+}
+
+/// Reads the text storages of the slides of a Keynote presentation.
+fn read_keynote(package: &mut Package) -> Result<String, ParserError> {
+    let mut paths: Vec<String> = package
+        .file_names()
+        .filter(|name| {
+            name.starts_with(SLIDE_PREFIX)
+                && Path::new(name)
+                    .extension()
+                    .is_some_and(|extension| extension == "iwa")
+        })
+        .map(str::to_string)
+        .collect();
+    // Slide identifiers grow as slides are added
+    paths.sort_by_key(|path| (slide_number(path), path.clone()));
+
+    let mut lines = Vec::new();
+    for (index, path) in paths.iter().enumerate() {
+        if index > 0 {
+            lines.push(String::new());
+            lines.push(format!("--- Slide {} ---", index + 1));
+        }
+
+        let stream = decompress(&read_part(package, path)?)?;
+        let (notes, texts): (Vec<_>, Vec<_>) = read_storages(&stream)?
+            .into_iter()
+            .partition(|(kind, _)| *kind == NOTE_KIND);
+        lines.extend(texts.into_iter().map(|(_, text)| text));
+        if !notes.is_empty() {
+            lines.push("--- Notes ---".to_string());
+            lines.extend(notes.into_iter().map(|(_, text)| text));
+        }
+    }
+
+    Ok(lines.join("\n").trim().to_string())
+}
+
+/// Parses the first preview of a document found in its package.
+fn read_preview(package: &mut Package) -> Result<String, ParserError> {
+    let path = PREVIEW_PATHS
+        .into_iter()
+        .find(|path| package.index_for_name(path).is_some())
+        .ok_or_else(|| {
+            ParserError::ParseError("iWork document without text or preview".to_string())
+        })?;
+    let preview = read_part(package, path)?;
+
+    if Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension == "pdf")
+    {
+        parse_pdf(&preview, &ParseOptions::default())
+    } else {
+        parse_image(&preview)
+    }
+}
+
+/// Returns the identifier in the path of a slide file, e.g. 970 for
+/// `Index/Slide-970.iwa`.
+fn slide_number(path: &str) -> u64 {
+    path.trim_start_matches(SLIDE_PREFIX)
+        .trim_end_matches(".iwa")
+        .parse()
+        .unwrap_or(u64::MAX)
+}
+
+/// Decompresses an IWA file, a sequence of Snappy compressed chunks each preceded by
+/// a zero byte and its 24-bit little-endian length, failing once the decompressed
+/// file would exceed [`MAX_STREAM_SIZE`].
+fn decompress(data: &[u8]) -> Result<Vec<u8>, ParserError> {
+    let mut decoder = Decoder::new();
+    let mut stream = Vec::new();
+    let mut rest = data;
+
+    while let [kind, low, middle, high, chunk @ ..] = rest {
+        let length = usize::from(*low) | usize::from(*middle) << 8 | usize::from(*high) << 16;
+        if *kind != 0 || chunk.len() < length {
+            return Err(ParserError::ParseError("Invalid IWA chunk".to_string()));
+        }
+        let compressed = &chunk[..length];
+        if stream.len() + decompress_len(compressed)? > MAX_STREAM_SIZE {
+            return Err(ParserError::ParseError(format!(
+                "IWA file larger than {MAX_STREAM_SIZE} bytes"
+            )));
+        }
+        stream.extend(decoder.decompress_vec(compressed)?);
+        rest = &chunk[length..];
+    }
+
+    Ok(stream)
+}
+
+/// Reads the text storages of a decompressed IWA stream, with their kind.
+///
+/// The stream is a sequence of archives, each made of the length of its
+/// `TSP.ArchiveInfo` header, the header, and the messages it describes.
+fn read_storages(stream: &[u8]) -> Result<Vec<(u64, String)>, ParserError> {
+    let invalid = || ParserError::ParseError("Invalid IWA archive".to_string());
+    let mut reader = Reader { data: stream };
+    let mut storages = Vec::new();
+
+    while !reader.data.is_empty() {
+        let length = reader.varint().ok_or_else(invalid)?;
+        let header = reader.bytes(length).ok_or_else(invalid)?;
+
+        // The `message_infos` of the header give the type and length of each message
+        for (_, info) in (Reader { data: header }).filter(|(number, _)| *number == 2) {
+            let Value::Bytes(info) = info else {
+                return Err(invalid());
+            };
+            let (mut kind, mut length) = (0, 0);
+            for (number, value) in (Reader { data: info }) {
+                match (number, value) {
+                    (1, Value::Varint(value)) => kind = value,
+                    (3, Value::Varint(value)) => length = value,
+                    _ => {}
+                }
+            }
+
+            let payload = reader.bytes(length).ok_or_else(invalid)?;
+            if STORAGE_TYPES.contains(&kind) {
+                storages.extend(read_storage(payload));
+            }
+        }
+    }
+
+    Ok(storages)
+}
+
+/// Reads the kind and text of a `TSWP.StorageArchive` message, if it has text.
+fn read_storage(payload: &[u8]) -> Option<(u64, String)> {
+    let mut kind = DEFAULT_KIND;
+    let mut text = String::new();
+
+    for (number, value) in (Reader { data: payload }) {
+        match (number, value) {
+            (1, Value::Varint(value)) => kind = value,
+            (3, Value::Bytes(value)) => text.push_str(&String::from_utf8_lossy(value)),
+            _ => {}
+        }
+    }
+
+    let text = clean_text(&text);
+    (!text.is_empty()).then_some((kind, text))
+}
+
+/// Converts the characters of a text storage: paragraph, line, page and column
+/// breaks become new lines, while attachment markers and other control characters
+/// are dropped.
+fn clean_text(text: &str) -> String {
+    let text: String = text
+        .chars()
+        .filter_map(|character| match character {
+            '\u{2028}' | '\u{2029}' | '\u{4}' | '\u{5}' | '\u{c}' => Some('\n'),
+            '\n' | '\t' => Some(character),
+            '\u{fffc}' => None,
+            _ if character.is_control() => None,
+            _ => Some(character),
+        })
+        .collect();
+
+    text.lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A value of a Protocol Buffers field.
+enum Value<'a> {
+    /// A varint, of the integer, boolean and enumeration fields
+    Varint(u64),
+    /// A length-delimited value, of the string, bytes and message fields
+    Bytes(&'a [u8]),
+    /// A fixed-size value, of the fixed and floating point fields
+    Fixed,
+}
+
+/// A reader of Protocol Buffers messages, iterating over their fields.
+///
+/// Iteration stops at the end of the message or at the first malformed field.
+struct Reader<'a> {
+    /// Data left to read
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Reads a base 128 varint.
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0;
+        for (index, byte) in self.data.iter().enumerate().take(10) {
+            value |= u64::from(byte & 0x7f) << (7 * index);
+            if byte & 0x80 == 0 {
+                self.data = &self.data[index + 1..];
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    /// Reads a number of bytes.
+    fn bytes(&mut self, length: u64) -> Option<&'a [u8]> {
+        let length = usize::try_from(length).ok()?;
+        let (value, rest) = self.data.split_at_checked(length)?;
+        self.data = rest;
+        Some(value)
+    }
+}
+
+impl<'a> Iterator for Reader<'a> {
+    type Item = (u64, Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let key = self.varint()?;
+        let value = match key & 0x7 {
+            0 => Value::Varint(self.varint()?),
+            1 => self.bytes(8).map(|_| Value::Fixed)?,
+            2 => {
+                let length = self.varint()?;
+                Value::Bytes(self.bytes(length)?)
+            }
+            5 => self.bytes(4).map(|_| Value::Fixed)?,
+            // Deprecated groups are not used by iWork
+            _ => {
+                self.data = &[];
+                return None;
+            }
+        };
+        Some((key >> 3, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_pages_success() {
+        let document = parse_iwork(&read_test_file("test_pages_1.pages")).unwrap();
+
+        assert_eq!(
+            document.text,
+            "Test Pages Document
+Hello, this is a test pages for the parsing API.
+A line break
+and an attachment.
+Page header"
+        );
+        assert_eq!(document.metadata[METADATA_FORMAT], "pages");
+    }
+
+    #[test]
+    fn parse_keynote_success() {
+        let document = parse_iwork(&read_test_file("test_key_1.key")).unwrap();
+
+        assert_eq!(
+            document.text,
+            "Test Keynote
+Hello, this is a test key for the parsing API.
+--- Notes ---
+Speaker notes
+
+--- Slide 2 ---
+Second slide"
+        );
+        assert_eq!(document.metadata[METADATA_FORMAT], "keynote");
+    }
+
+    #[test]
+    fn parse_numbers_preview() {
+        let document = parse_iwork(&read_test_file("test_numbers_1.numbers")).unwrap();
+
+        assert_eq!(
+            document.text,
+            "Hello, this is a test numbers for the parsing API."
+        );
+        assert_eq!(document.metadata[METADATA_FORMAT], "numbers");
+    }
+
+    #[test]
+    fn iwork_type_success() {
+        assert_eq!(
+            iwork_type(&read_test_file("test_pages_1.pages")),
+            Some(APPLICATION_PAGES)
+        );
+        assert_eq!(
+            iwork_type(&read_test_file("test_key_1.key")),
+            Some(APPLICATION_KEYNOTE)
+        );
+        assert_eq!(
+            iwork_type(&read_test_file("test_numbers_1.numbers")),
+            Some(APPLICATION_NUMBERS)
+        );
+        assert_eq!(iwork_type(&read_test_file("test_zip_1.zip")), None);
+    }
+
+    #[test]
+    fn decompress_too_large() {
+        // A chunk declaring 4 GiB of decompressed data
+        let data = [0, 5, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+
+        assert!(matches!(decompress(&data), Err(ParserError::ParseError(_))));
+    }
+
+    #[test]
+    fn clean_text_success() {
+        assert_eq!(
+            clean_text("Title\u{2029}Line\u{2028}break \u{fffc}\u{1}\n\nEnd"),
+            "Title\nLine\nbreak\nEnd"
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! Markup language parser module.
+//!
+//! This module provides functionality for converting markup languages (Markdown,
+//! reStructuredText, AsciiDoc and LaTeX) to readable plain text. Markup syntax is
+//! stripped while headings, paragraphs and list structure are kept. `BibTeX`
+//! bibliographies are rendered as citation lines.
+
+mod asciidoc;
+mod bibtex;
+mod latex;
+mod markdown;
+mod rst;
+
+use super::super::{
+    document::{METADATA_FORMAT, METADATA_TITLE, ParsedDocument},
+    errors::ParserError,
+    options::ParseOptions,
+};
+
+/// Markup languages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MarkupFormat {
+    /// Markdown, including GitHub Flavored Markdown extensions
+    Markdown,
+    /// reStructuredText
+    Rst,
+    /// AsciiDoc
+    AsciiDoc,
+    /// LaTeX
+    Latex,
+    /// `BibTeX` bibliography
+    BibTeX,
+}
+
+impl MarkupFormat {
+    /// Finds the markup language matching a file extension.
+    pub(crate) fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "md" | "markdown" | "mdown" | "mkd" | "mkdn" => Some(Self::Markdown),
+            "rst" | "rest" => Some(Self::Rst),
+            "adoc" | "asciidoc" => Some(Self::AsciiDoc),
+            "tex" | "latex" | "ltx" => Some(Self::Latex),
+            "bib" => Some(Self::BibTeX),
+            _ => None,
+        }
+    }
+
+    /// Recognises a LaTeX document from its `\documentclass`, or a `BibTeX`
+    /// bibliography from its first entry, comments being skipped.
+    pub(crate) fn sniff(text: &str) -> Option<Self> {
+        let line = text
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('%'))?;
+
+        if line.starts_with("\\documentclass") {
+            return Some(Self::Latex);
+        }
+        let kind = line.strip_prefix('@')?;
+        let length = kind
+            .find(|character: char| !character.is_ascii_alphabetic())
+            .unwrap_or(kind.len());
+        (length > 0 && kind[length..].trim_start().starts_with(['{', '('])).then_some(Self::BibTeX)
+    }
+
+    /// Name of the format, as reported in the metadata.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Rst => "rst",
+            Self::AsciiDoc => "asciidoc",
+            Self::Latex => "latex",
+            Self::BibTeX => "bibtex",
+        }
+    }
+}
+
+/// Text converted from a markup language.
+struct Converted {
+    /// Lines of text, blank lines separating blocks
+    lines: Vec<String>,
+    /// Title of the document, if any
+    title: Option<String>,
+}
+
+/// Converts a markup document to plain text.
+///
+/// # Arguments
+///
+/// * `text` - The decoded content of the file
+/// * `format` - The markup language of the content
+/// * `options` - The parsing options, of which `skip_code_blocks`, `keep_links`,
+///   `table_format` and `math_mode` are used
+///
+/// # Returns
+///
+/// * `Ok(ParsedDocument)` - The plain text, with the format and title in the metadata
+/// * `Err(ParserError)` - If an error occurs during the conversion
+///
+/// # Implementation Notes
+///
+/// * Headings are kept on their own line, without their markers
+/// * List items are rendered with `-` bullets or numbers, nested items indented
+/// * Emphasis, inline code and link syntax are reduced to their text
+/// * Code blocks are kept as is unless the `skip_code_blocks` option is set
+/// * Formulas of LaTeX documents are kept as their source unless the `math_mode`
+///   option replaces them with placeholders
+pub(crate) fn parse_markup(
+    text: &str,
+    format: MarkupFormat,
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParserError> {
+    let converted = match format {
+        MarkupFormat::Markdown => markdown::convert_markdown(text, options)?,
+        MarkupFormat::Rst => rst::convert_rst(text, options)?,
+        MarkupFormat::AsciiDoc => asciidoc::convert_asciidoc(text, options)?,
+        MarkupFormat::Latex => latex::convert_latex(text, options)?,
+        MarkupFormat::BibTeX => bibtex::convert_bibtex(text, options)?,
+    };
+
+    let document = ParsedDocument::from(join_lines(&converted.lines))
+        .with_metadata(METADATA_FORMAT, format.name());
+
+    Ok(match converted.title {
+        Some(title) if !title.is_empty() => document.with_metadata(METADATA_TITLE, title),
+        _ => document,
+    })
+}
+
+/// Joins lines, trimming trailing whitespace and collapsing runs of blank lines.
+fn join_lines(lines: &[String]) -> String {
+    let mut text = String::new();
+    let mut blank = false;
+
+    for line in lines {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank = !text.is_empty();
+            continue;
+        }
+        if blank {
+            text.push('\n');
+            blank = false;
+        }
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(line);
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> String {
+        std::fs::read_to_string(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_markdown_success() {
+        let text = read_test_file("test_md_1.md");
+        let document =
+            parse_markup(&text, MarkupFormat::Markdown, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            document.text,
+            "Test Markdown
+
+This is a test Markdown file for the parsing API.
+
+Features
+
+- Headings
+- Lists
+  1. Ordered
+  2. Nested
+
+curl -F \"file=@test_md_1.md\" http://localhost:8080/parse
+
+See the documentation."
+        );
+        assert_eq!(document.metadata[METADATA_FORMAT], "markdown");
+        assert_eq!(document.metadata[METADATA_TITLE], "Test Markdown");
+    }
+
+    #[test]
+    fn parse_markdown_skip_code_blocks() {
+        let text = read_test_file("test_md_1.md");
+        let options = ParseOptions {
+            skip_code_blocks: true,
+            ..ParseOptions::default()
+        };
+        let document = parse_markup(&text, MarkupFormat::Markdown, &options).unwrap();
+
+        assert!(!document.text.contains("curl"));
+        assert!(
+            document
+                .text
+                .ends_with("  2. Nested\n\nSee the documentation.")
+        );
+    }
+
+    #[test]
+    fn parse_latex_success() {
+        let text = read_test_file("test_tex_1.tex");
+        let document = parse_markup(&text, MarkupFormat::Latex, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            document.text,
+            "Test LaTeX Document
+
+Jane Doe
+
+Abstract
+
+Hello, this is a test tex for the parsing API.
+
+Introduction
+
+Text with bold and emphasis, a citation [doe2020, martin2019] and a formula E = mc^2. (A footnote.)
+
+- First item
+- Second item
+
+Results
+
+\\int_0^1 x^2 \\, dx = \\frac{1}{3}
+
+Name,Value
+alpha,1
+
+Caf\u{e9} \u{2014} na\u{ef}ve & done."
+        );
+        assert_eq!(document.metadata[METADATA_FORMAT], "latex");
+        assert_eq!(document.metadata[METADATA_TITLE], "Test LaTeX Document");
+    }
+
+    #[test]
+    fn parse_bibtex_success() {
+        let text = read_test_file("test_bib_1.bib");
+        let document = parse_markup(&text, MarkupFormat::BibTeX, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            document.text,
+            "[doe2020] Jane Doe and John Smith (2020). Hello, this is a test bib for the parsing API. \
+             Journal of Computational Parsing, 12(3), pp. 45\u{2013}67. doi:10.1000/xyz123
+[martin2019] Fran\u{e7}ois Martin (2019). Parsing Documents. Example Press."
+        );
+        assert_eq!(document.metadata[METADATA_FORMAT], "bibtex");
+    }
+
+    #[test]
+    fn sniff_success() {
+        assert_eq!(
+            MarkupFormat::sniff(&read_test_file("test_tex_1.tex")),
+            Some(MarkupFormat::Latex)
+        );
+        assert_eq!(
+            MarkupFormat::sniff(&read_test_file("test_bib_1.bib")),
+            Some(MarkupFormat::BibTeX)
+        );
+        assert_eq!(MarkupFormat::sniff("Contact: jane@example.com"), None);
+        assert_eq!(MarkupFormat::sniff("# Title"), None);
+    }
+
+    #[test]
+    fn join_lines_success() {
+        let lines = ["", "Title", "", "", "Text  ", "More", ""].map(String::from);
+
+        assert_eq!(join_lines(&lines), "Title\n\nText\nMore");
+    }
+}
@@ -0,0 +1,526 @@
+//! AsciiDoc converter module.
+//!
+//! This module converts AsciiDoc to plain text line by line: the document header,
+//! attribute entries, block delimiters and comments are removed, attribute
+//! references are substituted and inline markup is reduced to its text.
+
+use super::super::super::{
+    errors::ParserError,
+    options::{ParseOptions, TableFormat},
+};
+use super::super::table::Table;
+use super::Converted;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+/// Block macros whose line is dropped.
+const SKIPPED_MACROS: [&str; 9] = [
+    "include::",
+    "ifdef::",
+    "ifndef::",
+    "ifeval::",
+    "endif::",
+    "image::",
+    "video::",
+    "audio::",
+    "toc::",
+];
+
+/// Converts AsciiDoc to plain text.
+///
+/// # Implementation Notes
+///
+/// * The document title (`= Title`) is the title of the document, the author and
+///   revision lines following it are dropped
+/// * Listing, literal and source blocks are code blocks, passthrough blocks are dropped
+/// * Tables are rendered in the `table_format` option
+pub(super) fn convert_asciidoc(
+    text: &str,
+    options: &ParseOptions,
+) -> Result<Converted, ParserError> {
+    let mut converter = Converter::new(options)?;
+    let lines: Vec<&str> = text.lines().collect();
+    let mut result = Converted {
+        lines: Vec::new(),
+        title: None,
+    };
+
+    let start = converter.header(&lines, &mut result);
+    converter.convert(&lines[start..], &mut result)?;
+    Ok(result)
+}
+
+/// Converter of AsciiDoc, with the patterns it uses and the attributes it collected.
+struct Converter {
+    /// Whether code blocks are dropped
+    skip_code_blocks: bool,
+    /// Whether link targets are kept
+    keep_links: bool,
+    /// Rendering of tables
+    table_format: TableFormat,
+    /// Values of the document attributes
+    attributes: HashMap<String, String>,
+    /// Next number of each level of ordered lists
+    numbers: Vec<usize>,
+    /// Attribute entry, e.g. `:name: value`
+    attribute_entry: Regex,
+    /// Section title
+    heading: Regex,
+    /// Unordered list item
+    bullet: Regex,
+    /// Ordered list item
+    numbered: Regex,
+    /// Description list item
+    description: Regex,
+    /// Attribute reference, e.g. `{name}`
+    attribute_reference: Regex,
+    /// Link macro
+    link: Regex,
+    /// Bare URL with a link text
+    url: Regex,
+    /// Cross-reference
+    cross_reference: Regex,
+    /// Inline image macro
+    image: Regex,
+    /// Footnote macro
+    footnote: Regex,
+    /// Keyboard, button and passthrough macros
+    text_macro: Regex,
+    /// Menu macro
+    menu: Regex,
+    /// Unconstrained formatting marks
+    unconstrained: Regex,
+    /// Constrained strong text
+    strong: Regex,
+    /// Constrained emphasis
+    emphasis: Regex,
+    /// Constrained monospace text
+    monospace: Regex,
+    /// Highlighted text, with an optional role
+    highlight: Regex,
+}
+
+impl Converter {
+    /// Compiles the patterns of the converter.
+    fn new(options: &ParseOptions) -> Result<Self, ParserError> {
+        Ok(Self {
+            skip_code_blocks: options.skip_code_blocks,
+            keep_links: options.keep_links,
+            table_format: options.table_format,
+            attributes: HashMap::new(),
+            numbers: Vec::new(),
+            attribute_entry: Regex::new(r"^:(!?)([\w-]+)(!?):(?:\s+(.*))?$")?,
+            heading: Regex::new(r"^(={1,6})\s+(.+?)(?:\s+=+)?$")?,
+            bullet: Regex::new(r"^\s*(\*{1,5}|-)\s+(.*)$")?,
+            numbered: Regex::new(r"^\s*(\.{1,5}|\d+\.)\s+(.*)$")?,
+            description: Regex::new(r"^(\S.*?)(?::{2,4}|;;)(?:\s+(.*))?$")?,
+            attribute_reference: Regex::new(r"\{([\w-]+)\}")?,
+            link: Regex::new(r"link:([^\s\[]+)\[([^\]]*)\]")?,
+            url: Regex::new(r"((?:https?|ftp|irc)://[^\s\[]+|mailto:[^\s\[]+)\[([^\]]*)\]")?,
+            cross_reference: Regex::new(
+                r"<<([^,>]+)(?:,\s*([^>]+))?>>|xref:([^\s\[]+)\[([^\]]*)\]",
+            )?,
+            image: Regex::new(r"image:([^\s\[:][^\s\[]*)\[([^\],\]]*)[^\]]*\]")?,
+            footnote: Regex::new(r"footnote(?:ref)?:[\w-]*\[([^\]]*)\]")?,
+            text_macro: Regex::new(r"(?:kbd|btn|pass):[\w,]*\[([^\]]*)\]")?,
+            menu: Regex::new(r"menu:([^\s\[]+)\[([^\]]*)\]")?,
+            unconstrained: Regex::new(r"\*\*(.+?)\*\*|__(.+?)__|``(.+?)``|##(.+?)##")?,
+            strong: Regex::new(r"\*([^*\s](?:[^*]*[^*\s])?)\*")?,
+            emphasis: Regex::new(r"\b_([^_\s](?:[^_]*[^_\s])?)_\b")?,
+            monospace: Regex::new(r"`([^`]+)`")?,
+            highlight: Regex::new(r"(?:\[[\w.#-]*\])?#([^#\s](?:[^#]*[^#\s])?)#")?,
+        })
+    }
+
+    /// Reads the document header: the title, author and revision lines, and
+    /// attribute entries.
+    ///
+    /// Returns the index of the first line of the body.
+    fn header(&mut self, lines: &[&str], converted: &mut Converted) -> usize {
+        let mut index = 0;
+
+        // Comments and attribute entries may precede the title
+        while let Some(line) = lines.get(index) {
+            if (line.starts_with("//") && !line.starts_with("////"))
+                || line.trim().is_empty()
+                || self.attribute(line)
+            {
+                index += 1;
+            } else {
+                break;
+            }
+        }
+
+        let Some(title) = lines.get(index).and_then(|line| line.strip_prefix("= ")) else {
+            return 0;
+        };
+        converted.title = Some(self.inline(title.trim()));
+        converted.lines.push(self.inline(title.trim()));
+        converted.lines.push(String::new());
+
+        // The header ends at the first blank line
+        index += 1;
+        while lines.get(index).is_some_and(|line| !line.trim().is_empty()) {
+            self.attribute(lines[index]);
+            index += 1;
+        }
+        index
+    }
+
+    /// Records an attribute entry, returning whether the line is one.
+    fn attribute(&mut self, line: &str) -> bool {
+        let Some(captures) = self.attribute_entry.captures(line) else {
+            return false;
+        };
+        let name = captures[2].to_string();
+        if captures[1].is_empty() && captures[3].is_empty() {
+            let value = captures.get(4).map_or("", |value| value.as_str());
+            self.attributes.insert(name, value.trim().to_string());
+        } else {
+            self.attributes.remove(&name);
+        }
+        true
+    }
+
+    /// Converts lines of AsciiDoc, appending the result to `converted`.
+    fn convert(&mut self, lines: &[&str], converted: &mut Converted) -> Result<(), ParserError> {
+        let mut index = 0;
+
+        while index < lines.len() {
+            let line = lines[index];
+            let trimmed = line.trim_end();
+
+            if let Some(delimiter) = block_delimiter(trimmed) {
+                let end = lines[index + 1..]
+                    .iter()
+                    .position(|line| line.trim_end() == delimiter)
+                    .map_or(lines.len(), |position| index + 1 + position);
+                self.block(delimiter, &lines[index + 1..end], converted)?;
+                index = end + 1;
+                continue;
+            }
+            index += 1;
+
+            if trimmed.is_empty() {
+                converted.lines.push(String::new());
+            } else if trimmed.starts_with("//")
+                || trimmed == "+"
+                || trimmed == "'''"
+                || trimmed == "<<<"
+                || (trimmed.starts_with('[') && trimmed.ends_with(']'))
+                || SKIPPED_MACROS.iter().any(|name| trimmed.starts_with(name))
+                || self.attribute(trimmed)
+            {
+                // Comments, list continuations, breaks, block attributes and macros
+            } else if let Some(captures) = self.heading.captures(trimmed) {
+                self.numbers.clear();
+                let heading = self.inline(&captures[2]);
+                if captures[1].len() == 1 && converted.title.is_none() {
+                    converted.title = Some(heading.clone());
+                }
+                converted
+                    .lines
+                    .extend([String::new(), heading, String::new()]);
+            } else if let Some(captures) = self.bullet.captures(trimmed) {
+                let depth = if &captures[1] == "-" {
+                    0
+                } else {
+                    captures[1].len() - 1
+                };
+                converted.lines.push(format!(
+                    "{}- {}",
+                    "  ".repeat(depth),
+                    self.inline(&captures[2])
+                ));
+            } else if let Some(captures) = self.numbered.captures(trimmed) {
+                let marker = &captures[1];
+                let (number, depth) = if marker.starts_with('.') {
+                    (self.next_number(marker.len() - 1), marker.len() - 1)
+                } else {
+                    (marker.trim_end_matches('.').to_string(), 0)
+                };
+                converted.lines.push(format!(
+                    "{}{number}. {}",
+                    "  ".repeat(depth),
+                    self.inline(&captures[2])
+                ));
+            } else if let Some(title) = trimmed
+                .strip_prefix('.')
+                .filter(|title| title.starts_with(|c: char| !c.is_whitespace() && c != '.'))
+            {
+                // Block title
+                converted.lines.push(self.inline(title));
+            } else if line.starts_with(char::is_whitespace) {
+                // Literal paragraph
+                if !self.skip_code_blocks {
+                    converted.lines.push(line.trim().to_string());
+                }
+            } else if let Some(captures) = self.description.captures(trimmed) {
+                let term = self.inline(&captures[1]);
+                let definition = captures.get(2).map_or("", |definition| definition.as_str());
+                converted.lines.push(
+                    format!("{term}: {}", self.inline(definition))
+                        .trim_end()
+                        .to_string(),
+                );
+            } else {
+                self.numbers.clear();
+                let text = trimmed.strip_suffix(" +").unwrap_or(trimmed);
+                converted.lines.push(self.inline(text));
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts the content of a delimited block.
+    fn block(
+        &mut self,
+        delimiter: &str,
+        content: &[&str],
+        converted: &mut Converted,
+    ) -> Result<(), ParserError> {
+        converted.lines.push(String::new());
+        match delimiter.chars().next() {
+            // Listing, literal and fenced source blocks
+            Some('-' | '.' | '`') if delimiter != "--" => {
+                if !self.skip_code_blocks {
+                    converted
+                        .lines
+                        .extend(content.iter().map(|line| (*line).to_string()));
+                }
+            }
+            // Passthrough and comment blocks
+            Some('+' | '/') => {}
+            Some('|') => self.table(content, converted)?,
+            // Example, sidebar, quote and open blocks
+            _ => self.convert(content, converted)?,
+        }
+        converted.lines.push(String::new());
+        Ok(())
+    }
+
+    /// Converts the content of a table, with one row per line or cells spanning
+    /// several lines.
+    fn table(&self, content: &[&str], converted: &mut Converted) -> Result<(), ParserError> {
+        let mut columns = None;
+        let mut has_header = false;
+        let mut cells = Vec::new();
+
+        for line in content {
+            let line = line.trim();
+            if line.is_empty() {
+                // A blank line right after the first row makes it a header
+                has_header |= columns == Some(cells.len());
+                continue;
+            }
+            let row: Vec<String> = line
+                .split('|')
+                .skip(usize::from(line.starts_with('|')))
+                .map(|cell| self.inline(cell.trim()))
+                .collect();
+            // The first line defines the number of columns
+            columns.get_or_insert(row.len());
+            cells.extend(row);
+        }
+
+        let rows: Vec<Vec<String>> = cells
+            .chunks(columns.unwrap_or(1).max(1))
+            .map(<[String]>::to_vec)
+            .collect();
+        let table = if has_header {
+            Table {
+                rows,
+                has_header: true,
+            }
+        } else {
+            Table::new(rows)
+        };
+
+        converted
+            .lines
+            .extend(table.render(self.table_format)?.lines().map(str::to_string));
+        Ok(())
+    }
+
+    /// Returns the next number of an ordered list level, restarting deeper levels.
+    fn next_number(&mut self, depth: usize) -> String {
+        self.numbers.resize(depth + 1, 0);
+        self.numbers[depth] += 1;
+        self.numbers[depth].to_string()
+    }
+
+    /// Substitutes attribute references and reduces inline markup to its text.
+    fn inline(&self, text: &str) -> String {
+        let text = self
+            .attribute_reference
+            .replace_all(text, |captures: &Captures| {
+                self.attributes
+                    .get(&captures[1])
+                    .cloned()
+                    .unwrap_or_else(|| captures[0].to_string())
+            });
+        let text = self.link.replace_all(&text, |captures: &Captures| {
+            self.link_text(&captures[1], &captures[2])
+        });
+        let text = self.url.replace_all(&text, |captures: &Captures| {
+            self.link_text(&captures[1], &captures[2])
+        });
+        let text = self
+            .cross_reference
+            .replace_all(&text, |captures: &Captures| {
+                [2, 4, 1, 3]
+                    .iter()
+                    .find_map(|group| captures.get(*group))
+                    .map_or("", |text| text.as_str())
+                    .trim()
+                    .to_string()
+            });
+        let text = self.image.replace_all(&text, "$2");
+        let text = self.footnote.replace_all(&text, " ($1)");
+        let text = self.text_macro.replace_all(&text, "$1");
+        let text = self.menu.replace_all(&text, |captures: &Captures| {
+            if captures[2].is_empty() {
+                captures[1].to_string()
+            } else {
+                format!("{} > {}", &captures[1], &captures[2].replace('>', " > "))
+            }
+        });
+        let text = self
+            .unconstrained
+            .replace_all(&text, |captures: &Captures| {
+                (1..=4)
+                    .find_map(|group| captures.get(group))
+                    .map_or("", |text| text.as_str())
+                    .to_string()
+            });
+        let text = self.strong.replace_all(&text, "$1");
+        let text = self.emphasis.replace_all(&text, "$1");
+        let text = self.monospace.replace_all(&text, "$1");
+        self.highlight.replace_all(&text, "$1").into_owned()
+    }
+
+    /// Renders a link, with its target when the text is empty or links are kept.
+    fn link_text(&self, target: &str, text: &str) -> String {
+        let target = target.strip_prefix("mailto:").unwrap_or(target);
+        if text.is_empty() {
+            target.to_string()
+        } else if self.keep_links {
+            format!("{text} ({target})")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Returns the delimiter of the block opened by a line, if any.
+fn block_delimiter(line: &str) -> Option<&str> {
+    if line.starts_with("```") {
+        return Some("```");
+    }
+    if line == "--" || line == "|===" {
+        return Some(line);
+    }
+    let mut chars = line.chars();
+    let first = chars.next()?;
+    ("-.=*_+/".contains(first) && line.len() >= 4 && chars.all(|character| character == first))
+        .then_some(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::join_lines;
+    use super::*;
+
+    fn convert(text: &str, options: &ParseOptions) -> String {
+        join_lines(&convert_asciidoc(text, options).unwrap().lines)
+    }
+
+    #[test]
+    fn convert_asciidoc_success() {
+        let text = "= User Guide
+Jane Doe <jane@example.com>
+:product: Parser
+
+// A comment
+
+== Introduction
+
+*{product}* extracts _text_ from `files`, see https://example.com[the site].
+
+* First
+** Nested
+. One
+. Two
+
+[source,rust]
+----
+fn main() {}
+----
+
+.Example
+====
+NOTE: Inside an example.
+====
+
+|===
+| Name | Value
+
+| a | 1
+|===
+
+CPU:: The processor
+";
+        let converted = convert_asciidoc(text, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            join_lines(&converted.lines),
+            "User Guide
+
+Introduction
+
+Parser extracts text from files, see the site.
+
+- First
+  - Nested
+1. One
+2. Two
+
+fn main() {}
+
+Example
+
+NOTE: Inside an example.
+
+Name,Value
+a,1
+
+CPU: The processor"
+        );
+        assert_eq!(converted.title.as_deref(), Some("User Guide"));
+    }
+
+    #[test]
+    fn convert_asciidoc_options() {
+        let options = ParseOptions {
+            skip_code_blocks: true,
+            keep_links: true,
+            ..ParseOptions::default()
+        };
+        let text = "Read link:https://example.com/docs[the docs].\n\n....\nliteral\n....\n\nEnd";
+
+        assert_eq!(
+            convert(text, &options),
+            "Read the docs (https://example.com/docs).\n\nEnd"
+        );
+    }
+
+    #[test]
+    fn block_delimiter_success() {
+        assert_eq!(block_delimiter("----"), Some("----"));
+        assert_eq!(block_delimiter("```rust"), Some("```"));
+        assert_eq!(block_delimiter("|==="), Some("|==="));
+        assert_eq!(block_delimiter("--"), Some("--"));
+        assert_eq!(block_delimiter("---"), None);
+        assert_eq!(block_delimiter("Text"), None);
+    }
+}
@@ -0,0 +1,380 @@
+//! `BibTeX` converter module.
+//!
+//! This module renders the entries of `BibTeX` bibliographies as citation lines:
+//! `@string` abbreviations are expanded, `@comment` and `@preamble` entries are
+//! dropped and the LaTeX markup of the fields is converted to text.
+
+use super::super::super::{errors::ParserError, options::ParseOptions};
+use super::Converted;
+use super::latex::latex_to_text;
+use std::collections::HashMap;
+
+/// Abbreviations of the months, predefined in `BibTeX`
+const MONTHS: [(&str, &str); 12] = [
+    ("jan", "January"),
+    ("feb", "February"),
+    ("mar", "March"),
+    ("apr", "April"),
+    ("may", "May"),
+    ("jun", "June"),
+    ("jul", "July"),
+    ("aug", "August"),
+    ("sep", "September"),
+    ("oct", "October"),
+    ("nov", "November"),
+    ("dec", "December"),
+];
+
+/// Fields naming where an entry was published, by order of preference
+const VENUE_FIELDS: [&str; 6] = [
+    "journal",
+    "booktitle",
+    "howpublished",
+    "school",
+    "institution",
+    "organization",
+];
+
+/// An entry of a bibliography.
+struct Entry {
+    /// Citation key of the entry
+    key: String,
+    /// Lowercase names of the fields, with their values still in LaTeX
+    fields: Vec<(String, String)>,
+}
+
+impl Entry {
+    /// Returns the value of a field, if it is present and not empty.
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+/// Converts a `BibTeX` bibliography to citation lines.
+///
+/// # Implementation Notes
+///
+/// * Each entry is rendered on one line, as
+///   `[key] Authors (Year). Title. Venue, Volume(Number), pp. Pages. doi:DOI`
+/// * Names written as `Last, First` are rendered as `First Last`
+pub(super) fn convert_bibtex(text: &str, options: &ParseOptions) -> Result<Converted, ParserError> {
+    let lines = read_entries(text)
+        .iter()
+        .map(|entry| render_entry(entry, options))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Converted { lines, title: None })
+}
+
+/// Reads the entries of a bibliography, expanding the `@string` abbreviations.
+fn read_entries(text: &str) -> Vec<Entry> {
+    let mut strings: HashMap<String, String> = MONTHS
+        .iter()
+        .map(|(abbreviation, month)| ((*abbreviation).to_string(), (*month).to_string()))
+        .collect();
+    let mut entries = Vec::new();
+    let mut position = 0;
+
+    // Text outside entries is a comment
+    while let Some(offset) = text[position..].find('@') {
+        let start = position + offset + 1;
+        let kind_length = text[start..]
+            .find(|character: char| !character.is_ascii_alphanumeric())
+            .unwrap_or(text.len() - start);
+        let kind = text[start..start + kind_length].to_ascii_lowercase();
+        let open = start + kind_length;
+        let open = open + (text[open..].len() - text[open..].trim_start().len());
+
+        let Some(delimiter) = text[open..]
+            .chars()
+            .next()
+            .filter(|c| *c == '{' || *c == '(')
+        else {
+            position = open;
+            continue;
+        };
+        let (body, end) = entry_body(text, open + 1, if delimiter == '{' { '}' } else { ')' });
+        position = end;
+
+        match kind.as_str() {
+            "comment" | "preamble" => {}
+            "string" => {
+                for (name, value) in read_fields(body, &strings) {
+                    strings.insert(name, value);
+                }
+            }
+            _ => {
+                let (key, fields) = body.split_once(',').unwrap_or((body, ""));
+                entries.push(Entry {
+                    key: key.trim().to_string(),
+                    fields: read_fields(fields, &strings),
+                });
+            }
+        }
+    }
+
+    entries
+}
+
+/// Returns the body of an entry starting at a position, after its opening delimiter,
+/// and the position after its closing delimiter.
+fn entry_body(text: &str, start: usize, closing: char) -> (&str, usize) {
+    let mut depth = 0;
+    for (offset, character) in text[start..].char_indices() {
+        match character {
+            '{' => depth += 1,
+            '}' if depth > 0 => depth -= 1,
+            _ if character == closing && depth == 0 => {
+                return (&text[start..start + offset], start + offset + 1);
+            }
+            _ => {}
+        }
+    }
+    (&text[start..], text.len())
+}
+
+/// Reads the `name = value` fields of an entry, separated by commas.
+///
+/// Values are delimited by braces or quotes, or are numbers or abbreviations, and
+/// may be concatenated with `#`.
+fn read_fields(body: &str, strings: &HashMap<String, String>) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    let mut rest = body;
+
+    while let Some((name, after)) = rest.split_once('=') {
+        let name = name
+            .trim()
+            .trim_start_matches(',')
+            .trim()
+            .to_ascii_lowercase();
+        let mut value = String::new();
+        rest = after;
+
+        loop {
+            rest = rest.trim_start();
+            let (part, remaining) = match rest.chars().next() {
+                Some('{') => {
+                    let (content, end) = entry_body(rest, 1, '}');
+                    (content.to_string(), &rest[end..])
+                }
+                Some('"') => {
+                    let (content, end) = quoted(rest);
+                    (content.to_string(), &rest[end..])
+                }
+                Some(_) => {
+                    let end = rest.find([',', '#', '}', ')']).unwrap_or(rest.len());
+                    let word = rest[..end].trim();
+                    let part = strings
+                        .get(&word.to_ascii_lowercase())
+                        .cloned()
+                        .unwrap_or_else(|| word.to_string());
+                    (part, &rest[end..])
+                }
+                None => (String::new(), rest),
+            };
+            value.push_str(&part);
+
+            rest = remaining.trim_start();
+            match rest.strip_prefix('#') {
+                Some(concatenated) => rest = concatenated,
+                None => break,
+            }
+        }
+
+        if !name.is_empty() {
+            fields.push((name, value));
+        }
+        rest = rest.trim_start().strip_prefix(',').unwrap_or(rest);
+    }
+
+    fields
+}
+
+/// Returns the content of a quoted value at the start of text, braces protecting the
+/// quotes they contain, and the position after its closing quote.
+fn quoted(text: &str) -> (&str, usize) {
+    let mut depth = 0;
+    for (offset, character) in text.char_indices().skip(1) {
+        match character {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            '"' if depth == 0 => return (&text[1..offset], offset + 1),
+            _ => {}
+        }
+    }
+    (&text[1..], text.len())
+}
+
+/// Renders an entry as a citation line.
+fn render_entry(entry: &Entry, options: &ParseOptions) -> Result<String, ParserError> {
+    let text = |name: &str| -> Result<Option<String>, ParserError> {
+        entry
+            .field(name)
+            .map(|value| latex_to_text(value, options))
+            .transpose()
+    };
+
+    let authors = match entry.field("author").or_else(|| entry.field("editor")) {
+        Some(names) => Some(format_names(names, options)?),
+        None => None,
+    };
+    let year = text("year")?.or(text("date")?.map(|date| date.chars().take(4).collect()));
+    let head = match (authors, year) {
+        (Some(authors), Some(year)) => Some(format!("{authors} ({year})")),
+        (Some(authors), None) => Some(authors),
+        (None, Some(year)) => Some(format!("({year})")),
+        (None, None) => None,
+    };
+
+    let mut details = Vec::new();
+    if let Some(venue) = VENUE_FIELDS.iter().find_map(|name| entry.field(name)) {
+        details.push(latex_to_text(venue, options)?);
+    }
+    match (text("volume")?, text("number")?) {
+        (Some(volume), Some(number)) => details.push(format!("{volume}({number})")),
+        (Some(volume), None) => details.push(volume),
+        (None, Some(number)) => details.push(format!("no. {number}")),
+        (None, None) => {}
+    }
+    if let Some(pages) = text("pages")? {
+        let prefix = if pages.contains(['\u{2013}', '-']) {
+            "pp."
+        } else {
+            "p."
+        };
+        details.push(format!("{prefix} {pages}"));
+    }
+    details.extend(text("publisher")?);
+
+    let mut sentences: Vec<String> = [head, text("title")?, Some(details.join(", "))]
+        .into_iter()
+        .flatten()
+        .filter(|sentence| !sentence.is_empty())
+        .map(|sentence| {
+            if sentence.ends_with(['.', '?', '!']) {
+                sentence
+            } else {
+                format!("{sentence}.")
+            }
+        })
+        .collect();
+    if let Some(doi) = entry.field("doi") {
+        sentences.push(format!("doi:{doi}"));
+    } else if let Some(url) = entry.field("url") {
+        sentences.push(url.to_string());
+    }
+
+    Ok(format!("[{}] {}", entry.key, sentences.join(" ")))
+}
+
+/// Formats the names of a `name and name` list, as `A, B and C`.
+fn format_names(names: &str, options: &ParseOptions) -> Result<String, ParserError> {
+    let mut formatted = split_names(names)
+        .iter()
+        .map(|name| {
+            if name == "others" {
+                return Ok("et al.".to_string());
+            }
+            // `Last, First` or `Last, Suffix, First`
+            let parts = split_top_level(name, ',');
+            let name = match parts.as_slice() {
+                [last, first] => format!("{} {}", first.trim(), last.trim()),
+                [last, suffix, first] => {
+                    format!("{} {} {}", first.trim(), last.trim(), suffix.trim())
+                }
+                _ => name.clone(),
+            };
+            latex_to_text(&name, options)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(match formatted.pop() {
+        Some(last) if formatted.is_empty() => last,
+        Some(last) if last == "et al." => format!("{} et al.", formatted.join(", ")),
+        Some(last) => format!("{} and {last}", formatted.join(", ")),
+        None => String::new(),
+    })
+}
+
+/// Splits a list of names on the `and` outside braces.
+fn split_names(names: &str) -> Vec<String> {
+    let names = names.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut result = Vec::new();
+    let mut name: Vec<&str> = Vec::new();
+
+    for word in split_top_level(&names, ' ') {
+        if word.eq_ignore_ascii_case("and") {
+            result.push(std::mem::take(&mut name).join(" "));
+        } else {
+            name.push(word);
+        }
+    }
+    result.push(name.join(" "));
+
+    result.retain(|name| !name.is_empty());
+    result
+}
+
+/// Splits text on a separator outside braces.
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (index, character) in text.char_indices() {
+        match character {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ if character == separator && depth == 0 => {
+                parts.push(&text[start..index]);
+                start = index + character.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_bibtex_success() {
+        let text = r#"@String(conf = "Proc. of the " # {Parsing} # " Conference")
+@inproceedings{lee2021,
+  author = {Lee, Jr., Kim and {Barnes and Noble} and others},
+  title = {{Fast} Parsing: A Study},
+  booktitle = conf,
+  month = jun,
+  year = "2021",
+  url = {https://example.com/paper}
+}
+@comment{ignored}
+@misc{anonymous, title = {An {\em Anonymous} Note}}"#;
+        let converted = convert_bibtex(text, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            converted.lines,
+            [
+                "[lee2021] Kim Lee Jr., Barnes and Noble et al. (2021). Fast Parsing: A Study. \
+                 Proc. of the Parsing Conference. https://example.com/paper",
+                "[anonymous] An Anonymous Note."
+            ]
+        );
+    }
+
+    #[test]
+    fn split_names_success() {
+        assert_eq!(
+            split_names("Doe, Jane and {Smith and Sons}  AND Martin"),
+            ["Doe, Jane", "{Smith and Sons}", "Martin"]
+        );
+    }
+}
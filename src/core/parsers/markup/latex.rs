@@ -0,0 +1,1131 @@
+//! LaTeX converter module.
+//!
+//! This module converts LaTeX sources to plain text by interpreting their common
+//! commands and environments: the preamble and comments are dropped, sectioning
+//! commands become headings, text macros are replaced with their text, and lists
+//! and tables are rendered like those of the other markup languages.
+
+use super::super::super::{
+    errors::ParserError,
+    options::{MathMode, ParseOptions, TableFormat},
+};
+use super::super::table::Table;
+use super::Converted;
+
+/// Deepest nesting of groups, environments and command arguments converted, bounding
+/// the recursion
+const MAX_NESTING_DEPTH: usize = 64;
+
+/// Placeholder replacing formulas in the `placeholder` math mode
+const MATH_PLACEHOLDER: &str = "[math]";
+
+/// Sectioning commands, rendered as headings
+const HEADINGS: [&str; 7] = [
+    "part",
+    "chapter",
+    "section",
+    "subsection",
+    "subsubsection",
+    "paragraph",
+    "subparagraph",
+];
+
+/// Commands replaced with the text of their argument
+const TEXT_COMMANDS: [&str; 16] = [
+    "textbf",
+    "textit",
+    "textsl",
+    "textsc",
+    "texttt",
+    "textsf",
+    "textrm",
+    "textmd",
+    "textup",
+    "textnormal",
+    "emph",
+    "underline",
+    "mbox",
+    "fbox",
+    "text",
+    "hbox",
+];
+
+/// Citation commands, rendered as their keys in brackets
+const CITE_COMMANDS: [&str; 9] = [
+    "cite",
+    "citep",
+    "citet",
+    "parencite",
+    "textcite",
+    "autocite",
+    "footcite",
+    "citeauthor",
+    "citeyear",
+];
+
+/// Cross-reference commands, rendered as their label in brackets
+const REF_COMMANDS: [&str; 7] = [
+    "ref", "eqref", "pageref", "autoref", "cref", "Cref", "nameref",
+];
+
+/// Commands dropped along with their number of arguments
+const DROPPED_COMMANDS: [(&str, usize); 29] = [
+    ("documentclass", 1),
+    ("usepackage", 1),
+    ("RequirePackage", 1),
+    ("date", 1),
+    ("label", 1),
+    ("index", 1),
+    ("vspace", 1),
+    ("hspace", 1),
+    ("includegraphics", 1),
+    ("bibliographystyle", 1),
+    ("bibliography", 1),
+    ("addbibresource", 1),
+    ("nocite", 1),
+    ("pagestyle", 1),
+    ("thispagestyle", 1),
+    ("input", 1),
+    ("include", 1),
+    ("hypersetup", 1),
+    ("geometry", 1),
+    ("color", 1),
+    ("setlength", 2),
+    ("addtolength", 2),
+    ("setcounter", 2),
+    ("newcommand", 2),
+    ("renewcommand", 2),
+    ("providecommand", 2),
+    ("DeclareMathOperator", 2),
+    ("newenvironment", 3),
+    ("renewenvironment", 3),
+];
+
+/// Commands replaced with a symbol or text
+const SYMBOLS: [(&str, &str); 34] = [
+    ("LaTeX", "LaTeX"),
+    ("LaTeXe", "LaTeX2e"),
+    ("TeX", "TeX"),
+    ("ldots", "..."),
+    ("dots", "..."),
+    ("textellipsis", "..."),
+    ("textbackslash", "\\"),
+    ("textasciitilde", "~"),
+    ("textunderscore", "_"),
+    ("textbar", "|"),
+    ("textendash", "\u{2013}"),
+    ("textemdash", "\u{2014}"),
+    ("S", "\u{a7}"),
+    ("P", "\u{b6}"),
+    ("copyright", "\u{a9}"),
+    ("textregistered", "\u{ae}"),
+    ("texttrademark", "\u{2122}"),
+    ("textdegree", "\u{b0}"),
+    ("euro", "\u{20ac}"),
+    ("pounds", "\u{a3}"),
+    ("ss", "\u{df}"),
+    ("ae", "\u{e6}"),
+    ("AE", "\u{c6}"),
+    ("oe", "\u{153}"),
+    ("OE", "\u{152}"),
+    ("o", "\u{f8}"),
+    ("O", "\u{d8}"),
+    ("aa", "\u{e5}"),
+    ("AA", "\u{c5}"),
+    ("l", "\u{142}"),
+    ("L", "\u{141}"),
+    ("quad", " "),
+    ("qquad", " "),
+    ("and", ", "),
+];
+
+/// Environments holding displayed formulas
+const MATH_ENVIRONMENTS: [&str; 9] = [
+    "equation",
+    "align",
+    "alignat",
+    "gather",
+    "multline",
+    "flalign",
+    "eqnarray",
+    "displaymath",
+    "math",
+];
+
+/// Environments whose content is kept verbatim, as code blocks
+const VERBATIM_ENVIRONMENTS: [&str; 5] = ["verbatim", "Verbatim", "lstlisting", "minted", "alltt"];
+
+/// Converts LaTeX to plain text.
+///
+/// # Implementation Notes
+///
+/// * Everything before `\begin{document}` and after `\end{document}` is dropped, the
+///   `\title` of the preamble being the title of the document
+/// * Formulas are kept as their source, or replaced with a placeholder in the
+///   `placeholder` math mode
+/// * Citations and cross-references are rendered as their keys in brackets
+/// * Verbatim environments are code blocks, tables are rendered in the
+///   `table_format` option
+pub(super) fn convert_latex(text: &str, options: &ParseOptions) -> Result<Converted, ParserError> {
+    let mut converter = Converter::new(options);
+    converter.convert(text)?;
+
+    Ok(Converted {
+        lines: converter.writer.finish(),
+        title: converter.title,
+    })
+}
+
+/// Converts LaTeX text to a single line, as used in `BibTeX` fields.
+pub(super) fn latex_to_text(text: &str, options: &ParseOptions) -> Result<String, ParserError> {
+    Converter::new(options).inline(text)
+}
+
+/// Kinds of lists.
+enum List {
+    /// Bullet list (`itemize`)
+    Bullets,
+    /// Numbered list (`enumerate`), with the number of the last item
+    Numbers(usize),
+    /// Description list (`description`)
+    Descriptions,
+}
+
+/// Lines of converted text, whitespace being collapsed as in LaTeX.
+#[derive(Default)]
+struct Writer {
+    /// Completed lines, blank lines separating paragraphs
+    lines: Vec<String>,
+    /// Line being written
+    line: String,
+}
+
+impl Writer {
+    /// Appends text, runs of whitespace being collapsed to a space.
+    fn text(&mut self, text: &str) {
+        for character in text.chars() {
+            if !character.is_whitespace() {
+                self.line.push(character);
+            } else if !self.line.is_empty() && !self.line.ends_with(' ') {
+                self.line.push(' ');
+            }
+        }
+    }
+
+    /// Ends the current line, unless it is empty.
+    fn newline(&mut self) {
+        if !self.line.trim().is_empty() {
+            self.lines.push(self.line.trim_end().to_string());
+        }
+        self.line.clear();
+    }
+
+    /// Ends the current paragraph.
+    fn paragraph(&mut self) {
+        self.newline();
+        self.lines.push(String::new());
+    }
+
+    /// Appends a line kept as is, such as a line of code.
+    fn raw_line(&mut self, line: &str) {
+        self.newline();
+        self.lines.push(line.to_string());
+    }
+
+    /// Returns the completed lines.
+    fn finish(mut self) -> Vec<String> {
+        self.newline();
+        self.lines
+    }
+}
+
+/// Converter of LaTeX, with the state of the conversion.
+struct Converter {
+    /// Rendering of formulas
+    math_mode: MathMode,
+    /// Whether code blocks are dropped
+    skip_code_blocks: bool,
+    /// Whether link targets are kept
+    keep_links: bool,
+    /// Rendering of tables
+    table_format: TableFormat,
+    /// Converted text
+    writer: Writer,
+    /// Title of the document, from `\title`
+    title: Option<String>,
+    /// Authors of the document, from `\author`
+    author: Option<String>,
+    /// Lists being converted, the innermost last
+    lists: Vec<List>,
+    /// Nesting depth of the text being converted
+    depth: usize,
+    /// Whether the end of the document was reached
+    finished: bool,
+}
+
+impl Converter {
+    /// Creates a converter with the options of the conversion.
+    fn new(options: &ParseOptions) -> Self {
+        Self {
+            math_mode: options.math_mode,
+            skip_code_blocks: options.skip_code_blocks,
+            keep_links: options.keep_links,
+            table_format: options.table_format,
+            writer: Writer::default(),
+            title: None,
+            author: None,
+            lists: Vec::new(),
+            depth: 0,
+            finished: false,
+        }
+    }
+
+    /// Converts LaTeX text, appending the result to the writer.
+    ///
+    /// Text nested deeper than [`MAX_NESTING_DEPTH`] is dropped.
+    fn convert(&mut self, text: &str) -> Result<(), ParserError> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Ok(());
+        }
+        self.depth += 1;
+        let result = self.convert_nested(text);
+        self.depth -= 1;
+        result
+    }
+
+    /// Converts LaTeX text one level deeper than its enclosing text.
+    fn convert_nested(&mut self, text: &str) -> Result<(), ParserError> {
+        let mut position = 0;
+
+        while !self.finished {
+            let Some(character) = text[position..].chars().next() else {
+                break;
+            };
+            position += character.len_utf8();
+            let rest = &text[position..];
+
+            match character {
+                '%' => {
+                    position = skip_comment(text, position);
+                    // A comment followed by an empty line ends the paragraph
+                    if text[position..].starts_with(['\n', '\r']) {
+                        self.writer.paragraph();
+                        position = skip_whitespace(text, position);
+                    }
+                }
+                '\\' => position = self.command(text, position)?,
+                '{' => {
+                    let (content, end) = group(text, position);
+                    self.convert(content)?;
+                    position = end;
+                }
+                '$' => {
+                    let display = rest.starts_with('$');
+                    let delimiter = if display { "$$" } else { "$" };
+                    let (formula, end) = until(text, position + delimiter.len() - 1, delimiter);
+                    self.math(formula, display);
+                    position = end;
+                }
+                '\n' => {
+                    if rest.trim_start_matches([' ', '\t', '\r']).starts_with('\n') {
+                        self.writer.paragraph();
+                        position = skip_whitespace(text, position);
+                    } else {
+                        self.writer.text(" ");
+                    }
+                }
+                '-' if rest.starts_with("--") => {
+                    self.writer.text("\u{2014}");
+                    position += 2;
+                }
+                '-' if rest.starts_with('-') => {
+                    self.writer.text("\u{2013}");
+                    position += 1;
+                }
+                '`' | '\'' if rest.starts_with(character) => {
+                    self.writer.text("\"");
+                    position += 1;
+                }
+                '`' => self.writer.text("'"),
+                '~' | '&' => self.writer.text(" "),
+                '}' => {}
+                _ => self.writer.text(character.encode_utf8(&mut [0; 4])),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Converts the command starting after a backslash, returning the position after
+    /// it and its arguments.
+    fn command(&mut self, text: &str, position: usize) -> Result<usize, ParserError> {
+        let rest = &text[position..];
+        let Some(first) = rest.chars().next() else {
+            return Ok(position);
+        };
+
+        // Control symbols: a backslash followed by a single character
+        if !first.is_ascii_alphabetic() {
+            return Ok(self.control_symbol(text, position + first.len_utf8(), first));
+        }
+
+        let length = rest
+            .find(|character: char| !character.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let name = &rest[..length];
+        let mut position = position + length;
+
+        match name {
+            "begin" => return self.environment(text, position),
+            "end" => position = argument(text, position).1,
+            "title" | "author" | "maketitle" => {
+                position = self.front_matter(name, text, position)?;
+            }
+            "item" => {
+                let (term, end) = optional(text, position);
+                self.item(term)?;
+                position = end;
+            }
+            "bibitem" => {
+                let (key, end) = argument(text, skip_optionals(text, position));
+                self.writer.newline();
+                self.writer.text(&format!("[{}] ", key.trim()));
+                position = end;
+            }
+            "footnote" => {
+                let (note, end) = argument(text, skip_optionals(text, position));
+                let note = self.inline(note)?;
+                self.writer.text(&format!(" ({note})"));
+                position = end;
+            }
+            "caption" => {
+                let (caption, end) =
+                    argument(text, skip_optionals(text, skip_star(text, position)));
+                self.writer.paragraph();
+                self.convert(caption)?;
+                self.writer.paragraph();
+                position = end;
+            }
+            "href" | "url" | "verb" => position = self.literal(name, text, position)?,
+            "textcolor" | "colorbox" => {
+                let (_, end) = argument(text, skip_optionals(text, position));
+                let (content, end) = argument(text, end);
+                self.convert(content)?;
+                position = end;
+            }
+            "def" | "gdef" | "edef" | "xdef" => {
+                // The name and parameters of the macro, then its body
+                let (_, end) = argument(text, position);
+                let start = text[end..]
+                    .find('{')
+                    .map_or(text.len(), |offset| end + offset + 1);
+                position = group(text, start).1;
+            }
+            "par" => self.writer.paragraph(),
+            "newline" | "linebreak" => self.writer.newline(),
+            "c" | "v" | "u" | "H" | "k" | "r" => {
+                let (letter, end) = argument(text, position);
+                self.writer.text(&accent(first, letter));
+                position = end;
+            }
+            _ if HEADINGS.contains(&name) => {
+                let (heading, end) =
+                    argument(text, skip_optionals(text, skip_star(text, position)));
+                self.writer.paragraph();
+                self.convert(heading)?;
+                self.writer.paragraph();
+                position = end;
+            }
+            _ if TEXT_COMMANDS.contains(&name) => {
+                let (content, end) = argument(text, skip_optionals(text, position));
+                self.convert(content)?;
+                position = end;
+            }
+            _ if CITE_COMMANDS.contains(&name) || REF_COMMANDS.contains(&name) => {
+                let (keys, end) = argument(text, skip_optionals(text, skip_star(text, position)));
+                let keys: Vec<&str> = keys.split(',').map(str::trim).collect();
+                self.writer.text(&format!("[{}]", keys.join(", ")));
+                position = end;
+            }
+            _ => position = self.other_command(name, text, position)?,
+        }
+
+        Ok(position)
+    }
+
+    /// Converts the `\title`, `\author` and `\maketitle` commands, returning the
+    /// position after them and their arguments.
+    fn front_matter(
+        &mut self,
+        name: &str,
+        text: &str,
+        mut position: usize,
+    ) -> Result<usize, ParserError> {
+        match name {
+            "title" | "author" => {
+                let (value, end) = argument(text, skip_optionals(text, position));
+                let value = Some(self.inline(value)?).filter(|value| !value.is_empty());
+                if name == "title" {
+                    self.title = value;
+                } else {
+                    self.author = value;
+                }
+                position = end;
+            }
+            "maketitle" => {
+                for line in [self.title.clone(), self.author.clone()]
+                    .into_iter()
+                    .flatten()
+                {
+                    self.writer.paragraph();
+                    self.writer.text(&line);
+                    self.writer.paragraph();
+                }
+            }
+            _ => {}
+        }
+        Ok(position)
+    }
+
+    /// Converts the `\href`, `\url` and `\verb` commands, whose arguments are
+    /// kept verbatim, returning the position after them and their arguments.
+    fn literal(
+        &mut self,
+        name: &str,
+        text: &str,
+        mut position: usize,
+    ) -> Result<usize, ParserError> {
+        match name {
+            "href" => {
+                let (target, end) = argument(text, position);
+                let (label, end) = argument(text, end);
+                let label = self.inline(label)?;
+                self.writer.text(&self.link_text(target.trim(), &label));
+                position = end;
+            }
+            "url" => {
+                let (target, end) = argument(text, position);
+                self.writer.text(target.trim());
+                position = end;
+            }
+            "verb" => {
+                let start = skip_star(text, position);
+                if let Some(delimiter) = text[start..].chars().next() {
+                    let (code, end) = until(
+                        text,
+                        start + delimiter.len_utf8(),
+                        delimiter.encode_utf8(&mut [0; 4]),
+                    );
+                    self.writer.text(code);
+                    position = end;
+                }
+            }
+            _ => {}
+        }
+        Ok(position)
+    }
+
+    /// Converts a control symbol, returning the position after it and its arguments.
+    fn control_symbol(&mut self, text: &str, mut position: usize, symbol: char) -> usize {
+        match symbol {
+            '\\' => {
+                position = skip_optionals(text, skip_star(text, position));
+                self.writer.newline();
+            }
+            '(' | '[' => {
+                let (formula, end) =
+                    until(text, position, if symbol == '(' { "\\)" } else { "\\]" });
+                self.math(formula, symbol == '[');
+                position = end;
+            }
+            '\'' | '`' | '^' | '"' | '~' | '=' | '.' => {
+                let (letter, end) = argument(text, position);
+                self.writer.text(&accent(symbol, letter));
+                position = end;
+            }
+            ' ' | '\n' | ',' | ';' | ':' => self.writer.text(" "),
+            '!' | '/' | '-' | '@' => {}
+            _ => self.writer.text(symbol.encode_utf8(&mut [0; 4])),
+        }
+        position
+    }
+
+    /// Converts a command without a specific rendering: dropped commands, symbols and
+    /// unknown commands, returning the position after it and its arguments.
+    fn other_command(
+        &mut self,
+        name: &str,
+        text: &str,
+        mut position: usize,
+    ) -> Result<usize, ParserError> {
+        if let Some((_, count)) = DROPPED_COMMANDS
+            .iter()
+            .find(|(dropped, _)| *dropped == name)
+        {
+            position = skip_star(text, position);
+            for _ in 0..*count {
+                position = argument(text, skip_optionals(text, position)).1;
+                position = skip_optionals(text, position);
+            }
+        } else if let Some((_, symbol)) = SYMBOLS.iter().find(|(command, _)| *command == name) {
+            self.writer.text(symbol);
+        } else {
+            // Unknown commands are dropped, the text of their arguments kept
+            position = skip_star(text, position);
+            loop {
+                position = skip_optionals(text, position);
+                if !text[position..].starts_with('{') {
+                    break;
+                }
+                let (content, end) = group(text, position + 1);
+                self.convert(content)?;
+                position = end;
+            }
+        }
+        Ok(position)
+    }
+
+    /// Converts the environment starting after `\begin`, returning the position after
+    /// its end.
+    fn environment(&mut self, text: &str, position: usize) -> Result<usize, ParserError> {
+        let (name, start) = argument(text, position);
+        let name = name.trim();
+        let (content, end) = environment_content(text, start, name);
+        let base = name.trim_end_matches('*');
+
+        match base {
+            "document" => {
+                // The preamble is dropped
+                self.writer = Writer::default();
+                self.convert(content)?;
+                self.finished = true;
+            }
+            "itemize" | "enumerate" | "description" => {
+                self.lists.push(match base {
+                    "itemize" => List::Bullets,
+                    "enumerate" => List::Numbers(0),
+                    _ => List::Descriptions,
+                });
+                self.writer.newline();
+                self.convert(skip_parameters(content, 0))?;
+                self.writer.newline();
+                self.lists.pop();
+            }
+            "comment" => {}
+            "tabular" | "tabularx" | "longtable" => {
+                let columns = if name == "tabular" || name == "longtable" {
+                    1
+                } else {
+                    2
+                };
+                self.table(skip_parameters(content, columns))?;
+            }
+            "abstract" => {
+                self.writer.paragraph();
+                self.writer.text("Abstract");
+                self.writer.paragraph();
+                self.convert(content)?;
+                self.writer.paragraph();
+            }
+            _ if MATH_ENVIRONMENTS.contains(&base) => self.math(content, true),
+            _ if VERBATIM_ENVIRONMENTS.contains(&base) => {
+                let arguments = usize::from(base == "minted");
+                let code = skip_parameters(content, arguments);
+                if !self.skip_code_blocks {
+                    self.writer.paragraph();
+                    for line in code.trim_matches('\n').lines() {
+                        self.writer.raw_line(line.trim_end());
+                    }
+                    self.writer.paragraph();
+                }
+            }
+            _ => {
+                let arguments = usize::from(base == "minipage" || base == "thebibliography");
+                self.writer.paragraph();
+                self.convert(skip_parameters(content, arguments))?;
+                self.writer.paragraph();
+            }
+        }
+
+        Ok(end)
+    }
+
+    /// Starts a list item, with its optional label.
+    fn item(&mut self, label: Option<&str>) -> Result<(), ParserError> {
+        let label = label.map(|label| self.inline(label)).transpose()?;
+        let depth = self.lists.len().saturating_sub(1);
+        let marker = match (self.lists.last_mut(), label) {
+            (Some(List::Descriptions), Some(term)) => format!("{term}: "),
+            (Some(List::Descriptions), None) => String::new(),
+            (_, Some(label)) => format!("{label} "),
+            (Some(List::Numbers(number)), None) => {
+                *number += 1;
+                format!("{number}. ")
+            }
+            (_, None) => "- ".to_string(),
+        };
+
+        self.writer.newline();
+        self.writer.line = format!("{}{marker}", "  ".repeat(depth));
+        Ok(())
+    }
+
+    /// Converts the content of a table, rows being separated by `\\` and cells by `&`.
+    fn table(&mut self, content: &str) -> Result<(), ParserError> {
+        let mut rows = Vec::new();
+        for row in split_rows(content) {
+            let row = strip_rules(row);
+            if row.trim().is_empty() {
+                continue;
+            }
+            let cells = split_cells(row)
+                .into_iter()
+                .map(|cell| self.inline(cell))
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.push(cells);
+        }
+
+        self.writer.paragraph();
+        for line in Table::new(rows).render(self.table_format)?.lines() {
+            self.writer.raw_line(line);
+        }
+        self.writer.paragraph();
+        Ok(())
+    }
+
+    /// Renders a formula, on its own line when displayed.
+    fn math(&mut self, formula: &str, display: bool) {
+        let formula = match self.math_mode {
+            MathMode::Raw => formula.trim(),
+            MathMode::Placeholder => MATH_PLACEHOLDER,
+        };
+        if display {
+            self.writer.newline();
+            self.writer.text(formula);
+            self.writer.newline();
+        } else {
+            self.writer.text(formula);
+        }
+    }
+
+    /// Converts LaTeX text to a single line.
+    fn inline(&mut self, text: &str) -> Result<String, ParserError> {
+        let writer = std::mem::take(&mut self.writer);
+        let result = self.convert(text);
+        let converted = std::mem::replace(&mut self.writer, writer);
+        result?;
+
+        Ok(converted
+            .finish()
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "))
+    }
+
+    /// Renders a link, with its target when links are kept.
+    fn link_text(&self, target: &str, text: &str) -> String {
+        if text.is_empty() {
+            target.to_string()
+        } else if self.keep_links {
+            format!("{text} ({target})")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Returns the content of the group starting at a position, after its opening
+/// brace, and the position after its closing brace.
+fn group(text: &str, start: usize) -> (&str, usize) {
+    let mut depth = 0;
+    let mut characters = text[start..].char_indices();
+
+    while let Some((offset, character)) = characters.next() {
+        match character {
+            '\\' => {
+                characters.next();
+            }
+            '{' => depth += 1,
+            '}' if depth == 0 => return (&text[start..start + offset], start + offset + 1),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    (&text[start..], text.len())
+}
+
+/// Returns the text of the optional argument at a position, if any, and the position
+/// after it.
+fn optional(text: &str, position: usize) -> (Option<&str>, usize) {
+    if !text[position..].starts_with('[') {
+        return (None, position);
+    }
+
+    let mut depth = 0;
+    let mut characters = text[position + 1..].char_indices();
+    while let Some((offset, character)) = characters.next() {
+        match character {
+            '\\' => {
+                characters.next();
+            }
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ']' if depth == 0 => {
+                let end = position + 1 + offset;
+                return (Some(&text[position + 1..end]), end + 1);
+            }
+            _ => {}
+        }
+    }
+
+    (Some(&text[position + 1..]), text.len())
+}
+
+/// Returns the position after the optional arguments at a position.
+fn skip_optionals(text: &str, mut position: usize) -> usize {
+    while let (Some(_), end) = optional(text, position) {
+        position = end;
+    }
+    position
+}
+
+/// Returns the position after the star of a starred command, if any.
+fn skip_star(text: &str, position: usize) -> usize {
+    if text[position..].starts_with('*') {
+        position + 1
+    } else {
+        position
+    }
+}
+
+/// Returns the position after the whitespace at a position.
+fn skip_whitespace(text: &str, position: usize) -> usize {
+    text.len() - text[position..].trim_start().len()
+}
+
+/// Returns the position after a comment, its line break and the indentation of the
+/// next line.
+fn skip_comment(text: &str, position: usize) -> usize {
+    let end = text[position..]
+        .find('\n')
+        .map_or(text.len(), |offset| position + offset + 1);
+    text.len() - text[end..].trim_start_matches([' ', '\t']).len()
+}
+
+/// Returns the mandatory argument at a position: the content of a group, a command
+/// or a single character, and the position after it.
+fn argument(text: &str, position: usize) -> (&str, usize) {
+    let start = skip_whitespace(text, position);
+    let rest = &text[start..];
+
+    match rest.chars().next() {
+        Some('{') => group(text, start + 1),
+        Some('\\') => {
+            // A command is named with letters, or with a single other character
+            let letters = rest[1..]
+                .find(|character: char| !character.is_ascii_alphabetic())
+                .unwrap_or(rest.len() - 1);
+            let length = if letters > 0 {
+                1 + letters
+            } else {
+                1 + rest[1..].chars().next().map_or(0, char::len_utf8)
+            };
+            (&rest[..length], start + length)
+        }
+        Some(character) => (&rest[..character.len_utf8()], start + character.len_utf8()),
+        None => ("", start),
+    }
+}
+
+/// Returns the text up to a closing delimiter that isn't escaped, and the position
+/// after the delimiter.
+fn until<'a>(text: &'a str, start: usize, delimiter: &str) -> (&'a str, usize) {
+    let mut position = start;
+    while let Some(offset) = text[position..].find(delimiter) {
+        let found = position + offset;
+        if delimiter.starts_with('\\') || !text[..found].ends_with('\\') {
+            return (&text[start..found], found + delimiter.len());
+        }
+        position = found + delimiter.len();
+    }
+
+    (&text[start..], text.len())
+}
+
+/// Returns the content of an environment starting at a position, after its
+/// `\begin{name}`, and the position after its `\end{name}`.
+fn environment_content<'a>(text: &'a str, start: usize, name: &str) -> (&'a str, usize) {
+    let begin = format!("\\begin{{{name}}}");
+    let end = format!("\\end{{{name}}}");
+    let mut depth = 0;
+    let mut position = start;
+
+    loop {
+        let next_end = text[position..].find(&end).map(|offset| position + offset);
+        let next_begin = text[position..]
+            .find(&begin)
+            .map(|offset| position + offset);
+        match (next_begin, next_end) {
+            (Some(nested), Some(closing)) if nested < closing => {
+                depth += 1;
+                position = nested + begin.len();
+            }
+            (_, Some(closing)) if depth > 0 => {
+                depth -= 1;
+                position = closing + end.len();
+            }
+            (_, Some(closing)) => return (&text[start..closing], closing + end.len()),
+            (_, None) => return (&text[start..], text.len()),
+        }
+    }
+}
+
+/// Skips the parameters at the start of the content of an environment: optional
+/// arguments, and a number of mandatory arguments.
+fn skip_parameters(content: &str, arguments: usize) -> &str {
+    let mut position = skip_optionals(content, 0);
+    for _ in 0..arguments {
+        position = skip_optionals(content, argument(content, position).1);
+    }
+    &content[position..]
+}
+
+/// Splits the content of a table into rows, on the `\\` outside groups.
+fn split_rows(content: &str) -> Vec<&str> {
+    let mut rows = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut characters = content.char_indices().peekable();
+
+    while let Some((index, character)) = characters.next() {
+        match character {
+            '\\' if depth == 0 && characters.peek().is_some_and(|&(_, next)| next == '\\') => {
+                characters.next();
+                rows.push(&content[start..index]);
+                start = skip_optionals(content, index + 2);
+                while characters.peek().is_some_and(|&(next, _)| next < start) {
+                    characters.next();
+                }
+            }
+            '\\' => {
+                characters.next();
+            }
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    rows.push(&content[start.min(content.len())..]);
+
+    rows
+}
+
+/// Splits a table row into cells, on the `&` outside groups.
+fn split_cells(row: &str) -> Vec<&str> {
+    let mut cells = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut characters = row.char_indices();
+
+    while let Some((index, character)) = characters.next() {
+        match character {
+            '\\' => {
+                characters.next();
+            }
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            '&' if depth == 0 => {
+                cells.push(row[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    cells.push(row[start..].trim());
+
+    cells
+}
+
+/// Removes the horizontal rules at the start of a table row.
+fn strip_rules(mut row: &str) -> &str {
+    loop {
+        let trimmed = row.trim_start();
+        let Some(command) = trimmed.strip_prefix('\\') else {
+            return row;
+        };
+        let length = command
+            .find(|character: char| !character.is_ascii_alphabetic())
+            .unwrap_or(command.len());
+        let rest = &command[length..];
+
+        row = match &command[..length] {
+            "hline" | "toprule" | "midrule" | "bottomrule" | "endhead" | "endfirsthead" => {
+                &rest[skip_optionals(rest, 0)..]
+            }
+            "cline" | "cmidrule" => {
+                // `\cmidrule(lr){2-3}`
+                let rest = rest
+                    .strip_prefix('(')
+                    .and_then(|rest| rest.split_once(')'))
+                    .map_or(rest, |(_, rest)| rest);
+                &rest[argument(rest, 0).1..]
+            }
+            _ => return row,
+        };
+    }
+}
+
+/// Applies an accent to a letter, as in `\'e` or `\c{c}`.
+///
+/// Letters without a precomposed accented form are returned without the accent.
+fn accent(mark: char, argument: &str) -> String {
+    let letter = match argument.trim() {
+        // Dotless i and j, as in `\'{\i}`
+        "\\i" => "i",
+        "\\j" => "j",
+        letter => letter,
+    };
+    let mut characters = letter.chars();
+    let (Some(base), None) = (characters.next(), characters.next()) else {
+        return letter.to_string();
+    };
+
+    let (bases, accented) = match mark {
+        '\'' => ("aeiouyAEIOUYcnszCNSZ", "áéíóúýÁÉÍÓÚÝćńśźĆŃŚŹ"),
+        '`' => ("aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+        '^' => ("aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+        '"' => ("aeiouyAEIOUY", "äëïöüÿÄËÏÖÜŸ"),
+        '~' => ("anoANO", "ãñõÃÑÕ"),
+        '=' => ("aeiouAEIOU", "āēīōūĀĒĪŌŪ"),
+        '.' => ("zZ", "żŻ"),
+        'c' => ("cCsS", "çÇşŞ"),
+        'v' => ("cCsSzZrRnNeE", "čČšŠžŽřŘňŇěĚ"),
+        'u' => ("aAgG", "ăĂğĞ"),
+        'H' => ("oOuU", "őŐűŰ"),
+        'k' => ("aAeE", "ąĄęĘ"),
+        'r' => ("aAuU", "åÅůŮ"),
+        _ => ("", ""),
+    };
+
+    bases
+        .chars()
+        .position(|character| character == base)
+        .and_then(|index| accented.chars().nth(index))
+        .unwrap_or(base)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::join_lines;
+    use super::*;
+
+    fn convert(text: &str, options: &ParseOptions) -> String {
+        join_lines(&convert_latex(text, options).unwrap().lines)
+    }
+
+    #[test]
+    fn convert_latex_success() {
+        let text = r"\documentclass{article}
+\title{A \emph{Short} Note}
+\begin{document}
+\maketitle
+\section*{Lists}
+\begin{enumerate}
+  \item First, see~\ref{sec:a}
+  \begin{itemize}
+    \item Nested
+  \end{itemize}
+  \item Second
+\end{enumerate}
+\begin{description}
+  \item[CPU] The processor
+\end{description}
+% A comment
+
+\begin{verbatim}
+fn main() {}
+\end{verbatim}
+Visit \href{https://example.com}{the site}.\\
+Jos\'{e} na\^{\i}ve \c{c}a, 50\% off\ldots
+\end{document}
+Ignored text";
+        let converted = convert_latex(text, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            join_lines(&converted.lines),
+            "A Short Note
+
+Lists
+
+1. First, see [sec:a]
+  - Nested
+2. Second
+CPU: The processor
+
+fn main() {}
+
+Visit the site.
+José naîve ça, 50% off..."
+        );
+        assert_eq!(converted.title.as_deref(), Some("A Short Note"));
+    }
+
+    #[test]
+    fn convert_latex_options() {
+        let options = ParseOptions {
+            math_mode: MathMode::Placeholder,
+            skip_code_blocks: true,
+            keep_links: true,
+            ..ParseOptions::default()
+        };
+        let text = r"Let $x^2$ be \[ \sum_i x_i \] and \href{https://example.com}{this}.
+\begin{lstlisting}[language=Rust]
+let x = 1;
+\end{lstlisting}
+End";
+
+        assert_eq!(
+            convert(text, &options),
+            "Let [math] be\n[math]\nand this (https://example.com). End"
+        );
+    }
+
+    #[test]
+    fn convert_latex_table() {
+        let text = r"\begin{tabular}{|l|c|}
+\toprule
+Name & \textbf{Value} \\ \midrule
+a\&b & {1 & 2} \\[2pt]
+\bottomrule
+\end{tabular}";
+
+        assert_eq!(
+            convert(text, &ParseOptions::default()),
+            "Name,Value\na&b,1 2"
+        );
+    }
+
+    #[test]
+    fn convert_latex_deeply_nested() {
+        let depth = 100_000;
+        let text = format!(
+            "Top {}deep{} \\emph{{end}}",
+            "{\\textbf".repeat(depth),
+            "}".repeat(depth)
+        );
+
+        assert_eq!(convert(&text, &ParseOptions::default()), "Top end");
+    }
+
+    #[test]
+    fn accent_success() {
+        assert_eq!(accent('\'', "e"), "é");
+        assert_eq!(accent('"', "\\i"), "ï");
+        assert_eq!(accent('v', "s"), "š");
+        assert_eq!(accent('\'', "x"), "x");
+    }
+}
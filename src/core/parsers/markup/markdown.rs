@@ -0,0 +1,330 @@
+//! Markdown converter module.
+//!
+//! This module converts Markdown, including tables, task lists, footnotes and
+//! front matter, to plain text using the events of the `pulldown-cmark` parser.
+
+use super::super::super::{
+    errors::ParserError,
+    options::{ParseOptions, TableFormat},
+};
+use super::super::{html::collapse_whitespace, table::Table};
+use super::Converted;
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+
+/// Converts Markdown to plain text.
+///
+/// # Implementation Notes
+///
+/// * Raw HTML, images and front matter are dropped
+/// * Tables are rendered in the `table_format` option
+/// * The first level 1 heading is the title of the document
+pub(super) fn convert_markdown(
+    text: &str,
+    options: &ParseOptions,
+) -> Result<Converted, ParserError> {
+    let parser_options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+        | Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS
+        | Options::ENABLE_DEFINITION_LIST;
+
+    let mut converter = Converter {
+        skip_code_blocks: options.skip_code_blocks,
+        keep_links: options.keep_links,
+        table_format: options.table_format,
+        ..Converter::default()
+    };
+    for event in Parser::new_ext(text, parser_options) {
+        converter.handle(event)?;
+    }
+    converter.flush();
+
+    Ok(Converted {
+        lines: converter.lines,
+        title: converter.title,
+    })
+}
+
+/// State of the conversion of Markdown events.
+#[derive(Default)]
+struct Converter {
+    /// Whether code blocks are dropped
+    skip_code_blocks: bool,
+    /// Whether link targets are kept
+    keep_links: bool,
+    /// Rendering of tables
+    table_format: TableFormat,
+    /// Converted lines
+    lines: Vec<String>,
+    /// Inline text of the current block
+    inline: String,
+    /// Title of the document
+    title: Option<String>,
+    /// Next number of each open list, `None` for bullet lists
+    lists: Vec<Option<u64>>,
+    /// Marker of the list item whose first line is pending
+    item_marker: Option<String>,
+    /// Targets of the open links
+    links: Vec<String>,
+    /// Content of the code block being read
+    code: Option<String>,
+    /// Rows of the table being read
+    table: Option<Vec<Vec<String>>>,
+    /// Cells of the table row being read
+    row: Vec<String>,
+    /// Depth of the open images, whose alternative text is dropped
+    images: usize,
+    /// Whether front matter is being read
+    metadata: bool,
+    /// Level of the current heading
+    heading: Option<HeadingLevel>,
+}
+
+impl Converter {
+    /// Handles a Markdown event.
+    fn handle(&mut self, event: Event) -> Result<(), ParserError> {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag)?,
+            Event::Text(text) => {
+                if let Some(code) = &mut self.code {
+                    code.push_str(&text);
+                } else if self.images == 0 && !self.metadata {
+                    self.inline.push_str(&text);
+                }
+            }
+            Event::Code(text) | Event::InlineMath(text) | Event::DisplayMath(text) => {
+                self.inline.push_str(&text);
+            }
+            Event::SoftBreak => self.inline.push(' '),
+            Event::HardBreak => self.inline.push('\n'),
+            Event::Rule => self.end_block(),
+            Event::TaskListMarker(checked) => {
+                self.inline.push_str(if checked { "[x] " } else { "[ ] " });
+            }
+            Event::Html(_) | Event::InlineHtml(_) | Event::FootnoteReference(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Handles the start of an element.
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.flush();
+                self.heading = Some(level);
+            }
+            Tag::CodeBlock(_) => {
+                self.flush();
+                self.code = Some(String::new());
+            }
+            Tag::List(start) => {
+                self.flush();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                let depth = self.lists.len().saturating_sub(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.item_marker = Some(format!("{}{marker}", "  ".repeat(depth)));
+            }
+            Tag::Table(_) => {
+                self.flush();
+                self.table = Some(Vec::new());
+            }
+            Tag::TableHead | Tag::TableRow => self.row.clear(),
+            Tag::Link { dest_url, .. } => self.links.push(dest_url.to_string()),
+            Tag::Image { .. } => self.images += 1,
+            Tag::MetadataBlock(_) => self.metadata = true,
+            Tag::Paragraph
+            | Tag::BlockQuote(_)
+            | Tag::FootnoteDefinition(_)
+            | Tag::DefinitionListTitle
+            | Tag::DefinitionListDefinition => self.flush(),
+            _ => {}
+        }
+    }
+
+    /// Handles the end of an element.
+    fn end(&mut self, tag: TagEnd) -> Result<(), ParserError> {
+        match tag {
+            TagEnd::Heading(_) => {
+                if self.heading.take() == Some(HeadingLevel::H1) && self.title.is_none() {
+                    self.title = Some(collapse_whitespace(&self.inline));
+                }
+                self.end_block();
+            }
+            TagEnd::Paragraph
+            | TagEnd::BlockQuote(_)
+            | TagEnd::FootnoteDefinition
+            | TagEnd::DefinitionList
+            | TagEnd::HtmlBlock => self.end_block(),
+            TagEnd::DefinitionListTitle | TagEnd::DefinitionListDefinition | TagEnd::Item => {
+                self.flush();
+            }
+            TagEnd::CodeBlock => {
+                let code = self.code.take().unwrap_or_default();
+                if !self.skip_code_blocks {
+                    let indent = self.indent();
+                    self.lines.extend(
+                        code.trim_end_matches('\n')
+                            .lines()
+                            .map(|line| format!("{indent}{line}")),
+                    );
+                }
+                self.end_block();
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.end_block();
+                }
+            }
+            TagEnd::TableCell => {
+                let cell = collapse_whitespace(&self.inline);
+                self.row.push(cell);
+                self.inline.clear();
+            }
+            TagEnd::TableHead | TagEnd::TableRow => {
+                if let Some(table) = &mut self.table {
+                    table.push(std::mem::take(&mut self.row));
+                }
+            }
+            TagEnd::Table => {
+                let rows = self.table.take().unwrap_or_default();
+                let table = Table {
+                    rows,
+                    has_header: true,
+                };
+                let rendered = table.render(self.table_format)?;
+                self.lines.extend(rendered.lines().map(str::to_string));
+                self.end_block();
+            }
+            TagEnd::Link => {
+                let target = self.links.pop().unwrap_or_default();
+                if self.keep_links && !target.is_empty() && !target.starts_with('#') {
+                    self.inline.push_str(&[" (", &target, ")"].concat());
+                }
+            }
+            TagEnd::Image => self.images = self.images.saturating_sub(1),
+            TagEnd::MetadataBlock(_) => self.metadata = false,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Moves the inline text of the current block to the lines.
+    fn flush(&mut self) {
+        let indent = self.indent();
+        let mut marker = self.item_marker.take();
+
+        for line in self.inline.split('\n') {
+            let line = collapse_whitespace(line);
+            if line.is_empty() {
+                continue;
+            }
+            let prefix = marker.take().unwrap_or_else(|| indent.clone());
+            self.lines.push(format!("{prefix}{line}"));
+        }
+
+        // An item starting with a block keeps its marker for that block
+        if marker.is_some() {
+            self.item_marker = marker;
+        }
+        self.inline.clear();
+    }
+
+    /// Ends the current block, separated from the next one by a blank line outside lists.
+    fn end_block(&mut self) {
+        self.flush();
+        if self.lists.is_empty() {
+            self.lines.push(String::new());
+        }
+    }
+
+    /// Indentation of the continuation lines of the current list item.
+    fn indent(&self) -> String {
+        "  ".repeat(self.lists.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::join_lines;
+    use super::*;
+
+    fn convert(text: &str, options: &ParseOptions) -> String {
+        join_lines(&convert_markdown(text, options).unwrap().lines)
+    }
+
+    #[test]
+    fn convert_markdown_success() {
+        let text = "---
+author: Jane
+---
+
+# Guide
+
+Some **bold** and `code` with a [link](https://example.com).
+
+![logo](logo.png)
+
+1. First
+2. Second
+   - Nested
+   - [x] Done
+
+```rust
+fn main() {}
+```
+
+| Name | Value |
+| ---- | ----- |
+| a    | 1     |
+";
+        let converted = convert_markdown(text, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            join_lines(&converted.lines),
+            "Guide
+
+Some bold and code with a link.
+
+1. First
+2. Second
+  - Nested
+  - [x] Done
+
+fn main() {}
+
+Name,Value
+a,1"
+        );
+        assert_eq!(converted.title.as_deref(), Some("Guide"));
+    }
+
+    #[test]
+    fn convert_markdown_options() {
+        let options = ParseOptions {
+            skip_code_blocks: true,
+            keep_links: true,
+            ..ParseOptions::default()
+        };
+        let text =
+            "See [docs](https://example.com/docs).\n\n    indented code\n\n~~~\nfenced\n~~~\n\nEnd";
+
+        assert_eq!(
+            convert(text, &options),
+            "See docs (https://example.com/docs).\n\nEnd"
+        );
+    }
+}
@@ -0,0 +1,469 @@
+//! reStructuredText converter module.
+//!
+//! This module converts reStructuredText to plain text line by line: section
+//! adornments, directives and comments are removed, and inline markup is reduced
+//! to its text.
+
+use super::super::super::{errors::ParserError, options::ParseOptions};
+use super::Converted;
+use regex::{Captures, Regex};
+
+/// Directives whose content is dropped.
+const SKIPPED_DIRECTIVES: [&str; 13] = [
+    "image",
+    "raw",
+    "include",
+    "literalinclude",
+    "toctree",
+    "contents",
+    "meta",
+    "highlight",
+    "index",
+    "sectnum",
+    "target-notes",
+    "default-role",
+    "role",
+];
+
+/// Directives whose content is source code.
+const CODE_DIRECTIVES: [&str; 4] = ["code-block", "code", "sourcecode", "math"];
+
+/// Converts reStructuredText to plain text.
+///
+/// # Implementation Notes
+///
+/// * The first section title is the title of the document
+/// * Admonitions and other directives keep their content, while images, raw
+///   content and tables of contents are dropped
+/// * Literal blocks (introduced by `::`) and code directives are code blocks
+pub(super) fn convert_rst(text: &str, options: &ParseOptions) -> Result<Converted, ParserError> {
+    let converter = Converter::new(options)?;
+    let lines: Vec<&str> = text.lines().collect();
+    let mut result = Converted {
+        lines: Vec::new(),
+        title: None,
+    };
+
+    converter.convert(&lines, &mut result);
+    Ok(result)
+}
+
+/// Converter of reStructuredText, with the patterns it uses.
+struct Converter {
+    /// Whether code blocks are dropped
+    skip_code_blocks: bool,
+    /// Whether link targets are kept
+    keep_links: bool,
+    /// Directive name and argument, after the `..` marker
+    directive: Regex,
+    /// Footnote or citation label and text, after the `..` marker
+    footnote: Regex,
+    /// List item marker
+    list_item: Regex,
+    /// Field list name and body
+    field: Regex,
+    /// Inline literal
+    literal: Regex,
+    /// Interpreted text with a role prefix
+    role: Regex,
+    /// Interpreted text with a role suffix
+    role_suffix: Regex,
+    /// Hyperlink reference with an embedded target
+    link: Regex,
+    /// Interpreted text or phrase reference
+    interpreted: Regex,
+    /// Strong emphasis
+    strong: Regex,
+    /// Emphasis
+    emphasis: Regex,
+    /// Footnote or citation reference
+    footnote_reference: Regex,
+    /// Substitution reference
+    substitution: Regex,
+    /// Simple hyperlink reference, e.g. `Python_`
+    reference: Regex,
+    /// Escaped character
+    escape: Regex,
+}
+
+impl Converter {
+    /// Compiles the patterns of the converter.
+    fn new(options: &ParseOptions) -> Result<Self, ParserError> {
+        Ok(Self {
+            skip_code_blocks: options.skip_code_blocks,
+            keep_links: options.keep_links,
+            directive: Regex::new(r"^([\w:+.-]+)::(?:\s+(.*))?$")?,
+            footnote: Regex::new(r"^\[([^\]]+)\]\s*(.*)$")?,
+            list_item: Regex::new(r"^(\s*)(?:[-*+•]|#\.|\(?(\d+)[.)])\s+(.*)$")?,
+            field: Regex::new(r"^(\s*):([^:`\s][^:`]*):(?:\s+(.*))?$")?,
+            literal: Regex::new(r"``([^`]+)``")?,
+            role: Regex::new(r":[\w:+.-]+:`([^`]+)`")?,
+            role_suffix: Regex::new(r"`([^`]+)`:[\w:+.-]+:")?,
+            link: Regex::new(r"`([^`<]*?)\s*<([^`>]+)>`__?")?,
+            interpreted: Regex::new(r"`([^`]+)`_{0,2}")?,
+            strong: Regex::new(r"\*\*([^*]+)\*\*")?,
+            emphasis: Regex::new(r"\*([^*\s](?:[^*]*[^*\s])?)\*")?,
+            footnote_reference: Regex::new(r"\s?\[(?:#[\w-]*|\*|\d+|[A-Za-z][\w.-]*)\]_")?,
+            substitution: Regex::new(r"\|([^|\s](?:[^|]*[^|\s])?)\|_{0,2}")?,
+            reference: Regex::new(r"([A-Za-z0-9])__?([\s.,;:!?)]|$)")?,
+            escape: Regex::new(r"\\(.)")?,
+        })
+    }
+
+    /// Converts lines of reStructuredText, appending the result to `converted`.
+    fn convert(&self, lines: &[&str], converted: &mut Converted) {
+        let mut index = 0;
+
+        while index < lines.len() {
+            let line = lines[index];
+            let trimmed = line.trim();
+            let indent = indentation(line);
+            let next = lines.get(index + 1).map_or("", |line| line.trim());
+
+            if trimmed.is_empty() {
+                converted.lines.push(String::new());
+                index += 1;
+            } else if indent == 0
+                && is_adornment(trimmed)
+                && !next.is_empty()
+                && lines
+                    .get(index + 2)
+                    .is_some_and(|line| is_adornment(line.trim()))
+            {
+                // Section title with an overline
+                self.heading(next, converted);
+                index += 3;
+            } else if indent == 0 && !is_adornment(trimmed) && is_adornment(next) {
+                // Section title with an underline
+                self.heading(trimmed, converted);
+                index += 2;
+            } else if is_adornment(trimmed) || is_table_border(trimmed) {
+                // Transitions and table borders
+                index += 1;
+            } else if let Some(marker) = trimmed
+                .strip_prefix("..")
+                .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+            {
+                let (block, end) = indented_block(lines, index + 1, indent);
+                self.explicit_markup(marker.trim(), &block, converted);
+                index = end;
+            } else if trimmed.len() > 1 && trimmed.starts_with('|') && trimmed.ends_with('|') {
+                // Grid table row
+                let cells: Vec<String> = trimmed[1..trimmed.len() - 1]
+                    .split('|')
+                    .map(|cell| self.inline(cell.trim()))
+                    .filter(|cell| !cell.is_empty())
+                    .collect();
+                converted.lines.push(cells.join(" | "));
+                index += 1;
+            } else {
+                index = self.paragraph_line(lines, index, converted);
+            }
+        }
+    }
+
+    /// Adds a section title, the first one being the title of the document.
+    fn heading(&self, text: &str, converted: &mut Converted) {
+        let heading = self.inline(text);
+        if converted.title.is_none() {
+            converted.title = Some(heading.clone());
+        }
+        converted
+            .lines
+            .extend([String::new(), heading, String::new()]);
+    }
+
+    /// Converts a directive, footnote, hyperlink target or comment.
+    fn explicit_markup(&self, marker: &str, block: &[&str], converted: &mut Converted) {
+        if let Some(captures) = self.directive.captures(marker) {
+            let name = captures[1].to_ascii_lowercase();
+            let argument = captures.get(2).map_or("", |argument| argument.as_str());
+            let content = strip_directive_options(block);
+
+            if CODE_DIRECTIVES.contains(&name.as_str()) {
+                self.code_block(&content, converted);
+            } else if name == "figure" {
+                // The argument is the image, the content its caption and legend
+                self.nested(&content, converted);
+            } else if !SKIPPED_DIRECTIVES.contains(&name.as_str()) {
+                converted.lines.push(String::new());
+                if !argument.is_empty() {
+                    converted.lines.push(self.inline(argument));
+                }
+                self.nested(&content, converted);
+            }
+        } else if let Some(captures) = self.footnote.captures(marker) {
+            converted.lines.push(String::new());
+            converted
+                .lines
+                .push(format!("[{}] {}", &captures[1], self.inline(&captures[2])));
+            self.nested(block, converted);
+        }
+        // Hyperlink targets, substitution definitions and comments are dropped
+    }
+
+    /// Converts a line of a paragraph or list, and the literal block it may introduce.
+    ///
+    /// Returns the index of the next line to convert.
+    fn paragraph_line(&self, lines: &[&str], index: usize, converted: &mut Converted) -> usize {
+        let line = lines[index];
+        let trimmed = line.trim_end();
+
+        // A paragraph ending with `::` introduces a literal block
+        let (text, literal) = match trimmed.strip_suffix("::") {
+            Some(text) if text.trim().is_empty() => (String::new(), true),
+            Some(text) if text.ends_with(char::is_whitespace) => (text.to_string(), true),
+            Some(text) => ([text, ":"].concat(), true),
+            None => (trimmed.to_string(), false),
+        };
+
+        if !text.trim().is_empty() {
+            converted.lines.push(self.block_line(&text));
+        }
+        if !literal {
+            return index + 1;
+        }
+
+        let (block, end) = indented_block(lines, index + 1, indentation(line));
+        self.code_block(&block, converted);
+        end
+    }
+
+    /// Converts a line of a paragraph, normalizing list and field markers.
+    fn block_line(&self, line: &str) -> String {
+        if let Some(captures) = self.list_item.captures(line) {
+            let marker = captures
+                .get(2)
+                .map_or_else(|| "-".to_string(), |number| format!("{}.", number.as_str()));
+            format!("{}{marker} {}", &captures[1], self.inline(&captures[3]))
+        } else if let Some(captures) = self.field.captures(line) {
+            let body = captures.get(3).map_or("", |body| body.as_str());
+            format!("{}{}: {}", &captures[1], &captures[2], self.inline(body))
+        } else if let Some(text) = line.trim_start().strip_prefix("| ") {
+            // Line block
+            format!("{}{}", " ".repeat(indentation(line)), self.inline(text))
+        } else {
+            format!(
+                "{}{}",
+                " ".repeat(indentation(line)),
+                self.inline(line.trim())
+            )
+        }
+    }
+
+    /// Adds a code block, unless code blocks are skipped.
+    fn code_block(&self, block: &[&str], converted: &mut Converted) {
+        if self.skip_code_blocks {
+            return;
+        }
+        converted.lines.push(String::new());
+        converted.lines.extend(dedent(block).map(str::to_string));
+        converted.lines.push(String::new());
+    }
+
+    /// Converts the indented content of a directive or footnote.
+    fn nested(&self, block: &[&str], converted: &mut Converted) {
+        let content: Vec<&str> = dedent(block).collect();
+        self.convert(&content, converted);
+        converted.lines.push(String::new());
+    }
+
+    /// Reduces inline markup to its text.
+    fn inline(&self, text: &str) -> String {
+        let text = self.literal.replace_all(text, "$1");
+        let text = self.role.replace_all(&text, |captures: &Captures| {
+            // Cross-references may have an explicit title, e.g. :ref:`Title <target>`
+            let content = captures[1].trim_start_matches(['~', '!']);
+            match content.rsplit_once('<') {
+                Some((title, _)) if content.ends_with('>') && !title.trim().is_empty() => {
+                    title.trim().to_string()
+                }
+                _ => content.to_string(),
+            }
+        });
+        let text = self.role_suffix.replace_all(&text, "$1");
+        let text = self.link.replace_all(&text, |captures: &Captures| {
+            let (title, target) = (&captures[1], &captures[2]);
+            if title.is_empty() {
+                target.to_string()
+            } else if self.keep_links {
+                format!("{title} ({target})")
+            } else {
+                title.to_string()
+            }
+        });
+        let text = self.interpreted.replace_all(&text, "$1");
+        let text = self.strong.replace_all(&text, "$1");
+        let text = self.emphasis.replace_all(&text, "$1");
+        let text = self.footnote_reference.replace_all(&text, "");
+        let text = self.substitution.replace_all(&text, "$1");
+        let text = self.reference.replace_all(&text, "$1$2");
+        self.escape.replace_all(&text, "$1").into_owned()
+    }
+}
+
+/// Whether a line is a section adornment or a transition, e.g. `=====`.
+fn is_adornment(line: &str) -> bool {
+    let mut chars = line.chars();
+    chars.next().is_some_and(|first| {
+        first.is_ascii_punctuation()
+            && line.chars().count() >= 3
+            && chars.all(|character| character == first)
+    })
+}
+
+/// Whether a line is a border of a grid or simple table.
+fn is_table_border(line: &str) -> bool {
+    (line.starts_with('+') && line.chars().all(|character| "+-=:".contains(character)))
+        || (line.contains(' ') && line.chars().all(|character| "= ".contains(character)))
+}
+
+/// Number of leading whitespace characters of a line.
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Collects the lines indented deeper than `indent` from `start`.
+///
+/// Returns the lines, without trailing blank lines, and the index following them.
+fn indented_block<'a>(lines: &[&'a str], start: usize, indent: usize) -> (Vec<&'a str>, usize) {
+    let mut end = start;
+    let mut index = start;
+
+    while let Some(line) = lines.get(index) {
+        if line.trim().is_empty() {
+            index += 1;
+        } else if indentation(line) > indent {
+            index += 1;
+            end = index;
+        } else {
+            break;
+        }
+    }
+
+    (lines[start.min(end)..end].to_vec(), end)
+}
+
+/// Removes the common indentation of lines.
+fn dedent<'a>(lines: &[&'a str]) -> impl Iterator<Item = &'a str> {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indentation(line))
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .map(move |line| line.get(indent..).unwrap_or_else(|| line.trim_start()))
+}
+
+/// Removes the options (`:name: value` lines) at the start of a directive content.
+fn strip_directive_options<'a>(block: &[&'a str]) -> Vec<&'a str> {
+    let options = block
+        .iter()
+        .take_while(|line| line.trim_start().starts_with(':'))
+        .count();
+
+    block[options..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::join_lines;
+    use super::*;
+
+    fn convert(text: &str, options: &ParseOptions) -> String {
+        join_lines(&convert_rst(text, options).unwrap().lines)
+    }
+
+    #[test]
+    fn convert_rst_success() {
+        let text = "=====
+Guide
+=====
+
+:Author: Jane
+
+Intro with **bold**, ``code`` and a `link <https://example.com>`_.
+
+Section
+-------
+
+- First item
+- Second item
+
+  1. Nested
+
+.. note:: Take care.
+
+.. image:: logo.png
+   :alt: Logo
+
+.. This is a comment.
+
+Example::
+
+    print(\"hello\")
+
+See :ref:`the setup <setup>` [#]_.
+";
+        let converted = convert_rst(text, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            join_lines(&converted.lines),
+            "Guide
+
+Author: Jane
+
+Intro with bold, code and a link.
+
+Section
+
+- First item
+- Second item
+
+  1. Nested
+
+Take care.
+
+Example:
+
+print(\"hello\")
+
+See the setup."
+        );
+        assert_eq!(converted.title.as_deref(), Some("Guide"));
+    }
+
+    #[test]
+    fn convert_rst_options() {
+        let options = ParseOptions {
+            skip_code_blocks: true,
+            keep_links: true,
+            ..ParseOptions::default()
+        };
+        let text = "Read the `docs <https://example.com/docs>`_.
+
+.. code-block:: python
+   :linenos:
+
+   import os
+
+End";
+
+        assert_eq!(
+            convert(text, &options),
+            "Read the docs (https://example.com/docs).\n\nEnd"
+        );
+    }
+
+    #[test]
+    fn is_adornment_success() {
+        assert!(is_adornment("====="));
+        assert!(is_adornment("~~~"));
+        assert!(!is_adornment("=="));
+        assert!(!is_adornment("=-="));
+        assert!(!is_adornment("Title"));
+    }
+}
@@ -0,0 +1,299 @@
+//! MBOX parser module.
+//!
+//! This module provides functionality for extracting the messages of MBOX mailboxes,
+//! in which messages follow each other, each introduced by a `From ` line. Messages
+//! are read one at a time from a buffered reader and parsed by the email parser, so
+//! that large mailboxes are never held in memory as a whole.
+
+use super::super::{
+    document::{METADATA_FORMAT, ParsedDocument},
+    errors::ParserError,
+    options::ParseOptions,
+};
+use super::email::{is_eml, parse_eml};
+use std::io::BufRead;
+
+/// Start of the line introducing each message of a mailbox
+const SEPARATOR: &[u8] = b"From ";
+
+/// A message read from a mailbox.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MailboxMessage {
+    /// Position of the message in the mailbox, starting at 0
+    pub index: usize,
+    /// Headers, body and attachments of the message, as parsed from EML
+    pub document: ParsedDocument,
+}
+
+/// Iterator over the messages of an MBOX mailbox, returned by [`parse_mbox`].
+pub struct MboxMessages<R> {
+    /// Reader of the mailbox
+    reader: R,
+    /// Options the messages are parsed with
+    options: ParseOptions,
+    /// Index of the next message
+    index: usize,
+    /// Whether the last line read was empty, as required before a `From ` line
+    after_blank: bool,
+    /// Whether the end of the mailbox or a read error was reached
+    done: bool,
+}
+
+/// Parses the messages of an MBOX mailbox, one at a time.
+///
+/// # Arguments
+///
+/// * `reader` - A buffered reader of the mailbox, such as a `BufReader` of a file
+/// * `options` - The options the messages and their attachments are parsed with
+///
+/// # Returns
+///
+/// An iterator yielding each message with its index, or the error that occurred
+/// while reading or parsing it. Messages following a message that failed to parse
+/// are still read, while a read error ends the iteration.
+///
+/// # Implementation Notes
+///
+/// * A message starts at a `From ` line at the start of the mailbox or after an
+///   empty line
+/// * Body lines quoted as `>From ` (`>>From `, ...) lose one level of quoting
+/// * Each message is parsed like an EML file
+///
+/// # Examples
+///
+/// ```
+/// use parser::{ParseOptions, parse_mbox};
+///
+/// let mailbox = b"From jane@example.com Mon Jan 15 10:30:00 2024
+/// From: jane@example.com
+/// Subject: First
+///
+/// Hello
+///
+/// From john@example.com Mon Jan 15 11:00:00 2024
+/// From: john@example.com
+/// Subject: Second
+///
+/// >From the start
+/// ";
+///
+/// let messages: Vec<_> = parse_mbox(&mailbox[..], &ParseOptions::default())
+///     .collect::<Result<_, _>>()
+///     .expect("Failed to parse mailbox");
+///
+/// assert_eq!(messages.len(), 2);
+/// assert_eq!(messages[1].index, 1);
+/// assert_eq!(
+///     messages[1].document.text,
+///     "From: john@example.com\nSubject: Second\n\nFrom the start"
+/// );
+/// ```
+pub fn parse_mbox<R: BufRead>(reader: R, options: &ParseOptions) -> MboxMessages<R> {
+    MboxMessages {
+        reader,
+        options: options.clone(),
+        index: 0,
+        after_blank: true,
+        done: false,
+    }
+}
+
+impl<R: BufRead> MboxMessages<R> {
+    /// Reads the lines of the next message, without its `From ` line.
+    fn read_message(&mut self) -> Result<Vec<u8>, ParserError> {
+        let mut message = Vec::new();
+
+        loop {
+            let mut line = Vec::new();
+            if self.reader.read_until(b'\n', &mut line)? == 0 {
+                self.done = true;
+                return Ok(message);
+            }
+
+            if line.starts_with(SEPARATOR) && self.after_blank {
+                // Text before the first separator is not a message
+                if message.iter().all(u8::is_ascii_whitespace) {
+                    message.clear();
+                    continue;
+                }
+                return Ok(message);
+            }
+            self.after_blank = line.trim_ascii().is_empty();
+
+            let quotes = line.iter().take_while(|&&byte| byte == b'>').count();
+            if quotes > 0 && line[quotes..].starts_with(SEPARATOR) {
+                message.extend_from_slice(&line[1..]);
+            } else {
+                message.extend_from_slice(&line);
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for MboxMessages<R> {
+    type Item = Result<MailboxMessage, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.done {
+                return None;
+            }
+
+            let message = match self.read_message() {
+                Ok(message) => message,
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(error));
+                }
+            };
+            // Empty messages are skipped, not the end of the mailbox
+            if message.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            let index = self.index;
+            self.index += 1;
+            return Some(
+                parse_eml(&message, &self.options)
+                    .map(|document| MailboxMessage { index, document }),
+            );
+        }
+    }
+}
+
+/// Checks whether data starts with the `From ` line of a mailbox, followed by the
+/// headers of a message.
+pub(crate) fn is_mbox(data: &[u8]) -> bool {
+    data.starts_with(SEPARATOR)
+        && data
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .is_some_and(|end| is_eml(&data[end + 1..]))
+}
+
+/// Parses a whole MBOX mailbox held in memory as a single document.
+///
+/// # Implementation Notes
+///
+/// * Messages after the first one start with a `--- Message N ---` separator
+/// * A message that fails to parse is replaced by its error, the following messages
+///   still being read
+pub(crate) fn parse_mailbox(data: &[u8], options: &ParseOptions) -> ParsedDocument {
+    let text = render_messages(parse_mbox(data, options));
+    ParsedDocument::from(text).with_metadata(METADATA_FORMAT, "mbox")
+}
+
+/// Renders the messages of a mailbox in order, each failed message as its error.
+fn render_messages(messages: impl Iterator<Item = Result<MailboxMessage, ParserError>>) -> String {
+    let mut sections = Vec::new();
+    for (index, message) in messages.enumerate() {
+        if index > 0 {
+            sections.push(format!("\n--- Message {} ---", index + 1));
+        }
+        sections.push(match message {
+            Ok(message) => message.document.text,
+            Err(error) => format!("[Message could not be parsed: {error}]"),
+        });
+    }
+    sections.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::document::METADATA_TITLE;
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_mbox_success() {
+        let data = read_test_file("test_mbox_1.mbox");
+        let messages: Vec<MailboxMessage> = parse_mbox(&data[..], &ParseOptions::default())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].index, 0);
+        assert_eq!(
+            messages[0].document.text,
+            "From: Jane Doe <jane@example.com>
+To: John Smith <john@example.com>
+Date: Mon, 15 Jan 2024 10:30:00 +0000
+Subject: First message
+
+Hello, this is a test mbox for the parsing API.
+From the start, quoted lines are unquoted.
+>From here, one level remains."
+        );
+        assert_eq!(messages[1].index, 1);
+        assert_eq!(
+            messages[1].document.metadata[METADATA_TITLE],
+            "Second message"
+        );
+    }
+
+    #[test]
+    fn parse_mbox_with_empty_message() {
+        let mailbox = b"From jane@example.com Mon Jan 15 10:30:00 2024
+From: jane@example.com
+Subject: First
+
+Hello
+
+From nobody Mon Jan 15 10:45:00 2024
+
+From john@example.com Mon Jan 15 11:00:00 2024
+From: john@example.com
+Subject: Second
+
+Bye
+";
+        let messages: Vec<MailboxMessage> = parse_mbox(&mailbox[..], &ParseOptions::default())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].index, 1);
+        assert_eq!(messages[1].document.metadata[METADATA_TITLE], "Second");
+    }
+
+    #[test]
+    fn parse_mailbox_success() {
+        let data = read_test_file("test_mbox_1.mbox");
+        let result = parse_mailbox(&data, &ParseOptions::default());
+
+        assert!(
+            result
+                .text
+                .contains("\n\n--- Message 2 ---\nFrom: John Smith")
+        );
+        assert!(is_mbox(&data));
+    }
+
+    #[test]
+    fn render_messages_with_failed_message() {
+        let message = |index, text: &str| {
+            Ok(MailboxMessage {
+                index,
+                document: ParsedDocument::from(text.to_string()),
+            })
+        };
+        let messages = vec![
+            message(0, "First"),
+            Err(ParserError::ParseError("Invalid message".to_string())),
+            message(2, "Third"),
+        ];
+
+        assert_eq!(
+            render_messages(messages.into_iter()),
+            "First\n\n--- Message 2 ---\n[Message could not be parsed: Parse error: Invalid message]\n\n--- Message 3 ---\nThird"
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! Jupyter notebook parser module.
+//!
+//! This module provides functionality for extracting the content of Jupyter notebooks
+//! (`.ipynb`), JSON documents holding a list of cells. Markdown cells are converted to
+//! plain text and code cells are kept with the language of the notebook, while the
+//! outputs of code cells are only extracted on request, binary outputs being skipped
+//! unless their images are to be recognised with OCR.
+
+use super::super::{
+    document::{METADATA_FORMAT, ParsedDocument},
+    errors::ParserError,
+    options::ParseOptions,
+};
+use super::{
+    image::parse_image,
+    markup::{MarkupFormat, parse_markup},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// MIME types of the image outputs recognised with OCR
+const IMAGE_TYPES: [&str; 2] = ["image/png", "image/jpeg"];
+
+/// A Jupyter notebook, in the version 4 format.
+#[derive(Deserialize)]
+struct Notebook {
+    /// Cells of the notebook, in order
+    cells: Vec<Cell>,
+    /// Metadata of the notebook, including its kernel
+    #[serde(default)]
+    metadata: NotebookMetadata,
+}
+
+/// Metadata of a notebook.
+#[derive(Deserialize, Default)]
+struct NotebookMetadata {
+    /// Kernel the notebook runs on
+    kernelspec: Option<KernelSpec>,
+    /// Language of the kernel
+    language_info: Option<LanguageInfo>,
+}
+
+/// Kernel a notebook runs on.
+#[derive(Deserialize)]
+struct KernelSpec {
+    /// Language of the kernel, e.g. `python`
+    language: Option<String>,
+}
+
+/// Language of the kernel of a notebook.
+#[derive(Deserialize)]
+struct LanguageInfo {
+    /// Name of the language, e.g. `python`
+    name: Option<String>,
+}
+
+/// A cell of a notebook.
+#[derive(Deserialize)]
+struct Cell {
+    /// Kind of the cell: `markdown`, `code` or `raw`
+    #[serde(rename = "cell_type")]
+    kind: String,
+    /// Content of the cell
+    #[serde(default)]
+    source: Value,
+    /// Outputs of a code cell
+    #[serde(default)]
+    outputs: Vec<Output>,
+}
+
+/// An output of a code cell.
+#[derive(Deserialize)]
+struct Output {
+    /// Kind of the output: `stream`, `execute_result`, `display_data` or `error`
+    #[serde(rename = "output_type")]
+    kind: String,
+    /// Text printed to a stream
+    #[serde(default)]
+    text: Value,
+    /// Representations of a result, keyed by MIME type
+    #[serde(default)]
+    data: Map<String, Value>,
+    /// Name of the exception of an error
+    ename: Option<String>,
+    /// Message of the exception of an error
+    evalue: Option<String>,
+}
+
+/// Checks whether text is a Jupyter notebook.
+pub(crate) fn is_notebook(text: &str) -> bool {
+    let trimmed = text.trim_start_matches('\u{feff}').trim_start();
+    trimmed.starts_with('{')
+        && trimmed.contains("\"nbformat\"")
+        && serde_json::from_str::<Notebook>(trimmed).is_ok()
+}
+
+/// Extracts the content of a Jupyter notebook.
+///
+/// # Arguments
+///
+/// * `text` - The decoded content of the file
+/// * `options` - The parsing options, of which `notebook`, `skip_code_blocks` and the
+///   options of Markdown are used
+///
+/// # Returns
+///
+/// * `Ok(ParsedDocument)` - The content of the cells, with the format in the metadata
+/// * `Err(ParserError)` - If the notebook is not valid JSON in the version 4 format
+///
+/// # Implementation Notes
+///
+/// * Markdown cells are converted like Markdown files, raw cells are kept as is
+/// * Code cells start with a `--- Code: <language> ---` separator, and are dropped
+///   with the `skip_code_blocks` option
+/// * With the `notebook.outputs` option, text outputs (streams, plain text results
+///   and errors) follow their cell after a `--- Output ---` separator
+/// * With the `notebook.ocr_images` option, text is recognised in PNG and JPEG
+///   outputs, other binary outputs being always skipped
+pub(crate) fn parse_notebook(
+    text: &str,
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParserError> {
+    let notebook: Notebook = serde_json::from_str(text.trim_start_matches('\u{feff}'))?;
+    let language = notebook
+        .metadata
+        .language_info
+        .and_then(|info| info.name)
+        .or_else(|| notebook.metadata.kernelspec.and_then(|spec| spec.language))
+        .unwrap_or_else(|| "code".to_string());
+
+    let mut sections = Vec::new();
+    for cell in &notebook.cells {
+        let source = join_text(&cell.source);
+        match cell.kind.as_str() {
+            "markdown" => {
+                sections.push(parse_markup(&source, MarkupFormat::Markdown, options)?.text);
+            }
+            "code" => {
+                if !options.skip_code_blocks && !source.trim().is_empty() {
+                    sections.push(format!("--- Code: {language} ---\n{}", source.trim_end()));
+                }
+                let outputs = render_outputs(&cell.outputs, options);
+                if !outputs.is_empty() {
+                    sections.push(format!("--- Output ---\n{outputs}"));
+                }
+            }
+            _ => sections.push(source.trim().to_string()),
+        }
+    }
+    sections.retain(|section| !section.trim().is_empty());
+
+    Ok(ParsedDocument::from(sections.join("\n\n")).with_metadata(METADATA_FORMAT, "ipynb"))
+}
+
+/// Renders the outputs of a code cell that are extracted according to the options.
+fn render_outputs(outputs: &[Output], options: &ParseOptions) -> String {
+    let mut texts = Vec::new();
+
+    for output in outputs {
+        if options.notebook.outputs {
+            let text = match output.kind.as_str() {
+                "stream" => join_text(&output.text),
+                "error" => [output.ename.as_deref(), output.evalue.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(": "),
+                _ => output
+                    .data
+                    .get("text/plain")
+                    .map(join_text)
+                    .unwrap_or_default(),
+            };
+            texts.push(text);
+        }
+
+        if options.notebook.ocr_images {
+            let images = IMAGE_TYPES
+                .iter()
+                .filter_map(|kind| output.data.get(*kind))
+                .map(join_text);
+            for image in images {
+                // Images that can't be decoded or recognised are skipped
+                let compact: String = image.split_whitespace().collect();
+                if let Some(text) = STANDARD
+                    .decode(compact)
+                    .ok()
+                    .and_then(|data| parse_image(&data).ok())
+                {
+                    texts.push(text);
+                }
+            }
+        }
+    }
+
+    texts
+        .iter()
+        .map(|text| text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Joins multiline text, stored either as a string or as a list of lines.
+fn join_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::options::NotebookOptions;
+    use super::*;
+
+    fn read_test_file(filename: &str) -> String {
+        std::fs::read_to_string(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_notebook_success() {
+        let text = read_test_file("test_ipynb_1.ipynb");
+        let result = parse_notebook(&text, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            result.text,
+            "Analysis
+
+Hello, this is a test ipynb for the parsing API.
+
+--- Code: python ---
+import math
+print(math.pi)
+
+--- Code: python ---
+values = [1, 2, 3]
+sum(values)
+
+--- Code: python ---
+1 / 0"
+        );
+        assert_eq!(result.metadata[METADATA_FORMAT], "ipynb");
+        assert!(is_notebook(&text));
+    }
+
+    #[test]
+    fn parse_notebook_outputs() {
+        let text = read_test_file("test_ipynb_1.ipynb");
+        let options = ParseOptions {
+            skip_code_blocks: true,
+            notebook: NotebookOptions {
+                outputs: true,
+                ..NotebookOptions::default()
+            },
+            ..ParseOptions::default()
+        };
+        let result = parse_notebook(&text, &options).unwrap();
+
+        assert_eq!(
+            result.text,
+            "Analysis
+
+Hello, this is a test ipynb for the parsing API.
+
+--- Output ---
+3.141592653589793
+
+--- Output ---
+6
+
+--- Output ---
+ZeroDivisionError: division by zero"
+        );
+    }
+}
@@ -0,0 +1,420 @@
+//! `OpenDocument` parser module.
+//!
+//! This module provides functionality for extracting text from `OpenDocument` text
+//! documents (ODT) and presentations (ODP), as written by `LibreOffice`. It uses the
+//! zip crate to extract the `content.xml` file and roxmltree to walk its paragraphs,
+//! headings, lists, tables and frames.
+
+use super::super::errors::ParserError;
+use super::package::{open_package, read_text_part};
+use super::xml::check_nesting;
+use roxmltree::{Document, Node};
+
+/// Namespace of the `office:` elements
+const OFFICE_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:office:1.0";
+
+/// Namespace of the `text:` elements
+const TEXT_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:text:1.0";
+
+/// Namespace of the `table:` elements
+const TABLE_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:table:1.0";
+
+/// Namespace of the `draw:` elements
+const DRAW_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:drawing:1.0";
+
+/// Namespace of the `presentation:` elements
+const PRESENTATION_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:presentation:1.0";
+
+/// Deepest nesting of lists indented, deeper lists being indented as the deepest
+const MAX_INDENT_DEPTH: usize = 8;
+
+/// Parses an ODT file and extracts text content.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the ODT data
+///
+/// # Returns
+///
+/// * `Ok(String)` - The extracted text, one paragraph per line
+/// * `Err(ParserError)` - If an error occurs during ODT parsing
+///
+/// # Implementation Notes
+///
+/// * Treats ODT as a ZIP archive and parses its `content.xml` file
+/// * List items are prefixed with `-` and indented by nesting level, up to 8 levels
+/// * Table cells are separated by tabs, one row per line
+/// * Footnotes and text boxes follow the paragraph they are anchored in, while
+///   comments and tracked changes are skipped
+/// * Documents nested deeper than 256 levels are rejected
+pub(crate) fn parse_odt(data: &[u8]) -> Result<String, ParserError> {
+    let content = read_content(data)?;
+    let document = Document::parse(&content)?;
+
+    let mut extractor = Extractor::default();
+    if let Some(body) = find_body(&document, "text") {
+        extractor.blocks(body, 0);
+    }
+
+    Ok(extractor.lines.join("\n").trim().to_string())
+}
+
+/// Parses an ODP file and extracts text content from slides.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the ODP data
+///
+/// # Returns
+///
+/// * `Ok(String)` - The extracted text from the presentation with slide separators
+/// * `Err(ParserError)` - If an error occurs during ODP parsing
+///
+/// # Implementation Notes
+///
+/// * Treats ODP as a ZIP archive and parses its `content.xml` file
+/// * Organizes text by slide number with the same separators as PPTX presentations
+/// * Speaker notes follow the text of their slide, after a `--- Notes ---` separator
+/// * Presentations nested deeper than 256 levels are rejected
+pub(crate) fn parse_odp(data: &[u8]) -> Result<String, ParserError> {
+    let content = read_content(data)?;
+    let document = Document::parse(&content)?;
+
+    let mut extractor = Extractor::default();
+    let pages = find_body(&document, "presentation")
+        .into_iter()
+        .flat_map(|body| body.children())
+        .filter(|node| is_element(*node, DRAW_NS, "page"));
+
+    for (index, page) in pages.enumerate() {
+        if index > 0 {
+            extractor.lines.push(String::new());
+            extractor.lines.push(format!("--- Slide {} ---", index + 1));
+        }
+        extractor.blocks(page, 0);
+
+        let notes_start = extractor.lines.len();
+        for notes in page
+            .children()
+            .filter(|node| is_element(*node, PRESENTATION_NS, "notes"))
+        {
+            extractor.blocks(notes, 0);
+        }
+        if extractor.lines.len() > notes_start {
+            extractor
+                .lines
+                .insert(notes_start, "--- Notes ---".to_string());
+        }
+    }
+
+    Ok(extractor.lines.join("\n").trim().to_string())
+}
+
+/// Reads the `content.xml` file of an `OpenDocument` archive, checking that it is
+/// not nested too deeply to be walked.
+fn read_content(data: &[u8]) -> Result<String, ParserError> {
+    let content = read_text_part(&mut open_package(data)?, "content.xml")?;
+    check_nesting(&content)?;
+    Ok(content)
+}
+
+/// Finds the `office:body` child holding the content of a document kind
+/// (`text`, `presentation`, ...).
+fn find_body<'a, 'input>(document: &'a Document<'input>, kind: &str) -> Option<Node<'a, 'input>> {
+    document
+        .root_element()
+        .children()
+        .find(|node| is_element(*node, OFFICE_NS, "body"))?
+        .children()
+        .find(|node| is_element(*node, OFFICE_NS, kind))
+}
+
+/// Checks whether a node is the element with the given namespace and local name.
+fn is_element(node: Node, namespace: &str, name: &str) -> bool {
+    node.is_element()
+        && node.tag_name().namespace() == Some(namespace)
+        && node.tag_name().name() == name
+}
+
+/// Checks whether an element holds no text of the document (comments, tracked
+/// changes, index templates, speaker notes, ...).
+fn is_skipped(node: Node) -> bool {
+    let tag = node.tag_name();
+    match tag.namespace() {
+        Some(OFFICE_NS) => matches!(tag.name(), "annotation" | "annotation-end" | "forms"),
+        Some(TEXT_NS) => {
+            matches!(
+                tag.name(),
+                "tracked-changes" | "sequence-decls" | "variable-decls" | "user-field-decls"
+            ) || tag.name().ends_with("-source")
+        }
+        Some(DRAW_NS) => tag.name() == "page-thumbnail",
+        Some(PRESENTATION_NS) => tag.name() == "notes",
+        _ => false,
+    }
+}
+
+/// Extractor of the lines of text of a document.
+#[derive(Default)]
+struct Extractor {
+    /// Extracted lines
+    lines: Vec<String>,
+}
+
+impl Extractor {
+    /// Extracts the block elements (paragraphs, lists, tables, frames) of a node.
+    fn blocks(&mut self, node: Node, depth: usize) {
+        for child in node.children().filter(Node::is_element) {
+            if is_skipped(child) {
+                continue;
+            }
+            if is_element(child, TEXT_NS, "p") || is_element(child, TEXT_NS, "h") {
+                self.paragraph(child, &indent(depth));
+            } else if is_element(child, TEXT_NS, "list") {
+                self.list(child, depth);
+            } else if is_element(child, TABLE_NS, "table") {
+                self.table(child);
+            } else {
+                // Sections, frames, text boxes, shapes and groups
+                self.blocks(child, depth);
+            }
+        }
+    }
+
+    /// Extracts the items of a list, nested lists being indented.
+    fn list(&mut self, node: Node, depth: usize) {
+        let items = node.children().filter(|child| {
+            is_element(*child, TEXT_NS, "list-item") || is_element(*child, TEXT_NS, "list-header")
+        });
+
+        for item in items {
+            let mut first = true;
+            for child in item.children().filter(Node::is_element) {
+                if is_element(child, TEXT_NS, "list") {
+                    self.list(child, depth + 1);
+                } else if is_element(child, TEXT_NS, "p") || is_element(child, TEXT_NS, "h") {
+                    let prefix = if first {
+                        format!("{}- ", indent(depth))
+                    } else {
+                        indent(depth + 1)
+                    };
+                    self.paragraph(child, &prefix);
+                    first = false;
+                } else {
+                    self.blocks(child, depth + 1);
+                }
+            }
+        }
+    }
+
+    /// Extracts the rows of a table, one line per row with tab-separated cells.
+    fn table(&mut self, node: Node) {
+        for row in node
+            .descendants()
+            .filter(|child| is_element(*child, TABLE_NS, "table-row"))
+            // Rows of nested tables are part of their cell
+            .filter(|row| {
+                row.ancestors()
+                    .find(|ancestor| is_element(*ancestor, TABLE_NS, "table"))
+                    == Some(node)
+            })
+        {
+            let cells: Vec<String> = row
+                .children()
+                .filter(|cell| is_element(*cell, TABLE_NS, "table-cell"))
+                .map(|cell| {
+                    let mut extractor = Extractor::default();
+                    extractor.blocks(cell, 0);
+                    extractor
+                        .lines
+                        .iter()
+                        .map(|line| line.trim())
+                        .collect::<Vec<&str>>()
+                        .join(" ")
+                })
+                .collect();
+
+            let line = cells.join("\t");
+            if !line.trim().is_empty() {
+                self.lines.push(line.trim_end().to_string());
+            }
+        }
+    }
+
+    /// Extracts a paragraph or heading, followed by its footnotes and text boxes.
+    fn paragraph(&mut self, node: Node, prefix: &str) {
+        let mut text = String::new();
+        let mut anchored = Vec::new();
+        inline_text(node, &mut text, &mut anchored);
+
+        let text = text.trim_matches(' ');
+        if !text.is_empty() {
+            for (index, line) in text.split('\n').enumerate() {
+                if index == 0 {
+                    self.lines.push(format!("{prefix}{line}"));
+                } else {
+                    self.lines
+                        .push(format!("{}{line}", " ".repeat(prefix.len())));
+                }
+            }
+        }
+
+        for node in anchored {
+            self.blocks(node, 0);
+        }
+    }
+}
+
+/// Returns the indentation of a nesting level, capped at [`MAX_INDENT_DEPTH`].
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth.min(MAX_INDENT_DEPTH))
+}
+
+/// Collects the text of a paragraph, whitespace collapsed as required by the
+/// specification, deferring footnote bodies and frames to `anchored`.
+fn inline_text<'a, 'input>(
+    node: Node<'a, 'input>,
+    text: &mut String,
+    anchored: &mut Vec<Node<'a, 'input>>,
+) {
+    for child in node.children() {
+        if child.is_text() {
+            for (index, word) in child
+                .text()
+                .unwrap_or_default()
+                .split(char::is_whitespace)
+                .enumerate()
+            {
+                if index > 0 && !text.ends_with(' ') {
+                    text.push(' ');
+                }
+                text.push_str(word);
+            }
+            continue;
+        }
+        if !child.is_element() || is_skipped(child) {
+            continue;
+        }
+
+        if is_element(child, TEXT_NS, "s") {
+            let count = child
+                .attribute((TEXT_NS, "c"))
+                .and_then(|count| count.parse().ok())
+                .unwrap_or(1);
+            text.push_str(&" ".repeat(count));
+        } else if is_element(child, TEXT_NS, "tab") {
+            text.push('\t');
+        } else if is_element(child, TEXT_NS, "line-break") {
+            text.push('\n');
+        } else if is_element(child, TEXT_NS, "note") {
+            // The citation is a number, the body holds paragraphs
+            anchored.extend(
+                child
+                    .children()
+                    .filter(|node| is_element(*node, TEXT_NS, "note-body")),
+            );
+        } else if is_element(child, DRAW_NS, "frame") || is_element(child, DRAW_NS, "custom-shape")
+        {
+            anchored.push(child);
+        } else {
+            // Spans, links, fields and other inline elements
+            inline_text(child, text, anchored);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    fn build_odt(text: &str) -> Vec<u8> {
+        let content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"><office:body><office:text>{text}</office:text></office:body></office:document-content>"#
+        );
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file("content.xml", SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn parse_odt_success() {
+        let data = read_test_file("test_odt_1.odt");
+        let result = parse_odt(&data).unwrap();
+
+        assert_eq!(
+            result,
+            "Test Document
+Hello, this is a test odt for the parsing API.
+- First item
+  - Nested item
+- Second item
+A footnote.
+Name\tValue
+alpha\t1"
+        );
+    }
+
+    #[test]
+    fn parse_odp_success() {
+        let data = read_test_file("test_odp_1.odp");
+        let result = parse_odp(&data).unwrap();
+
+        assert_eq!(
+            result,
+            "This is the title
+This is the subtitle
+
+--- Slide 2 ---
+This is the title of the second slide
+This is the text of the second slide
+--- Notes ---
+These are the speaker notes"
+        );
+    }
+
+    #[test]
+    fn parse_odt_nested_lists() {
+        let depth = 12;
+        let data = build_odt(&format!(
+            "<text:p>Top</text:p>{}<text:p>Deep</text:p>{}",
+            "<text:list><text:list-item>".repeat(depth),
+            "</text:list-item></text:list>".repeat(depth)
+        ));
+
+        assert_eq!(
+            parse_odt(&data).unwrap(),
+            format!("Top\n{}- Deep", "  ".repeat(MAX_INDENT_DEPTH))
+        );
+    }
+
+    #[test]
+    fn parse_odt_deeply_nested() {
+        let depth = 100_000;
+        let data = build_odt(&format!(
+            "<text:p>Top</text:p>{}<text:p>Deep</text:p>{}",
+            "<text:section>".repeat(depth),
+            "</text:section>".repeat(depth)
+        ));
+
+        assert!(matches!(parse_odt(&data), Err(ParserError::ParseError(_))));
+    }
+
+    #[test]
+    fn parse_odt_invalid() {
+        assert!(parse_odt(b"Not a zip archive").is_err());
+    }
+}
@@ -0,0 +1,274 @@
+//! Word 97-2003 (DOC) text extraction module.
+//!
+//! The text of a Word document is stored in the `WordDocument` stream as pieces,
+//! located by the piece table (`Clx`) of the table stream. Each piece is either
+//! UTF-16 or "compressed" 8-bit Windows-1252 text.
+
+use super::super::super::errors::ParserError;
+use super::{read_stream, read_u16, read_u32, read_usize};
+use cfb::CompoundFile;
+use encoding_rs::WINDOWS_1252;
+use std::io::{Read, Seek};
+
+/// Identifier at the start of the File Information Block
+const WORD_IDENTIFIER: u16 = 0xA5EC;
+
+/// First file format version of Word 97, earlier versions having no piece table
+const WORD_97_VERSION: u16 = 0x00C0;
+
+/// Flag of the File Information Block telling the document is encrypted
+const ENCRYPTED_FLAG: u16 = 0x0100;
+
+/// Flag of the File Information Block telling the table stream is `1Table`
+const TABLE_STREAM_FLAG: u16 = 0x0200;
+
+/// Index of the `fcClx`/`lcbClx` pair in the `FibRgFcLcb` array
+const CLX_INDEX: usize = 33;
+
+/// Index of `ccpText` in the `FibRgLw` array
+const CCP_TEXT_INDEX: usize = 3;
+
+/// Bit of a piece offset telling the piece is 8-bit text
+const COMPRESSED_FLAG: u32 = 0x4000_0000;
+
+/// Location of the text of a Word document, read from its File Information Block.
+struct Fib {
+    /// Whether the table stream is `1Table` rather than `0Table`
+    table_stream: bool,
+    /// Number of characters of the main document
+    ccp_text: usize,
+    /// Offset of the piece table in the table stream
+    fc_clx: usize,
+    /// Length of the piece table
+    lcb_clx: usize,
+}
+
+/// A run of text in the `WordDocument` stream.
+struct Piece {
+    /// Position of the first character in the document
+    cp_start: usize,
+    /// Position following the last character in the document
+    cp_end: usize,
+    /// Offset of the text in the `WordDocument` stream
+    offset: usize,
+    /// Whether the text is 8-bit rather than UTF-16
+    compressed: bool,
+}
+
+/// Extracts the text of the main document of a Word 97-2003 document.
+///
+/// # Implementation Notes
+///
+/// * Only the main document is extracted, not headers, footers or footnotes
+/// * Field instructions are dropped while field results are kept
+/// * Paragraphs are separated by line breaks and table cells by tabs
+pub(super) fn extract_doc_text<F: Read + Seek>(
+    compound: &mut CompoundFile<F>,
+) -> Result<String, ParserError> {
+    let word = read_stream(compound, super::WORD_DOCUMENT_STREAM)?;
+    let fib = read_fib(&word)?;
+
+    let table_stream = if fib.table_stream { "1Table" } else { "0Table" };
+    let table = read_stream(compound, table_stream)?;
+    let clx = fib
+        .fc_clx
+        .checked_add(fib.lcb_clx)
+        .and_then(|end| table.get(fib.fc_clx..end))
+        .ok_or_else(|| invalid("piece table out of bounds"))?;
+
+    let mut text = String::new();
+    for piece in read_pieces(clx)? {
+        let end = piece.cp_end.min(fib.ccp_text);
+        if piece.cp_start >= end {
+            continue;
+        }
+        let count = end - piece.cp_start;
+
+        if piece.compressed {
+            let bytes = piece
+                .offset
+                .checked_add(count)
+                .and_then(|end| word.get(piece.offset..end))
+                .ok_or_else(|| invalid("text piece out of bounds"))?;
+            text.push_str(&WINDOWS_1252.decode_without_bom_handling(bytes).0);
+        } else {
+            let bytes = count
+                .checked_mul(2)
+                .and_then(|length| piece.offset.checked_add(length))
+                .and_then(|end| word.get(piece.offset..end))
+                .ok_or_else(|| invalid("text piece out of bounds"))?;
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                .collect();
+            text.push_str(&String::from_utf16_lossy(&units));
+        }
+    }
+
+    Ok(clean_text(&text))
+}
+
+/// Reads the File Information Block at the start of the `WordDocument` stream.
+fn read_fib(word: &[u8]) -> Result<Fib, ParserError> {
+    if read_u16(word, 0) != Some(WORD_IDENTIFIER) {
+        return Err(invalid("missing File Information Block"));
+    }
+    let version = read_u16(word, 2).unwrap_or_default();
+    let flags = read_u16(word, 10).unwrap_or_default();
+
+    if version < WORD_97_VERSION {
+        return Err(ParserError::InvalidFormat(
+            "Word documents older than Word 97 are not supported".to_string(),
+        ));
+    }
+    if flags & ENCRYPTED_FLAG != 0 {
+        return Err(ParserError::InvalidFormat(
+            "Encrypted Word documents are not supported".to_string(),
+        ));
+    }
+
+    // The FibBase is followed by arrays prefixed by their number of elements
+    let truncated = || invalid("File Information Block is truncated");
+    let shorts = usize::from(read_u16(word, 32).ok_or_else(truncated)?);
+    let longs_offset = 34 + shorts * 2;
+    let longs = usize::from(read_u16(word, longs_offset).ok_or_else(truncated)?);
+    let rg_lw = longs_offset + 2;
+    let pairs_offset = rg_lw + longs * 4;
+    let pairs = usize::from(read_u16(word, pairs_offset).ok_or_else(truncated)?);
+    if longs <= CCP_TEXT_INDEX || pairs <= CLX_INDEX {
+        return Err(truncated());
+    }
+    let clx_offset = pairs_offset + 2 + CLX_INDEX * 8;
+
+    Ok(Fib {
+        table_stream: flags & TABLE_STREAM_FLAG != 0,
+        ccp_text: read_usize(word, rg_lw + CCP_TEXT_INDEX * 4).ok_or_else(truncated)?,
+        fc_clx: read_usize(word, clx_offset).ok_or_else(truncated)?,
+        lcb_clx: read_usize(word, clx_offset + 4).ok_or_else(truncated)?,
+    })
+}
+
+/// Reads the pieces of the piece table, skipping the property modifiers preceding it.
+fn read_pieces(clx: &[u8]) -> Result<Vec<Piece>, ParserError> {
+    let mut position = 0;
+
+    loop {
+        match clx.get(position) {
+            // Prc: array of property modifiers
+            Some(0x01) => {
+                let length = read_u16(clx, position + 1).ok_or_else(|| invalid("truncated Prc"))?;
+                position += 3 + usize::from(length);
+            }
+            // Pcdt: the piece table
+            Some(0x02) => {
+                let length =
+                    read_usize(clx, position + 1).ok_or_else(|| invalid("truncated Pcdt"))?;
+                let plc = clx
+                    .get(position + 5..)
+                    .and_then(|rest| rest.get(..length))
+                    .ok_or_else(|| invalid("truncated Pcdt"))?;
+                return Ok(read_plc_pcd(plc));
+            }
+            _ => return Err(invalid("missing piece table")),
+        }
+    }
+}
+
+/// Reads the pieces of a `PlcPcd`: the character positions of the pieces followed by
+/// their descriptors.
+fn read_plc_pcd(plc: &[u8]) -> Vec<Piece> {
+    let count = plc.len().saturating_sub(4) / 12;
+    let descriptors = (count + 1) * 4;
+
+    (0..count)
+        .filter_map(|index| {
+            let cp_start = read_usize(plc, index * 4)?;
+            let cp_end = read_usize(plc, (index + 1) * 4)?;
+            let fc = read_u32(plc, descriptors + index * 8 + 2)?;
+            let compressed = fc & COMPRESSED_FLAG != 0;
+            let fc = usize::try_from(fc & !COMPRESSED_FLAG).ok()?;
+
+            Some(Piece {
+                cp_start,
+                cp_end,
+                offset: if compressed { fc / 2 } else { fc },
+                compressed,
+            })
+        })
+        .collect()
+}
+
+/// Converts the special characters of Word text: paragraph, cell and row marks,
+/// fields, and anchors of pictures and notes.
+fn clean_text(raw: &str) -> String {
+    let mut text = String::new();
+    // For each open field, whether its instruction is being read
+    let mut fields: Vec<bool> = Vec::new();
+    // Number of open fields whose instruction is being read
+    let mut instructions = 0;
+    let mut previous = '\0';
+
+    for character in raw.chars() {
+        match character {
+            '\u{13}' => {
+                fields.push(true);
+                instructions += 1;
+            }
+            '\u{14}' => {
+                if let Some(instruction) = fields.last_mut().filter(|instruction| **instruction) {
+                    *instruction = false;
+                    instructions -= 1;
+                }
+            }
+            '\u{15}' => {
+                if fields.pop() == Some(true) {
+                    instructions -= 1;
+                }
+            }
+            _ if instructions > 0 => {}
+            // Paragraph marks, line breaks, page and section breaks
+            '\r' | '\u{0B}' | '\u{0C}' => text.push('\n'),
+            // A cell mark following another one ends the row
+            '\u{07}' if previous == '\u{07}' => text.push('\n'),
+            '\u{07}' | '\t' => text.push('\t'),
+            // Non-breaking hyphen
+            '\u{1E}' => text.push('-'),
+            // Anchors of pictures, notes and comments, optional hyphens
+            character if character < ' ' => {}
+            character => text.push(character),
+        }
+        previous = character;
+    }
+
+    text.lines()
+        .map(str::trim_end)
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Creates the error of an invalid Word document.
+fn invalid(reason: &str) -> ParserError {
+    ParserError::ParseError(format!("Invalid Word document: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_text_success() {
+        let raw = "Title\r\u{13} HYPERLINK \"https://example.com\" \u{14}Link\u{15} text\u{1}\ra\u{7}b\u{7}\u{7}c\u{7}d\u{7}\u{7}";
+
+        assert_eq!(clean_text(raw), "Title\nLink text\na\tb\nc\td");
+    }
+
+    #[test]
+    fn clean_text_nested_fields() {
+        // A field in the instruction of another, and stray separators and ends
+        let raw = "\u{13} IF \u{13} PAGE \u{14}1\u{15} = 1 \u{14}Yes\u{15} end\u{14}\u{15}";
+
+        assert_eq!(clean_text(raw), "Yes end");
+    }
+}
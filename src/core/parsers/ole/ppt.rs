@@ -0,0 +1,318 @@
+//! `PowerPoint` 97-2003 (PPT) text extraction module.
+//!
+//! A `PowerPoint` presentation is a tree of records in the `PowerPoint Document`
+//! stream. The text of the placeholders of each slide is listed in order in the
+//! `SlideListWithText` record of the document, while the text of other shapes and of
+//! the speaker notes is stored in the slide and notes records, found through the
+//! persist directory.
+
+use super::super::super::errors::ParserError;
+use super::{read_stream, read_u16, read_u32, read_usize};
+use cfb::CompoundFile;
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+
+/// Stream present in presentations encrypted with a password
+const ENCRYPTED_SUMMARY_STREAM: &str = "EncryptedSummary";
+
+/// Record version of containers, whose data is a list of records
+const CONTAINER_VERSION: u16 = 0xF;
+
+/// Length of a record header
+const HEADER_LENGTH: usize = 8;
+
+/// Deepest nesting of containers read for their text, bounding the recursion
+const MAX_NESTING_DEPTH: usize = 64;
+
+/// Record type of the document container
+const RT_DOCUMENT: u16 = 0x03E8;
+
+/// Record type of slide containers
+const RT_SLIDE: u16 = 0x03EE;
+
+/// Record type of the atom holding the properties of a slide
+const RT_SLIDE_ATOM: u16 = 0x03EF;
+
+/// Record type of notes containers
+const RT_NOTES: u16 = 0x03F0;
+
+/// Record type of the atom starting the entry of a slide in a slide list
+const RT_SLIDE_PERSIST_ATOM: u16 = 0x03F3;
+
+/// Record type of the list of slides and of their placeholder text
+const RT_SLIDE_LIST_WITH_TEXT: u16 = 0x0FF0;
+
+/// Record type of UTF-16 text atoms
+const RT_TEXT_CHARS_ATOM: u16 = 0x0FA0;
+
+/// Record type of 8-bit text atoms
+const RT_TEXT_BYTES_ATOM: u16 = 0x0FA8;
+
+/// Record type of the persist directory, mapping persist identifiers to offsets
+const RT_PERSIST_DIRECTORY_ATOM: u16 = 0x1772;
+
+/// Instance of the slide list listing the presentation slides (rather than masters
+/// or notes)
+const SLIDES_INSTANCE: u16 = 0;
+
+/// A record of the `PowerPoint Document` stream.
+#[derive(Clone, Copy)]
+struct Record<'a> {
+    /// Version of the record, `0xF` for containers
+    version: u16,
+    /// Instance of the record, whose meaning depends on its type
+    instance: u16,
+    /// Type of the record
+    kind: u16,
+    /// Data of the record, the child records of containers
+    data: &'a [u8],
+}
+
+impl<'a> Record<'a> {
+    /// Reads the record at an offset.
+    fn read(stream: &'a [u8], offset: usize) -> Option<Self> {
+        let options = read_u16(stream, offset)?;
+        let kind = read_u16(stream, offset + 2)?;
+        let length = read_usize(stream, offset + 4)?;
+        let start = offset + HEADER_LENGTH;
+
+        Some(Self {
+            version: options & 0x000F,
+            instance: options >> 4,
+            kind,
+            data: stream.get(start..start.checked_add(length)?)?,
+        })
+    }
+
+    /// Iterates over the child records of a container.
+    fn children(&self) -> impl Iterator<Item = Record<'a>> {
+        records(self.data)
+    }
+
+    /// Decodes the text of a text atom.
+    fn text(&self) -> Option<String> {
+        let text = match self.kind {
+            RT_TEXT_CHARS_ATOM => {
+                let units: Vec<u16> = self
+                    .data
+                    .chunks_exact(2)
+                    .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                    .collect();
+                String::from_utf16_lossy(&units)
+            }
+            // The bytes are the low bytes of UTF-16 code units
+            RT_TEXT_BYTES_ATOM => self.data.iter().map(|&byte| char::from(byte)).collect(),
+            _ => return None,
+        };
+
+        // Paragraphs are separated by carriage returns, lines by vertical tabs
+        let text = text.replace(['\r', '\u{0B}'], "\n");
+        let text = text.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+}
+
+/// Iterates over the consecutive records of a byte slice.
+fn records(data: &[u8]) -> impl Iterator<Item = Record<'_>> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        let record = Record::read(data, offset)?;
+        offset += HEADER_LENGTH + record.data.len();
+        Some(record)
+    })
+}
+
+/// A slide of the presentation.
+#[derive(Default)]
+struct Slide {
+    /// Persist identifier of the slide container
+    persist_id: u32,
+    /// Text of the slide
+    texts: Vec<String>,
+    /// Text of the speaker notes
+    notes: Vec<String>,
+}
+
+/// Extracts the text of the slides and notes of a `PowerPoint` 97-2003 presentation.
+///
+/// # Implementation Notes
+///
+/// * Slides are listed in presentation order, with the same separators as PPTX
+///   presentations
+/// * Speaker notes follow the text of their slide, after a `--- Notes ---` separator
+/// * The text of master slides is skipped
+pub(super) fn extract_ppt_text<F: Read + Seek>(
+    compound: &mut CompoundFile<F>,
+) -> Result<String, ParserError> {
+    if compound.is_stream(ENCRYPTED_SUMMARY_STREAM) {
+        return Err(ParserError::InvalidFormat(
+            "Encrypted PowerPoint presentations are not supported".to_string(),
+        ));
+    }
+    let stream = read_stream(compound, super::POWERPOINT_DOCUMENT_STREAM)?;
+
+    // Later persist directories, written by incremental saves, take precedence
+    let mut offsets = HashMap::new();
+    let mut document = None;
+    for record in records(&stream) {
+        match record.kind {
+            RT_PERSIST_DIRECTORY_ATOM => read_persist_directory(record.data, &mut offsets),
+            RT_DOCUMENT => document = Some(record),
+            _ => {}
+        }
+    }
+    let document = document.ok_or_else(|| {
+        ParserError::ParseError("Invalid PowerPoint presentation: missing document".to_string())
+    })?;
+
+    let mut slides = list_slides(&document);
+    for slide in &mut slides {
+        let Some(container) = find_record(&stream, &offsets, slide.persist_id, RT_SLIDE) else {
+            continue;
+        };
+        // Shapes other than placeholders hold their own text
+        for text in collect_texts(&container, 0) {
+            if !slide.texts.contains(&text) {
+                slide.texts.push(text);
+            }
+        }
+
+        let notes_id = container
+            .children()
+            .find(|record| record.kind == RT_SLIDE_ATOM)
+            .and_then(|atom| read_u32(atom.data, 16))
+            .unwrap_or_default();
+        if let Some(notes) = find_record(&stream, &offsets, notes_id, RT_NOTES) {
+            slide.notes = collect_texts(&notes, 0);
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (index, slide) in slides.into_iter().enumerate() {
+        if index > 0 {
+            lines.push(String::new());
+            lines.push(format!("--- Slide {} ---", index + 1));
+        }
+        lines.extend(slide.texts);
+        if !slide.notes.is_empty() {
+            lines.push("--- Notes ---".to_string());
+            lines.extend(slide.notes);
+        }
+    }
+
+    Ok(lines.join("\n").trim().to_string())
+}
+
+/// Lists the slides of the document, with the text of their placeholders.
+fn list_slides(document: &Record) -> Vec<Slide> {
+    let mut slides: Vec<Slide> = Vec::new();
+
+    let slide_list = document.children().find(|record| {
+        record.kind == RT_SLIDE_LIST_WITH_TEXT && record.instance == SLIDES_INSTANCE
+    });
+    for record in slide_list.iter().flat_map(Record::children) {
+        if record.kind == RT_SLIDE_PERSIST_ATOM {
+            slides.push(Slide {
+                persist_id: read_u32(record.data, 0).unwrap_or_default(),
+                ..Slide::default()
+            });
+        } else if let (Some(slide), Some(text)) = (slides.last_mut(), record.text()) {
+            slide.texts.push(text);
+        }
+    }
+
+    slides
+}
+
+/// Adds the entries of a persist directory to the offsets of persist identifiers.
+///
+/// Each entry starts with the first persist identifier (20 bits) and the number of
+/// identifiers (12 bits), followed by their offsets.
+fn read_persist_directory(data: &[u8], offsets: &mut HashMap<u32, usize>) {
+    let mut position = 0;
+    while let Some(entry) = read_u32(data, position) {
+        let first = entry & 0x000F_FFFF;
+        let count = entry >> 20;
+        position += 4;
+
+        for id in first..first + count {
+            let Some(offset) = read_usize(data, position) else {
+                return;
+            };
+            offsets.insert(id, offset);
+            position += 4;
+        }
+    }
+}
+
+/// Finds the record of a persist identifier, if it has the expected type.
+fn find_record<'a>(
+    stream: &'a [u8],
+    offsets: &HashMap<u32, usize>,
+    persist_id: u32,
+    kind: u16,
+) -> Option<Record<'a>> {
+    let offset = *offsets.get(&persist_id)?;
+    Record::read(stream, offset).filter(|record| record.kind == kind)
+}
+
+/// Collects the text atoms of a container and of its descendants, at a nesting
+/// depth, containers nested deeper than [`MAX_NESTING_DEPTH`] being skipped.
+fn collect_texts(container: &Record, depth: usize) -> Vec<String> {
+    let mut texts = Vec::new();
+    for record in container.children() {
+        if record.version == CONTAINER_VERSION {
+            if depth < MAX_NESTING_DEPTH {
+                texts.extend(collect_texts(&record, depth + 1));
+            }
+        } else if let Some(text) = record.text() {
+            texts.push(text);
+        }
+    }
+    texts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_persist_directory_success() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&(1_u32 | (2 << 20)).to_le_bytes());
+        data.extend_from_slice(&100_u32.to_le_bytes());
+        data.extend_from_slice(&200_u32.to_le_bytes());
+
+        let mut offsets = HashMap::new();
+        read_persist_directory(&data, &mut offsets);
+
+        assert_eq!(offsets, HashMap::from([(1, 100), (2, 200)]));
+    }
+
+    #[test]
+    fn collect_texts_deeply_nested() {
+        let header = |options: u16, kind: u16, length: usize| {
+            let mut header = Vec::new();
+            header.extend_from_slice(&options.to_le_bytes());
+            header.extend_from_slice(&kind.to_le_bytes());
+            header.extend_from_slice(&u32::try_from(length).unwrap().to_le_bytes());
+            header
+        };
+        let atom = |text: &str| [header(0, RT_TEXT_BYTES_ATOM, text.len()), text.into()].concat();
+
+        // Containers nested far deeper than the stack could recurse, then a text
+        let depth = 100_000;
+        let deep = atom("Deep");
+        let mut data = Vec::with_capacity(depth * HEADER_LENGTH + 2 * deep.len());
+        for level in 0..depth {
+            let length = (depth - level - 1) * HEADER_LENGTH + deep.len();
+            data.extend(header(CONTAINER_VERSION, RT_SLIDE, length));
+        }
+        data.extend(deep);
+        data.extend(atom("Top"));
+        let data = [header(CONTAINER_VERSION, RT_SLIDE, data.len()), data].concat();
+
+        let container = Record::read(&data, 0).unwrap();
+        assert_eq!(collect_texts(&container, 0), vec!["Top"]);
+    }
+}
@@ -0,0 +1,115 @@
+//! ZIP package reading module.
+//!
+//! PPTX, `OpenDocument`, EPUB and XPS files are ZIP archives of XML parts. This module
+//! provides the functions shared by their parsers and by the table, provenance and
+//! asset extractors to open the archive, read its parts and match their elements.
+
+use super::super::errors::ParserError;
+use roxmltree::Node;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+/// A ZIP package read from a byte slice.
+pub(crate) type Package<'a> = ZipArchive<Cursor<&'a [u8]>>;
+
+/// Opens the ZIP archive of a package.
+pub(crate) fn open_package(data: &[u8]) -> Result<Package<'_>, ParserError> {
+    Ok(ZipArchive::new(Cursor::new(data))?)
+}
+
+/// Reads the raw content of a part of a package.
+///
+/// Interleaved parts, stored as `[0].piece`, `[1].piece`, ... `[n].last.piece` files
+/// in a directory named after the part, are read piece by piece.
+pub(crate) fn read_part(package: &mut Package, name: &str) -> Result<Vec<u8>, ParserError> {
+    let mut content = Vec::new();
+    if package.index_for_name(name).is_some() {
+        package.by_name(name)?.read_to_end(&mut content)?;
+        return Ok(content);
+    }
+
+    for index in 0.. {
+        let piece = format!("{name}/[{index}].piece");
+        if package.index_for_name(&piece).is_none() {
+            package
+                .by_name(&format!("{name}/[{index}].last.piece"))?
+                .read_to_end(&mut content)?;
+            break;
+        }
+        package.by_name(&piece)?.read_to_end(&mut content)?;
+    }
+    Ok(content)
+}
+
+/// Reads a part of a package as UTF-8 text.
+pub(crate) fn read_text_part(package: &mut Package, name: &str) -> Result<String, ParserError> {
+    Ok(String::from_utf8(read_part(package, name)?)?)
+}
+
+/// Returns the directory of a path in the archive, empty at the root.
+pub(crate) fn directory(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(directory, _)| directory)
+}
+
+/// Resolves a URL against a directory of the archive, dropping its fragment and
+/// decoding its percent-escapes. URLs starting with `/` are relative to the root of
+/// the archive.
+pub(crate) fn resolve(base: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or_default();
+
+    let mut bytes = Vec::with_capacity(href.len());
+    let mut rest = href.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = (byte == b'%')
+            .then(|| tail.get(..2))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        if let Some(decoded) = escaped {
+            bytes.push(decoded);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    let href = String::from_utf8_lossy(&bytes);
+
+    let base = if href.starts_with('/') { "" } else { base };
+    let mut segments: Vec<&str> = base.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in href.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Checks whether a node is an element with the given local name.
+///
+/// Namespaces are ignored, as writers are not consistent in declaring them and the
+/// namespaces of XPS and `OpenXPS` are equivalent.
+pub(crate) fn has_name(node: Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_success() {
+        assert_eq!(
+            resolve("OEBPS/text", "../images/a%20b.png#x"),
+            "OEBPS/images/a b.png"
+        );
+        assert_eq!(resolve("", "chapter.xhtml"), "chapter.xhtml");
+        assert_eq!(
+            resolve("Documents/1", "/Documents/1/Pages/1.fpage"),
+            "Documents/1/Pages/1.fpage"
+        );
+    }
+}
@@ -1,10 +1,25 @@
 //! PDF parser module.
 //!
 //! This module provides functionality for extracting text from PDF documents using
-//! the `pdf_extract` library.
+//! the `pdf_extract` library. Markdown output, provenance and table extraction lay
+//! the characters out in lines themselves, to infer headings from their font sizes,
+//! locate them on their page and split them in columns.
 
-use super::super::errors::ParserError;
-use pdf_extract::extract_text_from_mem;
+use super::super::{
+    errors::ParserError,
+    options::{OutputFormat, ParseOptions},
+    provenance::BoundingBox,
+};
+use pdf_extract::{Document, MediaBox, OutputDev, OutputError, Transform, extract_text_from_mem};
+
+/// Ratio to the body font size above which lines are headings
+const HEADING_RATIO: f64 = 1.15;
+
+/// Maximum difference between font sizes considered equal
+const SIZE_TOLERANCE: f64 = 0.5;
+
+/// Ratio to the font size of the horizontal gap starting a new segment of a line
+const SEGMENT_GAP_RATIO: f64 = 1.0;
 
 /// Parses a PDF file and extracts text content.
 ///
@@ -14,6 +29,7 @@ use pdf_extract::extract_text_from_mem;
 /// # Arguments
 ///
 /// * `data` - A byte slice containing the PDF data
+/// * `options` - The parsing options, of which the `output_format` is used
 ///
 /// # Returns
 ///
@@ -24,12 +40,293 @@ use pdf_extract::extract_text_from_mem;
 ///
 /// * Uses the `pdf_extract` library for PDF text extraction
 /// * Trims whitespace from the result before returning
+/// * In Markdown output, lines set in a font larger than the body text become
+///   headings, the largest size being the first level, and pages are separated by
+///   horizontal rules
 /// * TODO: Need to find a way to silence the output of that function since on
 ///   unknown characters it outputs a lot of errors, cluttering the logs.
-pub(crate) fn parse_pdf(data: &[u8]) -> Result<String, ParserError> {
+pub(crate) fn parse_pdf(data: &[u8], options: &ParseOptions) -> Result<String, ParserError> {
+    if options.output_format == OutputFormat::Markdown {
+        return render_markdown(data);
+    }
     Ok(extract_text_from_mem(data)?.trim().to_string())
 }
 
+/// Lines of each page, with whether they follow a vertical gap and their segments.
+pub(crate) type PageSegments = Vec<Vec<(bool, Vec<(f64, String)>)>>;
+
+/// A line of text laid out on a page.
+struct Line {
+    /// Text of the line
+    text: String,
+    /// Largest font size of the characters of the line
+    size: f64,
+    /// Whether the line is separated from the previous one by a vertical gap
+    gap: bool,
+    /// Box enclosing the characters of the line, in points from the top left corner
+    /// of the page
+    bbox: BoundingBox,
+    /// Runs of the line separated by wide horizontal gaps, with their horizontal
+    /// position
+    segments: Vec<(f64, String)>,
+}
+
+/// Output device collecting the lines of each page along with their font sizes.
+///
+/// Characters are laid out in lines as by the plain text output of `pdf_extract`.
+#[derive(Default)]
+struct LineOutput {
+    /// Lines of each page
+    pages: Vec<Vec<Line>>,
+    /// Number of characters set in each font size
+    sizes: Vec<(f64, usize)>,
+    /// Height of the current page
+    page_height: f64,
+    /// Whether the next character starts a word
+    first_char: bool,
+    /// Vertical position of the last character
+    last_y: f64,
+    /// Horizontal position of the end of the last character
+    last_end: f64,
+}
+
+impl OutputDev for LineOutput {
+    fn begin_page(
+        &mut self,
+        _page_num: u32,
+        media_box: &MediaBox,
+        _art_box: Option<(f64, f64, f64, f64)>,
+    ) -> Result<(), OutputError> {
+        self.page_height = media_box.ury - media_box.lly;
+        self.pages.push(Vec::new());
+        Ok(())
+    }
+
+    fn end_page(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn output_character(
+        &mut self,
+        trm: &Transform,
+        width: f64,
+        _spacing: f64,
+        font_size: f64,
+        char: &str,
+    ) -> Result<(), OutputError> {
+        // Side of the square with the area of the transformed font size
+        let size = ((trm.m11 + trm.m21) * (trm.m12 + trm.m22) * font_size * font_size)
+            .abs()
+            .sqrt();
+        // Positions grow downwards from the top of the page, as in the plain text output
+        let (x, y) = (trm.m31, self.page_height - trm.m32);
+        // The glyph is approximated by a box of the font size above the baseline
+        let glyph = BoundingBox::new(x, y - size, width * size, size);
+        let Some(lines) = self.pages.last_mut() else {
+            return Ok(());
+        };
+
+        let jump = (y - self.last_y).abs();
+        let gap = jump > size * 1.5;
+        match lines.last_mut() {
+            Some(line) if !(gap || x < self.last_end && jump > size * 0.5) => {
+                if self.first_char && x > self.last_end + size * 0.1 {
+                    line.text.push(' ');
+                }
+                line.text.push_str(char);
+                match line.segments.last_mut() {
+                    Some((_, segment)) if x <= self.last_end + size * SEGMENT_GAP_RATIO => {
+                        if self.first_char && x > self.last_end + size * 0.1 {
+                            segment.push(' ');
+                        }
+                        segment.push_str(char);
+                    }
+                    _ => line.segments.push((x, char.to_string())),
+                }
+                line.size = line.size.max(size);
+                line.bbox = line.bbox.union(&glyph);
+            }
+            previous => {
+                let gap = gap && previous.is_some();
+                lines.push(Line {
+                    text: char.to_string(),
+                    size,
+                    gap,
+                    bbox: glyph,
+                    segments: vec![(x, char.to_string())],
+                });
+            }
+        }
+
+        if !char.trim().is_empty() {
+            match self
+                .sizes
+                .iter_mut()
+                .find(|(known, _)| (known - size).abs() < SIZE_TOLERANCE)
+            {
+                Some((_, count)) => *count += 1,
+                None => self.sizes.push((size, 1)),
+            }
+        }
+        self.first_char = false;
+        self.last_y = y;
+        self.last_end = x + width * size;
+        Ok(())
+    }
+
+    fn begin_word(&mut self) -> Result<(), OutputError> {
+        self.first_char = true;
+        Ok(())
+    }
+
+    fn end_word(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+}
+
+/// Lays out the characters of a PDF document in lines, page by page.
+fn lay_out(data: &[u8]) -> Result<LineOutput, ParserError> {
+    let mut document = Document::load_mem(data)?;
+    if document.is_encrypted() {
+        document.decrypt("")?;
+    }
+    let mut output = LineOutput::default();
+    pdf_extract::output_doc(&document, &mut output)?;
+    Ok(output)
+}
+
+/// Reads the lines of text of each page of a PDF document, along with their
+/// bounding boxes.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the PDF data
+///
+/// # Returns
+///
+/// * `Ok(Vec<Vec<(String, BoundingBox)>>)` - The lines of each page, with their
+///   bounding boxes in points from the top left corner of the page
+/// * `Err(ParserError)` - If an error occurs during PDF parsing
+pub(crate) fn read_lines(data: &[u8]) -> Result<Vec<Vec<(String, BoundingBox)>>, ParserError> {
+    Ok(lay_out(data)?
+        .pages
+        .into_iter()
+        .map(|lines| {
+            lines
+                .into_iter()
+                .map(|line| {
+                    let text = line.text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    (text, line.bbox)
+                })
+                .filter(|(text, _)| !text.is_empty())
+                .collect()
+        })
+        .collect())
+}
+
+/// Reads the lines of text of each page of a PDF document, split in segments at
+/// wide horizontal gaps, such as those between the columns of a table.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the PDF data
+///
+/// # Returns
+///
+/// * `Ok(PageSegments)` - The non-empty lines of each page, with whether they follow
+///   a vertical gap and their segments along with their horizontal position in points
+/// * `Err(ParserError)` - If an error occurs during PDF parsing
+pub(crate) fn read_segments(data: &[u8]) -> Result<PageSegments, ParserError> {
+    Ok(lay_out(data)?
+        .pages
+        .into_iter()
+        .map(|lines| {
+            lines
+                .into_iter()
+                .map(|line| {
+                    let segments = line
+                        .segments
+                        .into_iter()
+                        .map(|(x, text)| (x, text.split_whitespace().collect::<Vec<_>>().join(" ")))
+                        .filter(|(_, text)| !text.is_empty())
+                        .collect::<Vec<_>>();
+                    (line.gap, segments)
+                })
+                .filter(|(_, segments)| !segments.is_empty())
+                .collect()
+        })
+        .collect())
+}
+
+/// Renders the pages of a PDF document as Markdown, with headings inferred from
+/// the font sizes of their lines.
+fn render_markdown(data: &[u8]) -> Result<String, ParserError> {
+    let output = lay_out(data)?;
+
+    // The body text is set in the font size of most characters
+    let body = output
+        .sizes
+        .iter()
+        .max_by_key(|(_, count)| *count)
+        .map_or(0.0, |(size, _)| *size);
+    let mut headings: Vec<f64> = output
+        .sizes
+        .iter()
+        .map(|(size, _)| *size)
+        .filter(|size| *size > body * HEADING_RATIO)
+        .collect();
+    headings.sort_by(|a, b| b.total_cmp(a));
+    let level = |size: f64| {
+        headings
+            .iter()
+            .position(|heading| (heading - size).abs() < SIZE_TOLERANCE)
+            .map(|index| index.min(5) + 1)
+    };
+
+    let pages: Vec<String> = output
+        .pages
+        .iter()
+        .map(|lines| render_page(lines, level))
+        .filter(|page| !page.is_empty())
+        .collect();
+
+    Ok(pages.join("\n\n---\n\n"))
+}
+
+/// Renders the lines of a page as Markdown blocks, consecutive lines of the same
+/// heading level being merged into one heading.
+fn render_page(lines: &[Line], level: impl Fn(f64) -> Option<usize>) -> String {
+    let mut blocks: Vec<(Option<usize>, String)> = Vec::new();
+
+    for line in lines {
+        let text = line.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            continue;
+        }
+        let heading = level(line.size);
+        match blocks.last_mut() {
+            Some((previous, block)) if *previous == heading && !(line.gap && heading.is_none()) => {
+                block.push(if heading.is_some() { ' ' } else { '\n' });
+                block.push_str(&text);
+            }
+            _ => blocks.push((heading, text)),
+        }
+    }
+
+    blocks
+        .into_iter()
+        .map(|(heading, text)| match heading {
+            Some(level) => format!("{} {text}", "#".repeat(level)),
+            None => text,
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,7 +343,7 @@ mod tests {
     #[test]
     fn parse_pdf_success() {
         let data = read_test_file("test_pdf_1.pdf");
-        let result = parse_pdf(&data).unwrap();
+        let result = parse_pdf(&data, &ParseOptions::default()).unwrap();
 
         assert!(!result.is_empty());
         assert_eq!(
@@ -54,4 +351,41 @@ mod tests {
             "Hello, this is a test pdf for the parsing API.".to_string()
         );
     }
+
+    #[test]
+    fn parse_pdf_markdown_success() {
+        let data = read_test_file("test_pdf_4.pdf");
+        let options = ParseOptions {
+            output_format: OutputFormat::Markdown,
+            ..ParseOptions::default()
+        };
+        let result = parse_pdf(&data, &options).unwrap();
+
+        assert_eq!(
+            result,
+            "# Test PDF Document
+
+## Introduction
+
+Hello, this is a test pdf with headings
+for the parsing API.
+
+---
+
+## Conclusion
+
+The second page ends the document."
+        );
+    }
+
+    #[test]
+    fn read_lines_success() {
+        let pages = read_lines(&read_test_file("test_pdf_4.pdf")).unwrap();
+
+        assert_eq!(pages.len(), 2);
+        let (text, bbox) = &pages[0][0];
+        assert_eq!(text, "Test PDF Document");
+        assert_eq!((bbox.x, bbox.y, bbox.height), (72.0, 48.0, 24.0));
+        assert_eq!(pages[1][1].0, "The second page ends the document.");
+    }
 }
@@ -0,0 +1,388 @@
+//! Personal information parser module.
+//!
+//! This module provides functionality for extracting events from iCalendar files
+//! (`.ics`) and contacts from vCard files (`.vcf`). Both formats are made of content
+//! lines (`NAME;PARAMETER=value:value`), folded over several lines when long and
+//! grouped in `BEGIN`/`END` components, from which the main properties are rendered
+//! as readable text or returned as records.
+
+mod icalendar;
+mod vcard;
+
+pub use self::{
+    icalendar::{CalendarEvent, parse_events},
+    vcard::{Contact, parse_contacts},
+};
+pub(crate) use self::{
+    icalendar::{is_calendar, parse_calendar},
+    vcard::{is_vcard, parse_vcard},
+};
+
+use super::email::{decode_charset, decode_quoted_printable};
+
+/// Deepest nesting of components read, bounding the recursion over components
+const MAX_NESTING_DEPTH: usize = 16;
+
+/// A content line: a property with its parameters and value.
+struct Property {
+    /// Uppercase name of the property, without its group
+    name: String,
+    /// Uppercase names of the parameters, with their unquoted values
+    parameters: Vec<(String, String)>,
+    /// Value of the property, still escaped
+    value: String,
+}
+
+impl Property {
+    /// Parses a content line, unless it has no value.
+    fn parse(line: &str) -> Option<Self> {
+        let separator = find_unquoted(line, ':')?;
+        let (head, value) = (&line[..separator], &line[separator + 1..]);
+
+        let mut fields = split_unquoted(head, ';').into_iter();
+        let name = fields.next()?;
+        // Properties may be grouped, as in `item1.EMAIL`
+        let name = name.rsplit('.').next().unwrap_or(name).to_uppercase();
+
+        let parameters = fields
+            .map(|field| match field.split_once('=') {
+                Some((key, value)) => (key.trim().to_uppercase(), value.replace('"', "")),
+                // vCard 2.1 lists types without a name, as in `TEL;WORK;VOICE`
+                None => ("TYPE".to_string(), field.trim().to_string()),
+            })
+            .collect();
+
+        Some(Self {
+            name,
+            parameters,
+            value: value.to_string(),
+        })
+    }
+
+    /// Returns the value of a parameter, if it is present.
+    fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the value, decoded from quoted-printable (vCard 2.1) if needed.
+    fn raw(&self) -> String {
+        if self
+            .parameter("ENCODING")
+            .is_some_and(|encoding| encoding.eq_ignore_ascii_case("QUOTED-PRINTABLE"))
+        {
+            let decoded = decode_quoted_printable(self.value.as_bytes());
+            decode_charset(&decoded, self.parameter("CHARSET"))
+        } else {
+            self.value.clone()
+        }
+    }
+
+    /// Returns the unescaped text of the value.
+    fn text(&self) -> String {
+        unescape(self.raw().trim())
+    }
+
+    /// Returns the unescaped components of a structured value, such as a name or an
+    /// organization separated by `;`.
+    fn components(&self) -> Vec<String> {
+        split_escaped(&self.raw(), ';')
+            .iter()
+            .map(|component| unescape(component.trim()))
+            .collect()
+    }
+}
+
+/// A component: properties and nested components between `BEGIN` and `END` lines.
+struct Component {
+    /// Uppercase name of the component, such as `VEVENT` or `VCARD`
+    name: String,
+    /// Properties of the component, in order
+    properties: Vec<Property>,
+    /// Nested components, in order
+    components: Vec<Component>,
+}
+
+impl Component {
+    /// Returns the first property with a name, if any.
+    fn property(&self, name: &str) -> Option<&Property> {
+        self.properties
+            .iter()
+            .find(|property| property.name == name)
+    }
+
+    /// Returns the text of the first property with a name, if it is not empty.
+    fn text(&self, name: &str) -> Option<String> {
+        self.property(name)
+            .map(Property::text)
+            .filter(|text| !text.is_empty())
+    }
+
+    /// Returns the properties with a name.
+    fn all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Property> {
+        self.properties
+            .iter()
+            .filter(move |property| property.name == name)
+    }
+
+    /// Collects the components with a name, at any depth.
+    fn find<'a>(&'a self, name: &str, found: &mut Vec<&'a Component>) {
+        if self.name == name {
+            found.push(self);
+        }
+        for component in &self.components {
+            component.find(name, found);
+        }
+    }
+}
+
+/// Reads the top-level components of a file.
+///
+/// Components left open at the end of the file are kept, and properties outside
+/// any component are ignored, as are components nested deeper than
+/// [`MAX_NESTING_DEPTH`].
+fn read_components(text: &str) -> Vec<Component> {
+    let mut roots = Vec::new();
+    let mut stack: Vec<Component> = Vec::new();
+    // Number of components opened beyond the deepest nesting, whose lines are skipped
+    let mut skipped = 0;
+
+    for line in unfold(text) {
+        let Some(property) = Property::parse(&line) else {
+            continue;
+        };
+
+        match property.name.as_str() {
+            "BEGIN" if skipped > 0 || stack.len() >= MAX_NESTING_DEPTH => skipped += 1,
+            "END" if skipped > 0 => skipped -= 1,
+            _ if skipped > 0 => {}
+            "BEGIN" => stack.push(Component {
+                name: property.value.trim().to_uppercase(),
+                properties: Vec::new(),
+                components: Vec::new(),
+            }),
+            "END" => {
+                if let Some(component) = stack.pop() {
+                    match stack.last_mut() {
+                        Some(parent) => parent.components.push(component),
+                        None => roots.push(component),
+                    }
+                }
+            }
+            _ => {
+                if let Some(component) = stack.last_mut() {
+                    component.properties.push(property);
+                }
+            }
+        }
+    }
+
+    // Close the components left open
+    while let Some(component) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.components.push(component),
+            None => roots.push(component),
+        }
+    }
+
+    roots
+}
+
+/// Joins folded lines: lines starting with a space or tab continue the previous one.
+///
+/// Quoted-printable values of vCard 2.1 are continued after a soft line break (`=`)
+/// instead.
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for line in text.trim_start_matches('\u{feff}').lines() {
+        let Some(last) = lines.last_mut() else {
+            lines.push(line.to_string());
+            continue;
+        };
+
+        if let Some(continuation) = line.strip_prefix([' ', '\t']) {
+            last.push_str(continuation);
+        } else if last.ends_with('=') && is_quoted_printable(last) {
+            last.pop();
+            last.push_str(line.trim_start());
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+
+    lines
+}
+
+/// Checks whether the parameters of a content line declare a quoted-printable value.
+fn is_quoted_printable(line: &str) -> bool {
+    find_unquoted(line, ':').is_some_and(|separator| {
+        line[..separator]
+            .to_ascii_uppercase()
+            .contains("QUOTED-PRINTABLE")
+    })
+}
+
+/// Returns the position of the first occurrence of a character outside quotes.
+fn find_unquoted(text: &str, separator: char) -> Option<usize> {
+    let mut quoted = false;
+    text.char_indices().find_map(|(index, character)| {
+        if character == '"' {
+            quoted = !quoted;
+        }
+        (character == separator && !quoted).then_some(index)
+    })
+}
+
+/// Splits text on a separator, except inside quotes.
+fn split_unquoted(text: &str, separator: char) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut rest = text;
+    while let Some(position) = find_unquoted(rest, separator) {
+        fields.push(&rest[..position]);
+        rest = &rest[position + separator.len_utf8()..];
+    }
+    fields.push(rest);
+    fields
+}
+
+/// Splits a value on a separator, except where the separator is escaped.
+fn split_escaped(value: &str, separator: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut characters = value.chars();
+
+    while let Some(character) = characters.next() {
+        if character == '\\' {
+            field.push(character);
+            if let Some(escaped) = characters.next() {
+                field.push(escaped);
+            }
+        } else if character == separator {
+            fields.push(std::mem::take(&mut field));
+        } else {
+            field.push(character);
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+/// Unescapes a text value: `\n` is a line break, while `\,`, `\;` and `\\` stand for
+/// the escaped character.
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut characters = value.chars();
+
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            result.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('n' | 'N') => result.push('\n'),
+            Some(escaped) => result.push(escaped),
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+/// Renders labelled values as lines, line breaks of the values being replaced by
+/// spaces.
+fn render_fields(fields: &[(&str, String)]) -> String {
+    fields
+        .iter()
+        .map(|(label, value)| format!("{label}: {}", value.replace('\n', " ")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Joins the texts of records, each after the first starting with a `--- <label> N ---`
+/// separator.
+fn join_records(texts: Vec<String>, label: &str) -> String {
+    texts
+        .into_iter()
+        .enumerate()
+        .map(|(index, text)| {
+            if index == 0 {
+                text
+            } else {
+                format!("--- {label} {} ---\n{text}", index + 1)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Checks whether text starts with the `BEGIN` line of a component.
+fn starts_with_component(text: &str, name: &str) -> bool {
+    text.trim_start_matches('\u{feff}')
+        .trim_start()
+        .lines()
+        .next()
+        .is_some_and(|line| {
+            line.split_once(':').is_some_and(|(key, value)| {
+                key.trim().eq_ignore_ascii_case("BEGIN") && value.trim().eq_ignore_ascii_case(name)
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unfold_success() {
+        let lines = unfold(
+            "DESCRIPTION:A long\r\n  description\r\nNOTE;ENCODING=QUOTED-PRINTABLE:Caf=\r\n=C3=A9\r\n",
+        );
+
+        assert_eq!(
+            lines,
+            [
+                "DESCRIPTION:A long description",
+                "NOTE;ENCODING=QUOTED-PRINTABLE:Caf=C3=A9"
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_property_success() {
+        let property =
+            Property::parse("item1.ORG;LANGUAGE=en;X-LABEL=\"a:b\":Example\\, Inc.;Sales\\nEU")
+                .unwrap();
+
+        assert_eq!(property.name, "ORG");
+        assert_eq!(property.parameter("X-LABEL"), Some("a:b"));
+        assert_eq!(property.components(), ["Example, Inc.", "Sales\nEU"]);
+
+        let property = Property::parse("TEL;WORK;VOICE:+1 555 0100").unwrap();
+        assert_eq!(property.parameter("TYPE"), Some("WORK"));
+    }
+
+    #[test]
+    fn read_components_deeply_nested() {
+        let depth = 100_000;
+        let text = format!(
+            "BEGIN:VCALENDAR\n{}SUMMARY:Deep\n{}SUMMARY:Top\nBEGIN:VEVENT\nEND:VEVENT\nEND:VCALENDAR\n",
+            "BEGIN:X-NESTED\n".repeat(depth),
+            "END:X-NESTED\n".repeat(depth)
+        );
+        let components = read_components(&text);
+
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].text("SUMMARY").as_deref(), Some("Top"));
+        let mut events = Vec::new();
+        components[0].find("VEVENT", &mut events);
+        assert_eq!(events.len(), 1);
+
+        let mut nested = Vec::new();
+        components[0].find("X-NESTED", &mut nested);
+        assert_eq!(nested.len(), MAX_NESTING_DEPTH - 1);
+    }
+}
@@ -0,0 +1,264 @@
+//! iCalendar (ICS) reading module.
+//!
+//! An iCalendar file holds a `VCALENDAR` component, whose `VEVENT` components
+//! describe events with their summary, dates, location, description and attendees.
+
+use super::super::super::{
+    document::{METADATA_FORMAT, METADATA_TITLE, ParsedDocument},
+    encoding::decode_text,
+    errors::ParserError,
+};
+use super::{Component, Property, join_records, read_components, render_fields};
+use serde::Serialize;
+
+/// An event read from an iCalendar file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CalendarEvent {
+    /// Title of the event
+    pub summary: Option<String>,
+    /// Start of the event, as `YYYY-MM-DD` or `YYYY-MM-DD HH:MM` followed by `UTC` or
+    /// its time zone
+    pub start: Option<String>,
+    /// End of the event, formatted like the start
+    pub end: Option<String>,
+    /// Place of the event
+    pub location: Option<String>,
+    /// Description of the event, which may span several lines
+    pub description: Option<String>,
+    /// Attendees of the event, as `Name <address>` when their name is known
+    pub attendees: Vec<String>,
+}
+
+/// Reads the events of an iCalendar file.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the iCalendar file
+///
+/// # Returns
+///
+/// * `Ok(Vec<CalendarEvent>)` - The events of the calendar, in order
+/// * `Err(ParserError)` - If the data isn't text
+///
+/// # Examples
+///
+/// ```
+/// use parser::parse_events;
+///
+/// let calendar = b"BEGIN:VCALENDAR\r
+/// BEGIN:VEVENT\r
+/// SUMMARY:Team meeting\\, weekly\r
+/// DTSTART:20240115T103000Z\r
+/// ATTENDEE;CN=Jane Doe:mailto:jane@example.com\r
+/// END:VEVENT\r
+/// END:VCALENDAR\r
+/// ";
+///
+/// let events = parse_events(calendar).expect("Failed to parse calendar");
+///
+/// assert_eq!(events[0].summary.as_deref(), Some("Team meeting, weekly"));
+/// assert_eq!(events[0].start.as_deref(), Some("2024-01-15 10:30 UTC"));
+/// assert_eq!(events[0].attendees, ["Jane Doe <jane@example.com>"]);
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ParserError::InvalidFormat`] if the data can't be decoded as text.
+pub fn parse_events(data: &[u8]) -> Result<Vec<CalendarEvent>, ParserError> {
+    let (text, _) = decode_text(data, None)?;
+    Ok(read_events(&read_components(&text)))
+}
+
+/// Checks whether text starts with an iCalendar component.
+pub(crate) fn is_calendar(text: &str) -> bool {
+    super::starts_with_component(text, "VCALENDAR")
+}
+
+/// Renders the events of an iCalendar file as text.
+///
+/// # Implementation Notes
+///
+/// * Each event lists its summary, dates, location and attendees, followed by its
+///   description after a blank line
+/// * Events after the first one start with a `--- Event N ---` separator
+/// * The name of the calendar (`X-WR-CALNAME`) is the title of the document
+pub(crate) fn parse_calendar(text: &str) -> ParsedDocument {
+    let components = read_components(text);
+    let texts = read_events(&components).iter().map(render_event).collect();
+
+    let document = ParsedDocument::from(join_records(texts, "Event"))
+        .with_metadata(METADATA_FORMAT, "icalendar");
+    match components
+        .iter()
+        .find_map(|component| component.text("X-WR-CALNAME"))
+    {
+        Some(name) => document.with_metadata(METADATA_TITLE, name),
+        None => document,
+    }
+}
+
+/// Reads the events of the components of a file.
+fn read_events(components: &[Component]) -> Vec<CalendarEvent> {
+    let mut events = Vec::new();
+    for component in components {
+        component.find("VEVENT", &mut events);
+    }
+
+    events
+        .into_iter()
+        .map(|event| CalendarEvent {
+            summary: event.text("SUMMARY"),
+            start: event.property("DTSTART").map(format_date),
+            end: event.property("DTEND").map(format_date),
+            location: event.text("LOCATION"),
+            description: event.text("DESCRIPTION"),
+            attendees: event.all("ATTENDEE").filter_map(format_attendee).collect(),
+        })
+        .collect()
+}
+
+/// Renders an event as text.
+fn render_event(event: &CalendarEvent) -> String {
+    let mut fields = Vec::new();
+    let values = [
+        ("Summary", &event.summary),
+        ("Start", &event.start),
+        ("End", &event.end),
+        ("Location", &event.location),
+    ];
+    for (label, value) in values {
+        if let Some(value) = value {
+            fields.push((label, value.clone()));
+        }
+    }
+    for attendee in &event.attendees {
+        fields.push(("Attendee", attendee.clone()));
+    }
+
+    let mut sections = vec![render_fields(&fields)];
+    sections.extend(event.description.clone());
+    sections.join("\n\n").trim().to_string()
+}
+
+/// Formats a date or date-time property, with its time zone.
+///
+/// Values that are not in the basic iCalendar format are kept as is.
+fn format_date(property: &Property) -> String {
+    let value = property.text();
+    let (date, time) = match value.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (value.as_str(), None),
+    };
+    let digits = |text: &str| text.bytes().all(|byte| byte.is_ascii_digit());
+    if date.len() != 8 || !digits(date) {
+        return value;
+    }
+    let date = format!("{}-{}-{}", &date[..4], &date[4..6], &date[6..]);
+
+    let Some(time) = time else {
+        return date;
+    };
+    let (time, utc) = match time.strip_suffix(['Z', 'z']) {
+        Some(time) => (time, true),
+        None => (time, false),
+    };
+    if time.len() != 6 || !digits(time) {
+        return value;
+    }
+
+    let mut formatted = format!("{date} {}:{}", &time[..2], &time[2..4]);
+    if &time[4..] != "00" {
+        formatted = format!("{formatted}:{}", &time[4..]);
+    }
+    if utc {
+        formatted.push_str(" UTC");
+    } else if let Some(zone) = property.parameter("TZID") {
+        formatted = format!("{formatted} {zone}");
+    }
+    formatted
+}
+
+/// Formats an attendee as `Name <address>`, its common name and address being
+/// optional.
+fn format_attendee(property: &Property) -> Option<String> {
+    let value = property.text();
+    let address = value
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+        .map_or(value.as_str(), |_| &value[7..])
+        .trim();
+    let name = property.parameter("CN").map(str::trim);
+
+    match (name, address) {
+        (Some(name), "") if !name.is_empty() => Some(name.to_string()),
+        (Some(name), address) if !name.is_empty() && name != address => {
+            Some(format!("{name} <{address}>"))
+        }
+        (_, "") => None,
+        (_, address) => Some(address.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_events_success() {
+        let data = read_test_file("test_ics_1.ics");
+        let events = parse_events(&data).unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0],
+            CalendarEvent {
+                summary: Some("Project kickoff, phase 1".to_string()),
+                start: Some("2024-01-15 10:30 Europe/Paris".to_string()),
+                end: Some("2024-01-15 12:00 Europe/Paris".to_string()),
+                location: Some("Room 4; Building A".to_string()),
+                description: Some(
+                    "Hello, this is a test ics for the parsing API.\nThe agenda follows."
+                        .to_string()
+                ),
+                attendees: vec![
+                    "Jane Doe <jane@example.com>".to_string(),
+                    "john@example.com".to_string()
+                ],
+            }
+        );
+        assert_eq!(events[1].start.as_deref(), Some("2024-01-22"));
+    }
+
+    #[test]
+    fn parse_calendar_success() {
+        let data = read_test_file("test_ics_1.ics");
+        let result = parse_calendar(&String::from_utf8(data).unwrap());
+
+        assert!(result.text.starts_with(
+            "Summary: Project kickoff, phase 1\nStart: 2024-01-15 10:30 Europe/Paris"
+        ));
+        assert!(result.text.contains("\n\n--- Event 2 ---\nSummary: Review"));
+        assert_eq!(result.metadata[METADATA_TITLE], "Team calendar");
+    }
+
+    #[test]
+    fn format_date_success() {
+        let format = |line| format_date(&Property::parse(line).unwrap());
+
+        assert_eq!(
+            format("DTSTART:20240115T103015Z"),
+            "2024-01-15 10:30:15 UTC"
+        );
+        assert_eq!(format("DTSTART;VALUE=DATE:20240115"), "2024-01-15");
+        assert_eq!(format("DTSTART:tomorrow"), "tomorrow");
+    }
+}
@@ -0,0 +1,224 @@
+//! vCard (VCF) reading module.
+//!
+//! A vCard file holds one `VCARD` component per contact, with its formatted name
+//! (`FN`) or structured name (`N`), organization (`ORG`), email addresses (`EMAIL`)
+//! and phone numbers (`TEL`). Versions 2.1, 3.0 and 4.0 are supported.
+
+use super::super::super::{
+    document::{METADATA_FORMAT, ParsedDocument},
+    encoding::decode_text,
+    errors::ParserError,
+};
+use super::{Component, join_records, read_components, render_fields};
+use serde::Serialize;
+
+/// A contact read from a vCard file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Contact {
+    /// Full name of the contact
+    pub name: Option<String>,
+    /// Organization of the contact, followed by its units
+    pub organization: Option<String>,
+    /// Email addresses of the contact
+    pub emails: Vec<String>,
+    /// Phone numbers of the contact
+    pub phones: Vec<String>,
+}
+
+/// Reads the contacts of a vCard file.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the vCard file
+///
+/// # Returns
+///
+/// * `Ok(Vec<Contact>)` - The contacts of the file, in order
+/// * `Err(ParserError)` - If the data isn't text
+///
+/// # Examples
+///
+/// ```
+/// use parser::parse_contacts;
+///
+/// let card = b"BEGIN:VCARD\r
+/// VERSION:3.0\r
+/// N:Doe;Jane;;Dr.;\r
+/// ORG:Example\\, Inc.;Sales\r
+/// TEL;TYPE=work:+1 555 0100\r
+/// END:VCARD\r
+/// ";
+///
+/// let contacts = parse_contacts(card).expect("Failed to parse contacts");
+///
+/// assert_eq!(contacts[0].name.as_deref(), Some("Dr. Jane Doe"));
+/// assert_eq!(contacts[0].organization.as_deref(), Some("Example, Inc., Sales"));
+/// assert_eq!(contacts[0].phones, ["+1 555 0100"]);
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ParserError::InvalidFormat`] if the data can't be decoded as text.
+pub fn parse_contacts(data: &[u8]) -> Result<Vec<Contact>, ParserError> {
+    let (text, _) = decode_text(data, None)?;
+    Ok(read_contacts(&read_components(&text)))
+}
+
+/// Checks whether text starts with a vCard component.
+pub(crate) fn is_vcard(text: &str) -> bool {
+    super::starts_with_component(text, "VCARD")
+}
+
+/// Renders the contacts of a vCard file as text.
+///
+/// # Implementation Notes
+///
+/// * Each contact lists its name, organization, email addresses and phone numbers
+/// * Contacts after the first one start with a `--- Contact N ---` separator
+pub(crate) fn parse_vcard(text: &str) -> ParsedDocument {
+    let texts = read_contacts(&read_components(text))
+        .iter()
+        .map(render_contact)
+        .collect();
+
+    ParsedDocument::from(join_records(texts, "Contact")).with_metadata(METADATA_FORMAT, "vcard")
+}
+
+/// Reads the contacts of the components of a file.
+fn read_contacts(components: &[Component]) -> Vec<Contact> {
+    let mut cards = Vec::new();
+    for component in components {
+        component.find("VCARD", &mut cards);
+    }
+
+    cards
+        .into_iter()
+        .map(|card| Contact {
+            name: card.text("FN").or_else(|| structured_name(card)),
+            organization: card
+                .property("ORG")
+                .map(|organization| join_nonempty(organization.components(), ", "))
+                .filter(|organization| !organization.is_empty()),
+            emails: values(card, "EMAIL", "mailto:"),
+            phones: values(card, "TEL", "tel:"),
+        })
+        .collect()
+}
+
+/// Builds the full name of a contact from its structured name: family name, given
+/// name, additional names, prefixes and suffixes.
+fn structured_name(card: &Component) -> Option<String> {
+    let components = card.property("N")?.components();
+    let component = |index: usize| components.get(index).cloned().unwrap_or_default();
+    let name = join_nonempty(
+        [
+            component(3),
+            component(1),
+            component(2),
+            component(0),
+            component(4),
+        ],
+        " ",
+    );
+    (!name.is_empty()).then_some(name)
+}
+
+/// Returns the non-empty values of the properties with a name, without a URI scheme.
+fn values(card: &Component, name: &str, scheme: &str) -> Vec<String> {
+    card.all(name)
+        .map(|property| {
+            let value = property.text();
+            match value.get(..scheme.len()) {
+                Some(prefix) if prefix.eq_ignore_ascii_case(scheme) => {
+                    value[scheme.len()..].trim().to_string()
+                }
+                _ => value,
+            }
+        })
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Joins the non-empty strings with a separator.
+fn join_nonempty(strings: impl IntoIterator<Item = String>, separator: &str) -> String {
+    strings
+        .into_iter()
+        .filter(|string| !string.is_empty())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Renders a contact as text.
+fn render_contact(contact: &Contact) -> String {
+    let mut fields = Vec::new();
+    if let Some(name) = &contact.name {
+        fields.push(("Name", name.clone()));
+    }
+    if let Some(organization) = &contact.organization {
+        fields.push(("Organization", organization.clone()));
+    }
+    for email in &contact.emails {
+        fields.push(("Email", email.clone()));
+    }
+    for phone in &contact.phones {
+        fields.push(("Phone", phone.clone()));
+    }
+    render_fields(&fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_contacts_success() {
+        let data = read_test_file("test_vcf_1.vcf");
+        let contacts = parse_contacts(&data).unwrap();
+
+        assert_eq!(
+            contacts,
+            [
+                Contact {
+                    name: Some("Jane Doe".to_string()),
+                    organization: Some("Example, Inc., Sales".to_string()),
+                    emails: vec![
+                        "jane@example.com".to_string(),
+                        "jane.doe@home.example".to_string()
+                    ],
+                    phones: vec!["+1 555 0100".to_string()],
+                },
+                Contact {
+                    name: Some("François Martin".to_string()),
+                    organization: None,
+                    emails: vec!["francois@example.fr".to_string()],
+                    phones: vec!["+33 1 23 45 67 89".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_vcard_success() {
+        let data = read_test_file("test_vcf_1.vcf");
+        let result = parse_vcard(&String::from_utf8(data).unwrap());
+
+        assert!(result.text.starts_with(
+            "Name: Jane Doe\nOrganization: Example, Inc., Sales\nEmail: jane@example.com"
+        ));
+        assert!(
+            result
+                .text
+                .contains("\n\n--- Contact 2 ---\nName: François Martin")
+        );
+        assert_eq!(result.metadata[METADATA_FORMAT], "vcard");
+    }
+}
@@ -4,10 +4,24 @@
 //! PPTX presentation files. It uses the zip crate to extract slide XML files and
 //! regex to extract text content.
 
-use super::super::errors::ParserError;
+use super::super::{
+    errors::ParserError,
+    options::{OutputFormat, ParseOptions},
+};
+use super::package::{Package, directory, has_name, open_package, read_text_part, resolve};
 use regex::Regex;
-use std::io::{Cursor, Read};
-use zip::ZipArchive;
+use roxmltree::Document;
+use std::collections::HashMap;
+
+/// Path of the presentation part
+const PRESENTATION_PATH: &str = "ppt/presentation.xml";
+
+/// Path of the relationships of the presentation part
+const PRESENTATION_RELATIONSHIPS_PATH: &str = "ppt/_rels/presentation.xml.rels";
+
+/// Namespace of the relationship identifiers
+const RELATIONSHIPS_NS: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
 
 /// Parses a PPTX file and extracts text content from slides.
 ///
@@ -17,6 +31,7 @@ use zip::ZipArchive;
 /// # Arguments
 ///
 /// * `data` - A byte slice containing the PPTX data
+/// * `options` - The parsing options, of which the `output_format` is used
 ///
 /// # Returns
 ///
@@ -25,47 +40,40 @@ use zip::ZipArchive;
 ///
 /// # Implementation Notes
 ///
-/// * Treats PPTX as a ZIP archive and extracts slide XML files
+/// * Treats PPTX as a ZIP archive and extracts slide XML files, ordered as in the
+///   presentation (see [`slide_paths`])
 /// * Uses regex to find text elements in the slide XML
 /// * Organizes text by slide number with clear slide separators
 /// * Handles XML content without requiring a full XML parser
-pub(crate) fn parse_pptx(data: &[u8]) -> Result<String, ParserError> {
-    // Create a cursor to read from the byte data
-    let cursor = Cursor::new(data);
-
-    // Create a zip archive from the cursor
-    let mut archive = ZipArchive::new(cursor)?;
+/// * In Markdown output, each slide is a `## Slide <number>` section with one
+///   paragraph per text paragraph of the slide
+pub(crate) fn parse_pptx(data: &[u8], options: &ParseOptions) -> Result<String, ParserError> {
+    // Open the zip archive of the presentation
+    let mut archive = open_package(data)?;
 
     // Create regex once, outside the loop
     let text_pattern = Regex::new(r"<a:t[^>]*>([^<]+)</a:t>")?;
 
+    if options.output_format == OutputFormat::Markdown {
+        return render_markdown(&mut archive, &text_pattern);
+    }
+
     let mut text = String::new();
-    let mut slide_count = 0;
-
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i)?;
-
-        // Only process slide XML files
-        let is_xml = std::path::Path::new(file.name())
-            .extension()
-            .is_some_and(|ext| ext.eq_ignore_ascii_case("xml"));
-        if file.name().starts_with("ppt/slides/slide") && is_xml {
-            slide_count += 1;
-            if slide_count > 1 {
-                text.push_str("\n--- Slide ");
-                text.push_str(&slide_count.to_string());
-                text.push_str(" ---\n");
-            }
 
-            let mut content = String::new();
-            file.read_to_string(&mut content)?;
+    for (path, slide) in slide_paths(&mut archive)?.iter().zip(1..) {
+        if slide > 1 {
+            text.push_str("\n--- Slide ");
+            text.push_str(&slide.to_string());
+            text.push_str(" ---\n");
+        }
+
+        let content = read_text_part(&mut archive, path)?;
 
-            for cap in text_pattern.captures_iter(&content) {
-                // Use get() instead of array indexing to be extra safe
-                if let Some(matched) = cap.get(1) {
-                    text.push_str(matched.as_str());
-                    text.push('\n');
-                }
+        for cap in text_pattern.captures_iter(&content) {
+            // Use get() instead of array indexing to be extra safe
+            if let Some(matched) = cap.get(1) {
+                text.push_str(matched.as_str());
+                text.push('\n');
             }
         }
     }
@@ -73,9 +81,83 @@ pub(crate) fn parse_pptx(data: &[u8]) -> Result<String, ParserError> {
     Ok(text.trim().to_string())
 }
 
+/// Renders the slides of a presentation as Markdown sections.
+fn render_markdown(archive: &mut Package, text_pattern: &Regex) -> Result<String, ParserError> {
+    let paragraph_pattern = Regex::new(r"(?s)<a:p(?:\s[^>]*)?>(.*?)</a:p>")?;
+
+    let mut sections = Vec::new();
+    for (path, slide) in slide_paths(archive)?.iter().zip(1..) {
+        let content = read_text_part(archive, path)?;
+
+        let mut section = format!("## Slide {slide}");
+        for paragraph in paragraph_pattern.captures_iter(&content) {
+            let text = text_pattern
+                .captures_iter(paragraph.get(1).map_or("", |matched| matched.as_str()))
+                .filter_map(|cap| cap.get(1))
+                .map(|matched| matched.as_str())
+                .collect::<String>();
+            if !text.trim().is_empty() {
+                section.push_str("\n\n");
+                section.push_str(text.trim());
+            }
+        }
+        sections.push(section);
+    }
+
+    Ok(sections.join("\n\n"))
+}
+
+/// Lists the paths of the slide parts of a presentation in presentation order.
+///
+/// Slides follow the slide list of the presentation part, their relationship
+/// targets being resolved from its directory. Without the presentation part or its
+/// relationships, slides follow the numbers of their parts.
+pub(crate) fn slide_paths(package: &mut Package) -> Result<Vec<String>, ParserError> {
+    if package.index_for_name(PRESENTATION_PATH).is_some()
+        && package
+            .index_for_name(PRESENTATION_RELATIONSHIPS_PATH)
+            .is_some()
+    {
+        let relationships = read_text_part(package, PRESENTATION_RELATIONSHIPS_PATH)?;
+        let relationships = Document::parse(&relationships)?;
+        let targets: HashMap<&str, &str> = relationships
+            .descendants()
+            .filter(|node| has_name(*node, "Relationship"))
+            .filter_map(|node| Some((node.attribute("Id")?, node.attribute("Target")?)))
+            .collect();
+        let presentation = read_text_part(package, PRESENTATION_PATH)?;
+
+        return Ok(Document::parse(&presentation)?
+            .descendants()
+            .filter(|node| has_name(*node, "sldId"))
+            .filter_map(|node| node.attribute((RELATIONSHIPS_NS, "id")))
+            .filter_map(|id| targets.get(id))
+            .map(|target| resolve(directory(PRESENTATION_PATH), target))
+            .filter(|path| package.index_for_name(path).is_some())
+            .collect());
+    }
+
+    let mut paths: Vec<(u32, String)> = package
+        .file_names()
+        .filter_map(|name| {
+            let number = name
+                .strip_prefix("ppt/slides/slide")?
+                .strip_suffix(".xml")?
+                .parse()
+                .ok()?;
+            Some((number, name.to_string()))
+        })
+        .collect();
+    paths.sort();
+
+    Ok(paths.into_iter().map(|(_, path)| path).collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::{Cursor, Write};
+    use zip::{ZipWriter, write::SimpleFileOptions};
 
     fn read_test_file(filename: &str) -> Vec<u8> {
         std::fs::read(
@@ -89,7 +171,7 @@ mod tests {
     #[test]
     fn parse_pptx_success() {
         let data = read_test_file("test_pptx_1.pptx");
-        let result = parse_pptx(&data).unwrap();
+        let result = parse_pptx(&data, &ParseOptions::default()).unwrap();
 
         assert!(!result.is_empty());
         assert_eq!(
@@ -103,4 +185,67 @@ This is the text of the second slide"
                 .to_string()
         );
     }
+
+    #[test]
+    fn parse_pptx_markdown_success() {
+        let data = read_test_file("test_pptx_1.pptx");
+        let options = ParseOptions {
+            output_format: OutputFormat::Markdown,
+            ..ParseOptions::default()
+        };
+        let result = parse_pptx(&data, &options).unwrap();
+
+        assert_eq!(
+            result,
+            "## Slide 1
+
+This is the title
+
+This is the subtitle
+
+## Slide 2
+
+This is the title of the second slide
+
+This is the text of the second slide"
+        );
+    }
+
+    #[test]
+    fn parse_pptx_in_slide_list_order() {
+        let slide = |text: &str| {
+            format!(
+                r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:spTree><p:sp><p:txBody><a:p><a:r><a:t>{text}</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#
+            )
+        };
+        let parts = [
+            (
+                "ppt/presentation.xml",
+                r#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:sldIdLst><p:sldId id="256" r:id="rId2"/><p:sldId id="257" r:id="rId1"/></p:sldIdLst></p:presentation>"#.to_string(),
+            ),
+            (
+                "ppt/_rels/presentation.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Target="slides/slide1.xml"/><Relationship Id="rId2" Target="../ppt/slides/slide2.xml"/></Relationships>"#.to_string(),
+            ),
+            ("ppt/slides/slide1.xml", slide("Moved to the end")),
+            ("ppt/slides/slide2.xml", slide("Moved to the start")),
+        ];
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in parts {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        let data = writer.finish().unwrap().into_inner();
+
+        assert_eq!(
+            slide_paths(&mut open_package(&data).unwrap()).unwrap(),
+            vec!["ppt/slides/slide2.xml", "ppt/slides/slide1.xml"]
+        );
+        assert_eq!(
+            parse_pptx(&data, &ParseOptions::default()).unwrap(),
+            "Moved to the start\n\n--- Slide 2 ---\nMoved to the end"
+        );
+    }
 }
@@ -0,0 +1,477 @@
+//! RTF parser module.
+//!
+//! This module provides functionality for extracting text from Rich Text Format
+//! documents. The control words of the document are interpreted by a small reader:
+//! formatting is dropped, destinations such as font tables or pictures are skipped,
+//! and escaped characters are decoded from the code page of the document.
+
+use super::super::{
+    document::{METADATA_AUTHOR, METADATA_FORMAT, METADATA_TITLE, ParsedDocument},
+    errors::ParserError,
+};
+use encoding_rs::{Encoding, WINDOWS_1252};
+
+/// Destinations whose content is not part of the text of the document.
+const SKIPPED_DESTINATIONS: [&str; 31] = [
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "listtable",
+    "listoverridetable",
+    "revtbl",
+    "rsidtbl",
+    "generator",
+    "pict",
+    "object",
+    "objdata",
+    "fldinst",
+    "datafield",
+    "themedata",
+    "colorschememapping",
+    "latentstyles",
+    "datastore",
+    "xmlnstbl",
+    "mmathPr",
+    "filetbl",
+    "pgdsctbl",
+    "header",
+    "headerl",
+    "headerr",
+    "headerf",
+    "footer",
+    "footerl",
+    "footerr",
+    "footerf",
+    "bkmkstart",
+    "bkmkend",
+];
+
+/// Longest control word accepted by the specification.
+const MAX_CONTROL_WORD_LENGTH: usize = 32;
+
+/// Checks whether data starts with the RTF signature (`{\rtf`).
+pub(crate) fn is_rtf(data: &[u8]) -> bool {
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    data.trim_ascii_start().starts_with(b"{\\rtf")
+}
+
+/// Parses an RTF document and extracts its text.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the RTF data
+///
+/// # Returns
+///
+/// * `Ok(ParsedDocument)` - The text of the document, with its title and author
+///   in the metadata when the document information provides them
+/// * `Err(ParserError)` - If the data doesn't start with the RTF signature
+///
+/// # Implementation Notes
+///
+/// * Font, color and style tables, pictures, embedded objects, field instructions,
+///   headers and footers are skipped, as are all `\*` destinations
+/// * `\'hh` escapes are decoded from the code page declared by `\ansicpg`,
+///   Windows-1252 by default
+/// * `\uN` escapes are decoded as UTF-16, skipping the fallback characters declared
+///   by `\ucN`
+/// * Paragraphs and table rows are separated by line breaks, table cells by tabs
+pub(crate) fn parse_rtf(data: &[u8]) -> Result<ParsedDocument, ParserError> {
+    if !is_rtf(data) {
+        return Err(ParserError::InvalidFormat(
+            "Missing RTF signature".to_string(),
+        ));
+    }
+
+    let mut reader = Reader::new(data);
+    reader.read();
+
+    let mut document =
+        ParsedDocument::from(clean_text(&reader.text)).with_metadata(METADATA_FORMAT, "rtf");
+    for (key, value) in [
+        (METADATA_TITLE, reader.title),
+        (METADATA_AUTHOR, reader.author),
+    ] {
+        let value = value.trim();
+        if !value.is_empty() {
+            document = document.with_metadata(key, value);
+        }
+    }
+    Ok(document)
+}
+
+/// Where the text of a group goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Destination {
+    /// Text of the document
+    Text,
+    /// Document information, of which only the title and author are kept
+    Info,
+    /// Title of the document
+    Title,
+    /// Author of the document
+    Author,
+    /// Content that isn't text, such as font tables and pictures
+    Skipped,
+}
+
+/// State of a group, inherited by its nested groups.
+#[derive(Debug, Clone, Copy)]
+struct Group {
+    /// Destination of the text of the group
+    destination: Destination,
+    /// Number of fallback characters following a `\uN` escape
+    unicode_skip: usize,
+}
+
+/// Reader of the control words and text of an RTF document.
+struct Reader<'a> {
+    /// Data of the document
+    data: &'a [u8],
+    /// Position of the next byte to read
+    position: usize,
+    /// Encoding of escaped and 8-bit characters
+    encoding: &'static Encoding,
+    /// Open groups, the current one last
+    groups: Vec<Group>,
+    /// Escaped and 8-bit characters waiting to be decoded
+    pending: Vec<u8>,
+    /// High surrogate of a `\uN` escape waiting for its low surrogate
+    surrogate: Option<u16>,
+    /// Fallback characters left to skip after a `\uN` escape
+    skip: usize,
+    /// Text of the document
+    text: String,
+    /// Title of the document
+    title: String,
+    /// Author of the document
+    author: String,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            encoding: WINDOWS_1252,
+            groups: Vec::new(),
+            pending: Vec::new(),
+            surrogate: None,
+            skip: 0,
+            text: String::new(),
+            title: String::new(),
+            author: String::new(),
+        }
+    }
+
+    /// Reads the document up to the end of its outermost group.
+    fn read(&mut self) {
+        while let Some(&byte) = self.data.get(self.position) {
+            self.position += 1;
+            match byte {
+                b'{' => {
+                    self.flush();
+                    let group = self.groups.last().copied().unwrap_or(Group {
+                        destination: Destination::Text,
+                        unicode_skip: 1,
+                    });
+                    self.groups.push(group);
+                }
+                b'}' => {
+                    self.flush();
+                    self.skip = 0;
+                    self.groups.pop();
+                    if self.groups.is_empty() {
+                        break;
+                    }
+                }
+                b'\\' => self.control(),
+                // Line breaks of the file are not part of the text
+                b'\r' | b'\n' => {}
+                _ => self.character(byte),
+            }
+        }
+        self.flush();
+    }
+
+    /// Reads a control word or control symbol, after its backslash.
+    fn control(&mut self) {
+        let Some(&symbol) = self.data.get(self.position) else {
+            return;
+        };
+
+        if symbol.is_ascii_alphabetic() {
+            let (word, parameter) = self.control_word();
+            self.handle_word(&word, parameter);
+            return;
+        }
+
+        self.position += 1;
+        match symbol {
+            b'\'' => {
+                let hex = self.data.get(self.position..self.position + 2);
+                if let Some(byte) = hex
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    self.position += 2;
+                    self.character(byte);
+                }
+            }
+            b'\\' | b'{' | b'}' => self.character(symbol),
+            b'~' => self.push_char('\u{a0}'),
+            b'_' => self.push_char('\u{2011}'),
+            b'*' => self.set_destination(Destination::Skipped),
+            b'\r' | b'\n' => self.push_char('\n'),
+            // Optional hyphens and formula characters
+            _ => {}
+        }
+    }
+
+    /// Reads the letters and numeric parameter of a control word.
+    fn control_word(&mut self) -> (String, Option<i32>) {
+        let start = self.position;
+        while self.position - start < MAX_CONTROL_WORD_LENGTH
+            && self
+                .data
+                .get(self.position)
+                .is_some_and(u8::is_ascii_alphabetic)
+        {
+            self.position += 1;
+        }
+        let word = String::from_utf8_lossy(&self.data[start..self.position]).into_owned();
+
+        let number_start = self.position;
+        if self.data.get(self.position) == Some(&b'-') {
+            self.position += 1;
+        }
+        while self.data.get(self.position).is_some_and(u8::is_ascii_digit) {
+            self.position += 1;
+        }
+        let parameter = std::str::from_utf8(&self.data[number_start..self.position])
+            .ok()
+            .and_then(|number| number.parse::<i64>().ok())
+            .map(|number| {
+                i32::try_from(number.clamp(i64::from(i32::MIN), i64::from(i32::MAX)))
+                    .unwrap_or_default()
+            });
+
+        // A space delimiting the control word is part of it
+        if self.data.get(self.position) == Some(&b' ') {
+            self.position += 1;
+        }
+
+        (word, parameter)
+    }
+
+    /// Interprets a control word.
+    fn handle_word(&mut self, word: &str, parameter: Option<i32>) {
+        if self.skip > 0 {
+            self.skip -= 1;
+            return;
+        }
+
+        match word {
+            "ansicpg" => {
+                self.flush();
+                if let Some(encoding) = parameter.and_then(code_page_encoding) {
+                    self.encoding = encoding;
+                }
+            }
+            "u" => {
+                self.flush();
+                // Parameters are signed 16-bit values
+                let unit = parameter.unwrap_or_default().rem_euclid(0x10000);
+                self.push_unicode(u16::try_from(unit).unwrap_or_default());
+                self.skip = self.groups.last().map_or(1, |group| group.unicode_skip);
+            }
+            "uc" => {
+                if let Some(group) = self.groups.last_mut() {
+                    group.unicode_skip = usize::try_from(parameter.unwrap_or(1)).unwrap_or(0);
+                }
+            }
+            "par" | "line" | "sect" | "page" | "row" => self.push_char('\n'),
+            "tab" | "cell" => self.push_char('\t'),
+            "emdash" => self.push_char('\u{2014}'),
+            "endash" => self.push_char('\u{2013}'),
+            "emspace" | "enspace" | "qmspace" => self.push_char(' '),
+            "bullet" => self.push_char('\u{2022}'),
+            "lquote" => self.push_char('\u{2018}'),
+            "rquote" => self.push_char('\u{2019}'),
+            "ldblquote" => self.push_char('\u{201c}'),
+            "rdblquote" => self.push_char('\u{201d}'),
+            "bin" => {
+                // Binary data of the given length follows
+                let length = usize::try_from(parameter.unwrap_or_default()).unwrap_or_default();
+                self.position = self.position.saturating_add(length).min(self.data.len());
+            }
+            "info" => self.set_destination(Destination::Info),
+            "title" => self.set_destination(Destination::Title),
+            "author" => self.set_destination(Destination::Author),
+            _ if SKIPPED_DESTINATIONS.contains(&word) => {
+                self.set_destination(Destination::Skipped);
+            }
+            // Formatting and unknown control words
+            _ => {}
+        }
+    }
+
+    /// Adds a character byte, decoded with the others once the run ends.
+    fn character(&mut self, byte: u8) {
+        if self.skip > 0 {
+            self.skip -= 1;
+        } else {
+            self.pending.push(byte);
+        }
+    }
+
+    /// Decodes the pending character bytes.
+    fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let bytes = std::mem::take(&mut self.pending);
+        let (text, _) = self.encoding.decode_without_bom_handling(&bytes);
+        self.push_str(&text);
+    }
+
+    /// Adds a UTF-16 code unit, pairing surrogates.
+    fn push_unicode(&mut self, unit: u16) {
+        let character = match (self.surrogate.take(), unit) {
+            (_, 0xD800..=0xDBFF) => {
+                self.surrogate = Some(unit);
+                return;
+            }
+            (Some(high), 0xDC00..=0xDFFF) => char::decode_utf16([high, unit]).next(),
+            (_, unit) => char::decode_utf16([unit]).next(),
+        };
+        self.push_char(
+            character
+                .and_then(Result::ok)
+                .unwrap_or(char::REPLACEMENT_CHARACTER),
+        );
+    }
+
+    fn push_char(&mut self, character: char) {
+        self.flush();
+        self.push_str(character.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Adds text to the destination of the current group.
+    fn push_str(&mut self, text: &str) {
+        let destination = self
+            .groups
+            .last()
+            .map_or(Destination::Text, |group| group.destination);
+        match destination {
+            Destination::Text => self.text.push_str(text),
+            Destination::Title => self.title.push_str(text),
+            Destination::Author => self.author.push_str(text),
+            Destination::Info | Destination::Skipped => {}
+        }
+    }
+
+    /// Sets the destination of the current group, skipped content staying skipped.
+    fn set_destination(&mut self, destination: Destination) {
+        self.flush();
+        if let Some(group) = self.groups.last_mut()
+            && group.destination != Destination::Skipped
+        {
+            group.destination = destination;
+        }
+    }
+}
+
+/// Finds the encoding of a Windows code page.
+fn code_page_encoding(code_page: i32) -> Option<&'static Encoding> {
+    let label = match code_page {
+        874 => "windows-874".to_string(),
+        866 => "ibm866".to_string(),
+        932 => "shift_jis".to_string(),
+        936 => "gbk".to_string(),
+        949 => "euc-kr".to_string(),
+        950 => "big5".to_string(),
+        1250..=1258 => format!("windows-{code_page}"),
+        10000 => "macintosh".to_string(),
+        20866 => "koi8-r".to_string(),
+        28591..=28606 => format!("iso-8859-{}", code_page - 28590),
+        65001 => "utf-8".to_string(),
+        _ => return None,
+    };
+    Encoding::for_label(label.as_bytes())
+}
+
+/// Trims the lines of the text and collapses runs of blank lines.
+fn clean_text(text: &str) -> String {
+    let mut cleaned = String::new();
+    let mut blank_lines = 0;
+
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        if !cleaned.is_empty() {
+            cleaned.push_str(if blank_lines > 0 { "\n\n" } else { "\n" });
+        }
+        blank_lines = 0;
+        cleaned.push_str(line);
+    }
+
+    cleaned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_rtf_success() {
+        let data = read_test_file("test_rtf_1.rtf");
+        let result = parse_rtf(&data).unwrap();
+
+        assert_eq!(
+            result.text,
+            "Hello, this is a test rtf for the parsing API.\nCaf\u{e9} \u{2014} 10 \u{20ac}"
+        );
+        assert_eq!(result.metadata[METADATA_FORMAT], "rtf");
+        assert_eq!(result.metadata[METADATA_TITLE], "Test RTF");
+        assert_eq!(result.metadata[METADATA_AUTHOR], "Jane Doe");
+    }
+
+    #[test]
+    fn parse_rtf_escapes() {
+        let data = br"{\rtf1\ansi\ansicpg1251\uc2 {\*\generator Test;}\'cf\'f0\'e8\'e2\'e5\'f2 \u-10179\'3f\'3f\u-8704?? {\pict\pngblip 89504e47}\par
+a\cell b\cell\row}";
+        let result = parse_rtf(data).unwrap();
+
+        assert_eq!(
+            result.text,
+            "\u{41f}\u{440}\u{438}\u{432}\u{435}\u{442} \u{1f600}\na\tb"
+        );
+    }
+
+    #[test]
+    fn parse_rtf_invalid() {
+        assert!(matches!(
+            parse_rtf(b"Not an RTF document"),
+            Err(ParserError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn is_rtf_success() {
+        assert!(is_rtf(b"{\\rtf1\\ansi Hello}"));
+        assert!(is_rtf(b"\xEF\xBB\xBF\r\n{\\rtf1 Hello}"));
+        assert!(!is_rtf(b"{\"rtf\": 1}"));
+    }
+}
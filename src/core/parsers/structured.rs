@@ -0,0 +1,430 @@
+//! Structured data parser module.
+//!
+//! This module provides functionality for parsing structured data files (JSON,
+//! NDJSON, YAML and TOML) using `serde_json`, `serde_yaml_ng` and `toml`. Documents
+//! are validated and either pretty-printed, flattened to `path.to.key: value` lines
+//! or reduced to their string values, which suits search indexing best.
+
+use super::super::{
+    document::{METADATA_FORMAT, ParsedDocument},
+    errors::ParserError,
+    options::{ParseOptions, StructuredMode},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Serializer, Value, ser::PrettyFormatter};
+
+/// Indentation used when pretty-printing JSON
+const JSON_INDENT: &[u8] = b"    ";
+
+/// Structured data formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StructuredFormat {
+    /// A single JSON document
+    Json,
+    /// Newline-delimited JSON, one document per line
+    Ndjson,
+    /// One or more YAML documents
+    Yaml,
+    /// A TOML document
+    Toml,
+}
+
+impl StructuredFormat {
+    /// Finds the structured format matching a file extension.
+    pub(crate) fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "json" | "geojson" => Some(Self::Json),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    /// Guesses whether text without a file name is JSON or NDJSON.
+    ///
+    /// Only text starting like a JSON object or array is considered, and it must
+    /// parse, so that plain text is never mistaken for structured data. YAML and
+    /// TOML are too close to plain text to be guessed reliably.
+    pub(crate) fn sniff(text: &str) -> Option<Self> {
+        let trimmed = text.trim_start_matches('\u{feff}').trim();
+        if !trimmed.starts_with(['{', '[']) {
+            return None;
+        }
+
+        if serde_json::from_str::<Value>(trimmed).is_ok() {
+            return Some(Self::Json);
+        }
+
+        let mut lines = trimmed.lines().filter(|line| !line.trim().is_empty());
+        let ndjson = lines.clone().count() >= 2
+            && lines.all(|line| serde_json::from_str::<Value>(line).is_ok());
+        ndjson.then_some(Self::Ndjson)
+    }
+
+    /// Name of the format, as reported in the metadata.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Ndjson => "ndjson",
+            Self::Yaml => "yaml",
+            Self::Toml => "toml",
+        }
+    }
+}
+
+/// Parses structured data and renders it according to the options.
+///
+/// # Arguments
+///
+/// * `text` - The decoded content of the file
+/// * `format` - The format of the content
+/// * `options` - The parsing options, of which the `structured_mode` and `strict`
+///   flag are used
+///
+/// # Returns
+///
+/// * `Ok(ParsedDocument)` - The rendered data, with the format in the metadata.
+///   Invalid documents are returned as is, without format, unless in strict mode
+/// * `Err(ParserError)` - If the document is invalid and strict mode is on
+///
+/// # Implementation Notes
+///
+/// * JSON is pretty-printed with 4-space indentation, keeping the key order
+/// * NDJSON records are pretty-printed one after the other, blank lines are skipped
+/// * YAML streams may hold several documents, separated by `---` when pretty-printed
+/// * Flattened paths join keys with dots and index arrays with brackets
+pub(crate) fn parse_structured(
+    text: &str,
+    format: StructuredFormat,
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParserError> {
+    match render(text, format, options.structured_mode) {
+        Ok(rendered) => {
+            Ok(ParsedDocument::from(rendered).with_metadata(METADATA_FORMAT, format.name()))
+        }
+        Err(err) if options.strict => Err(err),
+        Err(_) => Ok(ParsedDocument::from(text.to_string())),
+    }
+}
+
+/// Parses and renders a structured document.
+fn render(
+    text: &str,
+    format: StructuredFormat,
+    mode: StructuredMode,
+) -> Result<String, ParserError> {
+    let text = text.trim_start_matches('\u{feff}');
+
+    match (format, mode) {
+        (StructuredFormat::Json, StructuredMode::Pretty) => {
+            to_pretty_json(&serde_json::from_str::<Value>(text)?)
+        }
+        (StructuredFormat::Ndjson, StructuredMode::Pretty) => Ok(parse_ndjson(text)?
+            .iter()
+            .map(to_pretty_json)
+            .collect::<Result<Vec<String>, ParserError>>()?
+            .join("\n")),
+        (StructuredFormat::Yaml, StructuredMode::Pretty) => Ok(parse_yaml(text)?
+            .iter()
+            .map(|document| Ok(serde_yaml_ng::to_string(document)?.trim_end().to_string()))
+            .collect::<Result<Vec<String>, ParserError>>()?
+            .join("\n---\n")),
+        (StructuredFormat::Toml, StructuredMode::Pretty) => Ok(toml::to_string_pretty(
+            &toml::from_str::<toml::Table>(text)?,
+        )?
+        .trim_end()
+        .to_string()),
+        (format, mode) => {
+            let value = to_json_value(text, format)?;
+            let mut lines = Vec::new();
+            if mode == StructuredMode::Flatten {
+                flatten(&value, "", &mut lines);
+            } else {
+                collect_strings(&value, &mut lines);
+            }
+            Ok(lines.join("\n"))
+        }
+    }
+}
+
+/// Parses a structured document into a JSON value, whatever its format.
+///
+/// NDJSON streams and YAML streams of several documents become arrays.
+fn to_json_value(text: &str, format: StructuredFormat) -> Result<Value, ParserError> {
+    match format {
+        StructuredFormat::Json => Ok(serde_json::from_str(text)?),
+        StructuredFormat::Ndjson => Ok(Value::Array(parse_ndjson(text)?)),
+        StructuredFormat::Yaml => {
+            let mut documents = parse_yaml(text)?
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<Result<Vec<Value>, serde_json::Error>>()?;
+            if documents.len() == 1 {
+                Ok(documents.remove(0))
+            } else {
+                Ok(Value::Array(documents))
+            }
+        }
+        StructuredFormat::Toml => Ok(toml_to_json(toml::Value::Table(toml::from_str(text)?))),
+    }
+}
+
+/// Parses the non-blank lines of NDJSON as JSON values.
+fn parse_ndjson(text: &str) -> Result<Vec<Value>, ParserError> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+/// Parses every document of a YAML stream.
+fn parse_yaml(text: &str) -> Result<Vec<serde_yaml_ng::Value>, ParserError> {
+    serde_yaml_ng::Deserializer::from_str(text)
+        .map(|document| Ok(serde_yaml_ng::Value::deserialize(document)?))
+        .collect()
+}
+
+/// Converts a TOML value into a JSON value, with datetimes as strings.
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::Number(i.into()),
+        toml::Value::Float(f) => Number::from_f64(f).map_or(Value::Null, Value::Number),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(array) => Value::Array(array.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect::<Map<String, Value>>(),
+        ),
+    }
+}
+
+/// Pretty-prints a JSON value with 4-space indentation.
+fn to_pretty_json(value: &Value) -> Result<String, ParserError> {
+    let mut buffer = Vec::new();
+    let mut serializer =
+        Serializer::with_formatter(&mut buffer, PrettyFormatter::with_indent(JSON_INDENT));
+    value.serialize(&mut serializer)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+/// Writes one `path: value` line per scalar of the value.
+fn flatten(value: &Value, path: &str, lines: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                flatten(value, &path, lines);
+            }
+        }
+        Value::Array(array) => {
+            for (i, value) in array.iter().enumerate() {
+                flatten(value, &format!("{path}[{i}]"), lines);
+            }
+        }
+        scalar => {
+            let scalar = match scalar {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            if path.is_empty() {
+                lines.push(scalar);
+            } else {
+                lines.push(format!("{path}: {scalar}"));
+            }
+        }
+    }
+}
+
+/// Collects the string values of the value, skipping keys, numbers and booleans.
+fn collect_strings(value: &Value, lines: &mut Vec<String>) {
+    match value {
+        Value::String(s) if !s.trim().is_empty() => lines.push(s.clone()),
+        Value::Array(array) => array.iter().for_each(|value| collect_strings(value, lines)),
+        Value::Object(map) => map.values().for_each(|value| collect_strings(value, lines)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> String {
+        std::fs::read_to_string(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    fn options(structured_mode: StructuredMode) -> ParseOptions {
+        ParseOptions {
+            structured_mode,
+            ..ParseOptions::default()
+        }
+    }
+
+    #[test]
+    fn parse_json_pretty_success() {
+        let result = parse_structured(
+            r#"{"name":"John Doe","tags":["a","b"],"age":30}"#,
+            StructuredFormat::Json,
+            &ParseOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            result.text,
+            r#"{
+    "name": "John Doe",
+    "tags": [
+        "a",
+        "b"
+    ],
+    "age": 30
+}"#
+        );
+        assert_eq!(result.metadata[METADATA_FORMAT], "json");
+    }
+
+    #[test]
+    fn parse_json_flatten_success() {
+        let text = read_test_file("test_json_1.json");
+        let result = parse_structured(
+            &text,
+            StructuredFormat::Json,
+            &options(StructuredMode::Flatten),
+        )
+        .unwrap();
+
+        assert_eq!(
+            result.text,
+            "name: John Doe\nage: 30\nemail: john@example.com"
+        );
+    }
+
+    #[test]
+    fn parse_ndjson_success() {
+        let text =
+            "{\"id\":1,\"user\":{\"name\":\"Ann\"}}\n\n{\"id\":2,\"user\":{\"name\":\"Bob\"}}\n";
+        let flat = parse_structured(
+            text,
+            StructuredFormat::Ndjson,
+            &options(StructuredMode::Flatten),
+        )
+        .unwrap();
+        let strings = parse_structured(
+            text,
+            StructuredFormat::Ndjson,
+            &options(StructuredMode::Strings),
+        )
+        .unwrap();
+
+        assert_eq!(
+            flat.text,
+            "[0].id: 1\n[0].user.name: Ann\n[1].id: 2\n[1].user.name: Bob"
+        );
+        assert_eq!(strings.text, "Ann\nBob");
+    }
+
+    #[test]
+    fn parse_yaml_success() {
+        let text = "title: Report\nauthors:\n  - name: Ann\n  - name: Bob\ndraft: false\n";
+        let pretty =
+            parse_structured(text, StructuredFormat::Yaml, &ParseOptions::default()).unwrap();
+        let flat = parse_structured(
+            text,
+            StructuredFormat::Yaml,
+            &options(StructuredMode::Flatten),
+        )
+        .unwrap();
+
+        assert_eq!(
+            pretty.text,
+            "title: Report\nauthors:\n- name: Ann\n- name: Bob\ndraft: false"
+        );
+        assert_eq!(
+            flat.text,
+            "title: Report\nauthors[0].name: Ann\nauthors[1].name: Bob\ndraft: false"
+        );
+        assert_eq!(pretty.metadata[METADATA_FORMAT], "yaml");
+    }
+
+    #[test]
+    fn parse_toml_success() {
+        let text = "title = \"Config\"\n\n[server]\nhost = \"localhost\"\nport = 8080\nstarted = 2024-05-01T10:00:00Z\n";
+        let flat = parse_structured(
+            text,
+            StructuredFormat::Toml,
+            &options(StructuredMode::Flatten),
+        )
+        .unwrap();
+        let strings = parse_structured(
+            text,
+            StructuredFormat::Toml,
+            &options(StructuredMode::Strings),
+        )
+        .unwrap();
+
+        assert_eq!(
+            flat.text,
+            "title: Config\nserver.host: localhost\nserver.port: 8080\nserver.started: 2024-05-01T10:00:00Z"
+        );
+        assert_eq!(strings.text, "Config\nlocalhost\n2024-05-01T10:00:00Z");
+    }
+
+    #[test]
+    fn parse_invalid_lenient() {
+        let result = parse_structured(
+            "{\"name\": ",
+            StructuredFormat::Json,
+            &ParseOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.text, "{\"name\": ");
+        assert!(!result.metadata.contains_key(METADATA_FORMAT));
+    }
+
+    #[test]
+    fn parse_invalid_strict() {
+        let options = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+
+        for format in [
+            StructuredFormat::Json,
+            StructuredFormat::Ndjson,
+            StructuredFormat::Yaml,
+            StructuredFormat::Toml,
+        ] {
+            let result = parse_structured("key: [unclosed\n= {", format, &options);
+            assert!(matches!(result, Err(ParserError::ParseError(_))));
+        }
+    }
+
+    #[test]
+    fn sniff_success() {
+        assert_eq!(
+            StructuredFormat::sniff(&read_test_file("test_json_1.json")),
+            Some(StructuredFormat::Json)
+        );
+        assert_eq!(
+            StructuredFormat::sniff("{\"a\":1}\n{\"a\":2}\n"),
+            Some(StructuredFormat::Ndjson)
+        );
+        assert_eq!(StructuredFormat::sniff("[not json"), None);
+        assert_eq!(StructuredFormat::sniff("Hello, world!"), None);
+    }
+}
@@ -0,0 +1,423 @@
+//! Subtitle parser module.
+//!
+//! This module provides functionality for extracting the text of subtitle and
+//! transcript files (`SubRip`, `WebVTT` and `YouTube` SBV). Cue numbers, timings, settings
+//! and styling tags are stripped, and the text of consecutive cues is merged into
+//! paragraphs, each optionally starting with the time it is spoken at.
+
+use super::super::{
+    document::{METADATA_FORMAT, ParsedDocument},
+    encoding::decode_text,
+    errors::ParserError,
+    options::ParseOptions,
+};
+use serde::Serialize;
+
+/// Silence between two cues, in milliseconds, from which they belong to different
+/// paragraphs
+const PARAGRAPH_GAP: u64 = 2000;
+
+/// Subtitle formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SubtitleFormat {
+    /// `SubRip` (`.srt`): numbered cues with `00:00:01,000 --> 00:00:02,000` timings
+    Srt,
+    /// `WebVTT` (`.vtt`): a `WEBVTT` header, then cues with optional identifiers
+    Vtt,
+    /// `YouTube` SBV (`.sbv`): cues with `0:00:01.000,0:00:02.000` timings
+    Sbv,
+}
+
+impl SubtitleFormat {
+    /// Finds the subtitle format matching a file extension.
+    pub(crate) fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "srt" => Some(Self::Srt),
+            "vtt" | "webvtt" => Some(Self::Vtt),
+            "sbv" => Some(Self::Sbv),
+            _ => None,
+        }
+    }
+
+    /// Recognises a subtitle format from its first cue, or the `WEBVTT` header.
+    pub(crate) fn sniff(text: &str) -> Option<Self> {
+        let mut lines = text
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .map(str::trim)
+            .skip_while(|line| line.is_empty());
+        let first = lines.next()?;
+
+        if first == "WEBVTT" || first.starts_with("WEBVTT ") || first.starts_with("WEBVTT\t") {
+            Some(Self::Vtt)
+        } else if first.bytes().all(|byte| byte.is_ascii_digit())
+            && lines.next().and_then(parse_timing).is_some()
+        {
+            Some(Self::Srt)
+        } else if parse_sbv_timing(first).is_some() {
+            Some(Self::Sbv)
+        } else {
+            None
+        }
+    }
+
+    /// Name of the format, as reported in the metadata.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+            Self::Sbv => "sbv",
+        }
+    }
+}
+
+/// A cue of a subtitle file: text displayed between two times.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Cue {
+    /// Time the text appears at, in milliseconds from the start of the video
+    pub start: u64,
+    /// Time the text disappears at, in milliseconds from the start of the video
+    pub end: u64,
+    /// Text of the cue without styling tags, its lines joined with spaces
+    pub text: String,
+}
+
+/// Reads the cues of a subtitle file.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing a `SubRip` (SRT), `WebVTT` (VTT) or `YouTube` SBV file
+///
+/// # Returns
+///
+/// * `Ok(Vec<Cue>)` - The cues of the file with their timings, in order
+/// * `Err(ParserError)` - If the data isn't text or not a subtitle file
+///
+/// # Examples
+///
+/// ```
+/// use parser::parse_subtitles;
+///
+/// let subtitles = b"1
+/// 00:00:01,000 --> 00:00:03,500
+/// <i>Hello,</i> world!
+///
+/// 2
+/// 00:00:04,000 --> 00:00:05,000
+/// How are you?
+/// ";
+///
+/// let cues = parse_subtitles(subtitles).expect("Failed to parse subtitles");
+///
+/// assert_eq!(cues[0].start, 1000);
+/// assert_eq!(cues[0].end, 3500);
+/// assert_eq!(cues[0].text, "Hello, world!");
+/// assert_eq!(cues[1].text, "How are you?");
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ParserError::InvalidFormat`] if the data can't be decoded as text or
+/// isn't in a known subtitle format.
+pub fn parse_subtitles(data: &[u8]) -> Result<Vec<Cue>, ParserError> {
+    let (text, _) = decode_text(data, None)?;
+    let format = SubtitleFormat::sniff(&text)
+        .ok_or_else(|| ParserError::InvalidFormat("Not a subtitle file".to_string()))?;
+    Ok(read_cues(&text, format))
+}
+
+/// Extracts the text of a subtitle file as paragraphs.
+///
+/// # Arguments
+///
+/// * `text` - The decoded content of the file
+/// * `format` - The subtitle format of the content
+/// * `options` - The parsing options, of which `timestamps` is used
+///
+/// # Returns
+///
+/// The text of the cues, with the format in the metadata.
+///
+/// # Implementation Notes
+///
+/// * Cues separated by less than two seconds are joined into a paragraph
+/// * Lines repeated from the previous cue, as in rolling captions, are dropped
+/// * With the `timestamps` option, each paragraph starts with the time of its first
+///   cue, as `[HH:MM:SS.mmm]`
+pub(crate) fn parse_subtitle(
+    text: &str,
+    format: SubtitleFormat,
+    options: &ParseOptions,
+) -> ParsedDocument {
+    let cues = read_cues(text, format);
+
+    let mut paragraphs: Vec<(u64, String)> = Vec::new();
+    let mut previous_end = None;
+    for cue in cues {
+        let starts_paragraph =
+            previous_end.is_none_or(|end: u64| cue.start.saturating_sub(end) >= PARAGRAPH_GAP);
+        previous_end = Some(cue.end);
+
+        match paragraphs.last_mut() {
+            Some((_, paragraph)) if !starts_paragraph => {
+                paragraph.push(' ');
+                paragraph.push_str(&cue.text);
+            }
+            _ => paragraphs.push((cue.start, cue.text)),
+        }
+    }
+
+    let paragraphs: Vec<String> = paragraphs
+        .into_iter()
+        .map(|(start, paragraph)| {
+            if options.timestamps {
+                format!("[{}] {paragraph}", format_time(start))
+            } else {
+                paragraph
+            }
+        })
+        .collect();
+
+    ParsedDocument::from(paragraphs.join("\n\n")).with_metadata(METADATA_FORMAT, format.name())
+}
+
+/// Reads the cues of a subtitle file, skipping cues without text.
+fn read_cues(text: &str, format: SubtitleFormat) -> Vec<Cue> {
+    let mut cues: Vec<Cue> = Vec::new();
+    let mut previous_lines: Vec<String> = Vec::new();
+
+    for block in blocks(text) {
+        let Some((timing, lines)) = find_timing(&block, format) else {
+            continue;
+        };
+        let (start, end) = timing;
+
+        let lines: Vec<String> = lines
+            .iter()
+            .map(|line| clean_line(line))
+            .filter(|line| !line.is_empty())
+            .collect();
+        // Rolling captions repeat the last line of the previous cue
+        let text = lines
+            .iter()
+            .filter(|line| !previous_lines.contains(line))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ");
+        previous_lines = lines;
+
+        if !text.is_empty() {
+            cues.push(Cue { start, end, text });
+        }
+    }
+
+    cues
+}
+
+/// Splits a file into blocks of non-empty lines.
+fn blocks(text: &str) -> Vec<Vec<&str>> {
+    let mut blocks = vec![Vec::new()];
+    for line in text.trim_start_matches('\u{feff}').lines() {
+        if line.trim().is_empty() {
+            blocks.push(Vec::new());
+        } else if let Some(block) = blocks.last_mut() {
+            block.push(line);
+        }
+    }
+    blocks.retain(|block| !block.is_empty());
+    blocks
+}
+
+/// Finds the timing of a cue block, returning it with the text lines that follow.
+///
+/// Blocks without timing, such as the `WebVTT` header, `NOTE`, `STYLE` and `REGION`
+/// blocks, are not cues.
+fn find_timing<'a>(
+    block: &'a [&'a str],
+    format: SubtitleFormat,
+) -> Option<((u64, u64), &'a [&'a str])> {
+    // The timing follows the cue number (SRT) or identifier (VTT), if any
+    let candidates = if format == SubtitleFormat::Sbv { 1 } else { 2 };
+    block
+        .iter()
+        .take(candidates)
+        .enumerate()
+        .find_map(|(index, line)| {
+            let timing = match format {
+                SubtitleFormat::Srt | SubtitleFormat::Vtt => parse_timing(line),
+                SubtitleFormat::Sbv => parse_sbv_timing(line),
+            }?;
+            Some((timing, &block[index + 1..]))
+        })
+}
+
+/// Parses a `start --> end` timing line, ignoring the cue settings that follow.
+fn parse_timing(line: &str) -> Option<(u64, u64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    Some((parse_time(start.trim())?, parse_time(end)?))
+}
+
+/// Parses a `start,end` SBV timing line.
+fn parse_sbv_timing(line: &str) -> Option<(u64, u64)> {
+    let (start, end) = line.trim().split_once(',')?;
+    Some((parse_time(start)?, parse_time(end)?))
+}
+
+/// Parses a time as `HH:MM:SS.mmm` or `MM:SS.mmm`, the milliseconds being separated
+/// by a dot or a comma, into milliseconds. Times overflowing the milliseconds are
+/// rejected.
+fn parse_time(time: &str) -> Option<u64> {
+    let (clock, fraction) = time.split_once(['.', ',']).unwrap_or((time, "0"));
+    if fraction.is_empty() || fraction.len() > 3 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // Fractions are decimal: `.5` is 500 milliseconds
+    let milliseconds = format!("{fraction:0<3}").parse::<u64>().ok()?;
+
+    let parts = clock
+        .split(':')
+        .map(|part| {
+            (!part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+                .then(|| part.parse::<u64>().ok())
+                .flatten()
+        })
+        .collect::<Option<Vec<u64>>>()?;
+    let (hours, minutes, seconds) = match parts[..] {
+        [minutes, seconds] => (0, minutes, seconds),
+        [hours, minutes, seconds] => (hours, minutes, seconds),
+        _ => return None,
+    };
+
+    hours
+        .checked_mul(60)?
+        .checked_add(minutes)?
+        .checked_mul(60)?
+        .checked_add(seconds)?
+        .checked_mul(1000)?
+        .checked_add(milliseconds)
+}
+
+/// Formats milliseconds as `HH:MM:SS.mmm`.
+fn format_time(milliseconds: u64) -> String {
+    let seconds = milliseconds / 1000;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+        milliseconds % 1000
+    )
+}
+
+/// Strips the styling of a cue line: HTML-like tags (`<i>`, `<v Speaker>`,
+/// `<00:00:01.000>`), `SubStation` overrides (`{\an8}`) and character references.
+fn clean_line(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut characters = line.chars();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '<' => {
+                characters.by_ref().find(|&character| character == '>');
+            }
+            '{' if characters.clone().next() == Some('\\') => {
+                characters.by_ref().find(|&character| character == '}');
+            }
+            _ => text.push(character),
+        }
+    }
+
+    let text = text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&lrm;", "")
+        .replace("&rlm;", "")
+        .replace("&amp;", "&");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> String {
+        std::fs::read_to_string(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_srt_success() {
+        let text = read_test_file("test_srt_1.srt");
+        let result = parse_subtitle(&text, SubtitleFormat::Srt, &ParseOptions::default());
+
+        assert_eq!(
+            result.text,
+            "Hello, this is a test srt for the parsing API. Cues are merged into paragraphs.
+
+After a pause, a new paragraph starts."
+        );
+        assert_eq!(result.metadata[METADATA_FORMAT], "srt");
+        assert_eq!(SubtitleFormat::sniff(&text), Some(SubtitleFormat::Srt));
+    }
+
+    #[test]
+    fn parse_vtt_timestamps() {
+        let text = read_test_file("test_vtt_1.vtt");
+        let options = ParseOptions {
+            timestamps: true,
+            ..ParseOptions::default()
+        };
+        let result = parse_subtitle(&text, SubtitleFormat::Vtt, &options);
+
+        assert_eq!(
+            result.text,
+            "[00:00:01.000] Hello, this is a test vtt for the parsing API. Speakers & tags are removed.
+
+[00:01:05.500] Rolling captions are not repeated."
+        );
+        assert_eq!(SubtitleFormat::sniff(&text), Some(SubtitleFormat::Vtt));
+    }
+
+    #[test]
+    fn parse_subtitles_sbv() {
+        let data = b"0:00:01.000,0:00:02.500\nFirst line\n\n0:00:03.000,0:00:04.000\nSecond line\n";
+        let cues = parse_subtitles(data).unwrap();
+
+        assert_eq!(
+            cues,
+            [
+                Cue {
+                    start: 1000,
+                    end: 2500,
+                    text: "First line".to_string()
+                },
+                Cue {
+                    start: 3000,
+                    end: 4000,
+                    text: "Second line".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_time_success() {
+        assert_eq!(parse_time("01:02:03,456"), Some(3_723_456));
+        assert_eq!(parse_time("02:03.5"), Some(123_500));
+        assert_eq!(parse_time("1:02:03"), Some(3_723_000));
+        assert_eq!(parse_time("1-02"), None);
+    }
+
+    #[test]
+    fn parse_time_overflow() {
+        assert_eq!(parse_time("18446744073709551615:00:00.000"), None);
+        assert_eq!(parse_time("00:00:18446744073709551.616"), None);
+        assert_eq!(parse_time("999999999999999:00.000"), None);
+    }
+}
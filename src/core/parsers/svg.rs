@@ -0,0 +1,286 @@
+//! SVG parser module.
+//!
+//! This module provides functionality for extracting text from SVG images. The
+//! `<title>`, `<desc>` and `<text>` elements are read in document order with
+//! roxmltree, while text converted to outlines can only be recognised by rendering
+//! the image with resvg and passing it to the OCR engine.
+
+use super::super::{
+    document::{METADATA_FORMAT, METADATA_TITLE, ParsedDocument},
+    errors::ParserError,
+    options::ParseOptions,
+};
+use super::image::parse_image;
+use super::xml::check_nesting;
+use encoding_rs::UTF_8;
+use resvg::tiny_skia::{Color, Pixmap, Transform};
+use resvg::usvg::{Options, Tree};
+use roxmltree::{Document, Node, ParsingOptions};
+
+/// Namespace of the SVG elements
+const SVG_NS: &str = "http://www.w3.org/2000/svg";
+
+/// Number of bytes read to recognise an SVG image
+const SNIFF_LENGTH: usize = 4096;
+
+/// Size in pixels of the longest side of images rendered for OCR
+const RASTER_SIZE: f32 = 2000.0;
+
+/// Checks whether data is an SVG image: an XML document whose root element is
+/// `svg`, after its declaration, comments and document type.
+pub(crate) fn is_svg(data: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&data[..data.len().min(SNIFF_LENGTH)]);
+    let mut rest = head.trim_start_matches('\u{feff}').trim_start();
+
+    loop {
+        let skipped = if rest.starts_with("<?") {
+            rest.split_once("?>")
+        } else if rest.starts_with("<!--") {
+            rest.split_once("-->")
+        } else if rest.starts_with("<!") {
+            // The document type may have an internal subset between brackets
+            match (rest.find('['), rest.find('>')) {
+                (Some(bracket), Some(end)) if bracket < end => rest.split_once("]>"),
+                _ => rest.split_once('>'),
+            }
+        } else {
+            break;
+        };
+        let Some((_, after)) = skipped else {
+            return false;
+        };
+        rest = after.trim_start();
+    }
+
+    let Some(after) = rest
+        .strip_prefix("<svg:svg")
+        .or_else(|| rest.strip_prefix("<svg"))
+    else {
+        return false;
+    };
+    after.starts_with(|character: char| {
+        character.is_ascii_whitespace() || character == '>' || character == '/'
+    })
+}
+
+/// Parses an SVG image and extracts its text.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the SVG data
+/// * `options` - The parsing options, of which `svg_ocr` is used
+///
+/// # Returns
+///
+/// * `Ok(ParsedDocument)` - The text of the image, one element per line, with its
+///   title in the metadata
+/// * `Err(ParserError)` - If the image is not well-formed XML, or can't be rendered
+///   for OCR
+///
+/// # Implementation Notes
+///
+/// * `<title>`, `<desc>` and `<text>` elements are read in document order, styles,
+///   scripts and metadata being skipped
+/// * `<tspan>` elements positioned with `x`, `y` or `dy` attributes start new lines
+/// * With the `svg_ocr` option, the image is rendered without its text elements and
+///   the text recognised in it, such as text converted to outlines, is appended
+/// * Images nested deeper than 256 levels are rejected
+pub(crate) fn parse_svg(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParserError> {
+    let (svg, _, _) = UTF_8.decode(data);
+    // Never load external entities, but keep images referencing them readable
+    let resolver = |_: Option<&str>, _: &str| -> Result<Option<&str>, String> { Ok(Some("")) };
+    let parsing_options = ParsingOptions {
+        allow_dtd: true,
+        entity_resolver: Some(&resolver),
+        ..ParsingOptions::default()
+    };
+    check_nesting(&svg)?;
+    let document = Document::parse_with_options(&svg, parsing_options)?;
+    let root = document.root_element();
+
+    let mut lines = Vec::new();
+    read_elements(root, &mut lines);
+    if options.svg_ocr {
+        lines.push(parse_image(&rasterize(data)?)?);
+    }
+
+    let text = lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let title = root
+        .children()
+        .find(|node| is_svg_element(*node, "title"))
+        .map(collapsed_text)
+        .filter(|title| !title.is_empty());
+
+    let document = ParsedDocument::from(text).with_metadata(METADATA_FORMAT, "svg");
+    Ok(match title {
+        Some(title) => document.with_metadata(METADATA_TITLE, title),
+        None => document,
+    })
+}
+
+/// Reads the text of the `<title>`, `<desc>` and `<text>` elements of an element.
+fn read_elements(node: Node, lines: &mut Vec<String>) {
+    for child in node.children().filter(Node::is_element) {
+        if is_svg_element(child, "title") || is_svg_element(child, "desc") {
+            lines.push(collapsed_text(child));
+        } else if is_svg_element(child, "text") {
+            let start = lines.len();
+            lines.push(String::new());
+            read_text(child, lines);
+            for line in &mut lines[start..] {
+                *line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+            }
+        } else if !["style", "script", "metadata"]
+            .iter()
+            .any(|name| is_svg_element(child, name))
+        {
+            read_elements(child, lines);
+        }
+    }
+}
+
+/// Reads the content of a `<text>` element into the last line, positioned
+/// `<tspan>` elements starting new lines.
+fn read_text(node: Node, lines: &mut Vec<String>) {
+    for child in node.children() {
+        if let Some(text) = child.text().filter(|_| child.is_text()) {
+            if let Some(line) = lines.last_mut() {
+                line.push_str(text);
+            }
+        } else if is_svg_element(child, "tspan")
+            || is_svg_element(child, "textPath")
+            || is_svg_element(child, "a")
+        {
+            let positioned = ["x", "y", "dy"]
+                .iter()
+                .any(|name| child.has_attribute(*name));
+            if is_svg_element(child, "tspan")
+                && positioned
+                && lines.last().is_some_and(|line| !line.trim().is_empty())
+            {
+                lines.push(String::new());
+            }
+            read_text(child, lines);
+        }
+    }
+}
+
+/// Renders an SVG image on a white background, as a PNG image for the OCR engine.
+///
+/// Text elements are not rendered, their text being read from the document.
+fn rasterize(data: &[u8]) -> Result<Vec<u8>, ParserError> {
+    let invalid = || ParserError::ParseError("Could not render SVG image".to_string());
+    let tree = Tree::from_data(data, &Options::default())?;
+    let size = tree.size();
+    let scale = RASTER_SIZE / size.width().max(size.height());
+    let pixels = size.scale_by(scale).ok_or_else(invalid)?.to_int_size();
+
+    let mut pixmap = Pixmap::new(pixels.width(), pixels.height()).ok_or_else(invalid)?;
+    pixmap.fill(Color::WHITE);
+    resvg::render(
+        &tree,
+        Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    pixmap
+        .encode_png()
+        .map_err(|error| ParserError::ParseError(error.to_string()))
+}
+
+/// Checks whether a node is an SVG element with the given local name, elements
+/// without a namespace being accepted.
+fn is_svg_element(node: Node, name: &str) -> bool {
+    node.is_element()
+        && node.tag_name().name() == name
+        && node
+            .tag_name()
+            .namespace()
+            .is_none_or(|namespace| namespace == SVG_NS)
+}
+
+/// Collects the text of an element, whitespace collapsed.
+fn collapsed_text(node: Node) -> String {
+    node.descendants()
+        .filter(Node::is_text)
+        .filter_map(|node| node.text())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_svg_success() {
+        let document =
+            parse_svg(&read_test_file("test_svg_1.svg"), &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            document.text,
+            "Test SVG
+A test svg for the parsing API.
+Hello, this is a test svg for the parsing API.
+First line
+Second line
+Link & text"
+        );
+        assert_eq!(document.metadata[METADATA_FORMAT], "svg");
+        assert_eq!(document.metadata[METADATA_TITLE], "Test SVG");
+    }
+
+    #[test]
+    fn parse_svg_deeply_nested() {
+        let depth = 100_000;
+        let svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\"><text>Top</text>{}<text>deep</text>{}</svg>",
+            "<g>".repeat(depth),
+            "</g>".repeat(depth)
+        );
+
+        assert!(matches!(
+            parse_svg(svg.as_bytes(), &ParseOptions::default()),
+            Err(ParserError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn is_svg_success() {
+        assert!(is_svg(&read_test_file("test_svg_1.svg")));
+        assert!(is_svg(
+            b"\xEF\xBB\xBF<!-- logo -->\n<svg:svg xmlns:svg=\"x\"/>"
+        ));
+        assert!(is_svg(b"<!DOCTYPE svg [<!ENTITY a \"b\">]><svg>&a;</svg>"));
+        assert!(!is_svg(&read_test_file("test_xml_1.xml")));
+        assert!(!is_svg(b"<svgs/>"));
+    }
+
+    #[test]
+    fn rasterize_success() {
+        let png = rasterize(&read_test_file("test_svg_1.svg")).unwrap();
+        let decoder = png::Decoder::new(std::io::Cursor::new(png));
+        let info = decoder.read_info().unwrap();
+
+        assert_eq!((info.info().width, info.info().height), (2000, 1000));
+    }
+}
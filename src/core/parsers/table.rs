@@ -0,0 +1,245 @@
+//! Table rendering module.
+//!
+//! This module provides the rendering shared by the parsers producing tabular
+//! content, such as CSV files and spreadsheets. A table can be rendered as
+//! normalized CSV, as a Markdown table or as JSON records.
+
+use super::super::{errors::ParserError, options::TableFormat};
+use csv::WriterBuilder;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// Rows of cells, with the first row possibly holding the column headers.
+pub(crate) struct Table {
+    /// Cells of the table, row by row
+    pub(crate) rows: Vec<Vec<String>>,
+    /// Whether the first row holds the column headers
+    pub(crate) has_header: bool,
+}
+
+impl Table {
+    /// Creates a table, detecting whether its first row is a header.
+    pub(crate) fn new(rows: Vec<Vec<String>>) -> Self {
+        let has_header = detect_header(&rows);
+        Self { rows, has_header }
+    }
+
+    /// Renders the table in the given format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParserError`] if the CSV or JSON serialization fails.
+    pub(crate) fn render(&self, format: TableFormat) -> Result<String, ParserError> {
+        match format {
+            TableFormat::Csv => self.to_csv(),
+            TableFormat::Markdown => Ok(self.to_markdown()),
+            TableFormat::Json => Ok(serde_json::to_string_pretty(&self.to_records())?),
+        }
+    }
+
+    /// Renders the table as comma-separated values, quoting cells where needed.
+    fn to_csv(&self) -> Result<String, ParserError> {
+        let mut writer = WriterBuilder::new()
+            .flexible(true)
+            .terminator(csv::Terminator::Any(b'\n'))
+            .from_writer(Vec::new());
+
+        for row in &self.rows {
+            writer.write_record(row)?;
+        }
+
+        let data = writer
+            .into_inner()
+            .map_err(|err| ParserError::IoError(err.to_string()))?;
+
+        Ok(String::from_utf8(data)?.trim_end_matches('\n').to_string())
+    }
+
+    /// Renders the table as a Markdown table.
+    ///
+    /// Markdown tables require a header row, so generic column names are used
+    /// when the table has none.
+    fn to_markdown(&self) -> String {
+        let width = self.rows.iter().map(Vec::len).max().unwrap_or_default();
+        if width == 0 {
+            return String::new();
+        }
+
+        let (header, body) = self.split_header();
+        let header = (0..width)
+            .map(|i| {
+                header
+                    .and_then(|row| row.get(i))
+                    .map_or_else(|| format!("Column {}", i + 1), Clone::clone)
+            })
+            .collect::<Vec<String>>();
+
+        let format_row = |row: &[String]| {
+            let cells = (0..width)
+                .map(|i| escape_markdown_cell(row.get(i).map_or("", String::as_str)))
+                .collect::<Vec<String>>();
+            format!("| {} |", cells.join(" | "))
+        };
+
+        let mut lines = vec![format_row(&header), format!("|{}", " --- |".repeat(width))];
+        lines.extend(body.iter().map(|row| format_row(row)));
+        lines.join("\n")
+    }
+
+    /// Converts the data rows into records keyed by the column headers.
+    ///
+    /// Columns without a header are keyed `column_N`, counting from 1.
+    pub(crate) fn to_records(&self) -> Vec<Map<String, Value>> {
+        let (header, body) = self.split_header();
+
+        body.iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .map(|(i, cell)| {
+                        let key = header
+                            .and_then(|row| row.get(i))
+                            .map_or_else(|| format!("column_{}", i + 1), Clone::clone);
+                        (key, Value::String(cell.clone()))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Splits the rows into the header row, if any, and the data rows.
+    fn split_header(&self) -> (Option<&Vec<String>>, &[Vec<String>]) {
+        match self.rows.split_first() {
+            Some((header, body)) if self.has_header => (Some(header), body),
+            _ => (None, &self.rows),
+        }
+    }
+}
+
+/// Guesses whether the first row of a table holds column headers.
+///
+/// The first row qualifies when its cells are non-empty, distinct and not numbers,
+/// and either a column holds only numbers below it or none of its labels reappear
+/// in their column.
+pub(crate) fn detect_header(rows: &[Vec<String>]) -> bool {
+    let Some((first, body)) = rows.split_first() else {
+        return false;
+    };
+    if body.is_empty() {
+        return false;
+    }
+
+    let mut labels = HashSet::new();
+    let valid_labels = first
+        .iter()
+        .all(|cell| !cell.is_empty() && !is_number(cell) && labels.insert(cell.as_str()));
+    if !valid_labels {
+        return false;
+    }
+
+    // A column of numbers under a text label is the strongest hint
+    let numeric_column = (0..first.len()).any(|i| {
+        body.iter()
+            .all(|row| row.get(i).is_some_and(|cell| is_number(cell)))
+    });
+    let labels_unique = (0..first.len()).all(|i| body.iter().all(|row| row.get(i) != first.get(i)));
+
+    numeric_column || labels_unique
+}
+
+/// Checks whether a cell holds a number.
+fn is_number(cell: &str) -> bool {
+    cell.parse::<f64>().is_ok()
+}
+
+/// Escapes the characters that would break a Markdown table cell.
+fn escape_markdown_cell(cell: &str) -> String {
+    cell.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_table() -> Table {
+        Table::new(vec![
+            vec!["name".to_string(), "age".to_string()],
+            vec!["Doe, John".to_string(), "30".to_string()],
+            vec!["Jane | Doe".to_string(), "28".to_string()],
+        ])
+    }
+
+    #[test]
+    fn render_csv_success() {
+        let result = sample_table().render(TableFormat::Csv).unwrap();
+
+        assert_eq!(result, "name,age\n\"Doe, John\",30\nJane | Doe,28");
+    }
+
+    #[test]
+    fn render_markdown_success() {
+        let result = sample_table().render(TableFormat::Markdown).unwrap();
+
+        assert_eq!(
+            result,
+            "| name | age |
+| --- | --- |
+| Doe, John | 30 |
+| Jane \\| Doe | 28 |"
+        );
+    }
+
+    #[test]
+    fn render_json_success() {
+        let result = sample_table().render(TableFormat::Json).unwrap();
+
+        assert_eq!(
+            result,
+            r#"[
+  {
+    "name": "Doe, John",
+    "age": "30"
+  },
+  {
+    "name": "Jane | Doe",
+    "age": "28"
+  }
+]"#
+        );
+    }
+
+    #[test]
+    fn detect_header_success() {
+        assert!(sample_table().has_header);
+        assert!(
+            !Table::new(vec![
+                vec!["1".to_string(), "2".to_string()],
+                vec!["3".to_string(), "4".to_string()],
+            ])
+            .has_header
+        );
+        assert!(
+            !Table::new(vec![
+                vec!["a".to_string(), "b".to_string()],
+                vec!["a".to_string(), "c".to_string()],
+            ])
+            .has_header
+        );
+    }
+
+    #[test]
+    fn render_without_header() {
+        let table = Table::new(vec![vec!["1".to_string(), "2".to_string()]]);
+
+        assert_eq!(
+            table.render(TableFormat::Markdown).unwrap(),
+            "| Column 1 | Column 2 |\n| --- | --- |\n| 1 | 2 |"
+        );
+        assert_eq!(
+            table.to_records()[0]["column_2"],
+            Value::String("2".to_string())
+        );
+    }
+}
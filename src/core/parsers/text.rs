@@ -1,34 +1,159 @@
 //! Text parser module.
 //!
 //! This module provides functionality for parsing plain text files, including TXT,
-//! CSV, and JSON formats. It focuses on UTF-8 encoded text files.
+//! CSV, and JSON formats. Files in encodings other than UTF-8 (UTF-16, Windows-1252,
+//! Shift-JIS, ...) are transcoded to UTF-8, then text formats with a syntax of their
+//! own (delimited data, structured data, HTML, XML, markup languages) are handed to
+//! their parsers.
 
-use super::super::errors::ParserError;
-use std::str;
+use super::super::{
+    document::{METADATA_ENCODING, ParsedDocument},
+    encoding::decode_text,
+    errors::ParserError,
+    options::ParseOptions,
+};
+use super::{
+    code::{Language, parse_code},
+    csv::{parse_csv, sniff_delimiter},
+    html::{is_xhtml, render_html},
+    markup::{MarkupFormat, parse_markup},
+    notebook::{is_notebook, parse_notebook},
+    pim::{is_calendar, is_vcard, parse_calendar, parse_vcard},
+    structured::{StructuredFormat, parse_structured},
+    subtitle::{SubtitleFormat, parse_subtitle},
+    xml::{is_xml, parse_xml},
+};
+use std::path::Path;
 
-/// Parses UTF-8 encoded text files and returns their content.
+/// Formats of text files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextFormat {
+    /// Text returned as is
+    Plain,
+    /// Delimited data, with its delimiter
+    Delimited(u8),
+    /// JSON, NDJSON, YAML or TOML
+    Structured(StructuredFormat),
+    /// HTML or XHTML
+    Html,
+    /// Generic XML
+    Xml,
+    /// Markdown, reStructuredText, AsciiDoc, LaTeX or `BibTeX`
+    Markup(MarkupFormat),
+    /// iCalendar
+    Calendar,
+    /// vCard
+    Contacts,
+    /// Jupyter notebook
+    Notebook,
+    /// `SubRip`, `WebVTT` or SBV
+    Subtitle(SubtitleFormat),
+    /// Source code, with its programming language
+    Code(Language),
+}
+
+/// Parses text files and returns their content as UTF-8.
 ///
 /// This function handles various text-based formats such as plain text files,
-/// CSV files, and JSON files by converting their binary content to UTF-8 strings.
+/// CSV files, and JSON files by decoding their binary content to UTF-8 strings.
 ///
 /// # Arguments
 ///
 /// * `data` - A byte slice containing the text file data
+/// * `options` - The parsing options, of which the `encoding` override and the
+///   `file_name` hint are used, the others being passed to the specific parsers
 ///
 /// # Returns
 ///
-/// * `Ok(String)` - The text content from the file
-/// * `Err(ParserError)` - If the data isn't valid UTF-8 or another error occurs
+/// * `Ok(ParsedDocument)` - The text content from the file, with the encoding it was
+///   decoded from in the `encoding` metadata entry
+/// * `Err(ParserError)` - If the encoding is unknown, the data isn't text, or the
+///   specific parser fails
 ///
 /// # Implementation Notes
 ///
-/// * Uses the standard library's UTF-8 validation
-/// * Performs no additional formatting or processing beyond UTF-8 conversion
-/// * Works with plain text, CSV, JSON, and other UTF-8 encoded text formats
-pub(crate) fn parse_text(data: &[u8]) -> Result<String, ParserError> {
-    // Convert bytes to string, using UTF-8 encoding
-    let text = str::from_utf8(data)?;
-    Ok(text.to_string())
+/// * Detects the encoding from the byte order mark, or statistically without one
+/// * The `encoding` option takes precedence over detection
+/// * The format is recognised from the file extension, or sniffed from the content
+///   when the file has no name or an unknown extension
+pub(crate) fn parse_text(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParserError> {
+    let (text, encoding) = decode_text(data, options.encoding.as_deref())?;
+
+    let document = match detect_format(&text, options.file_name.as_deref()) {
+        TextFormat::Plain => ParsedDocument::from(text),
+        TextFormat::Delimited(delimiter) => parse_csv(&text, delimiter, options)?,
+        TextFormat::Structured(format) => parse_structured(&text, format, options)?,
+        TextFormat::Html => render_html(&text, options),
+        TextFormat::Xml => parse_xml(&text, options)?,
+        TextFormat::Markup(format) => parse_markup(&text, format, options)?,
+        TextFormat::Calendar => parse_calendar(&text),
+        TextFormat::Contacts => parse_vcard(&text),
+        TextFormat::Notebook => parse_notebook(&text, options)?,
+        TextFormat::Subtitle(format) => parse_subtitle(&text, format, options),
+        TextFormat::Code(language) => parse_code(&text, language, options),
+    };
+
+    Ok(document.with_metadata(METADATA_ENCODING, encoding.name()))
+}
+
+/// Recognises the format of a text file from its extension, or from its content.
+fn detect_format(text: &str, file_name: Option<&str>) -> TextFormat {
+    let extension = file_name
+        .and_then(|name| Path::new(name).extension())
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some("txt" | "text" | "log") => TextFormat::Plain,
+        Some("tsv" | "tab") => TextFormat::Delimited(b'\t'),
+        Some("csv") => TextFormat::Delimited(sniff_delimiter(text).unwrap_or(b',')),
+        Some("html" | "htm" | "xhtml" | "xht") => TextFormat::Html,
+        Some("xml" | "rss" | "atom" | "xsd" | "xsl" | "xslt" | "kml" | "gpx") => TextFormat::Xml,
+        Some("ics" | "ical" | "icalendar" | "ifb") => TextFormat::Calendar,
+        Some("vcf" | "vcard") => TextFormat::Contacts,
+        Some("ipynb") => TextFormat::Notebook,
+        Some(extension) => StructuredFormat::from_extension(extension)
+            .map(TextFormat::Structured)
+            .or_else(|| MarkupFormat::from_extension(extension).map(TextFormat::Markup))
+            .or_else(|| SubtitleFormat::from_extension(extension).map(TextFormat::Subtitle))
+            .or_else(|| Language::from_extension(extension).map(TextFormat::Code))
+            .unwrap_or_else(|| sniff_format(text)),
+        None => sniff_format(text),
+    }
+}
+
+/// Recognises the format of a text file from its content.
+///
+/// Only formats with unambiguous markers are recognised: the shebang line of a
+/// script, a leading iCalendar or vCard component, a `WEBVTT` header or subtitle cue,
+/// an XML declaration, a LaTeX `\documentclass` or `BibTeX` entry, a Jupyter notebook,
+/// valid JSON, or a delimiter used consistently across lines.
+fn sniff_format(text: &str) -> TextFormat {
+    if let Some(language) = Language::sniff(text) {
+        TextFormat::Code(language)
+    } else if is_calendar(text) {
+        TextFormat::Calendar
+    } else if is_vcard(text) {
+        TextFormat::Contacts
+    } else if let Some(format) = SubtitleFormat::sniff(text) {
+        TextFormat::Subtitle(format)
+    } else if is_xhtml(text) {
+        TextFormat::Html
+    } else if is_xml(text) {
+        TextFormat::Xml
+    } else if let Some(format) = MarkupFormat::sniff(text) {
+        TextFormat::Markup(format)
+    } else if is_notebook(text) {
+        TextFormat::Notebook
+    } else if let Some(format) = StructuredFormat::sniff(text) {
+        TextFormat::Structured(format)
+    } else if let Some(delimiter) = sniff_delimiter(text) {
+        TextFormat::Delimited(delimiter)
+    } else {
+        TextFormat::Plain
+    }
 }
 
 #[cfg(test)]
@@ -47,7 +172,7 @@ mod tests {
     #[test]
     fn parse_txt_success() {
         let data = read_test_file("test_txt_1.txt");
-        let result = parse_text(&data).unwrap();
+        let result = parse_text(&data, &ParseOptions::default()).unwrap().text;
 
         assert!(!result.is_empty());
         assert_eq!(
@@ -59,14 +184,14 @@ mod tests {
     #[test]
     fn parse_csv_success() {
         let data = read_test_file("test_csv_1.csv");
-        let result = parse_text(&data).unwrap();
+        let result = parse_text(&data, &ParseOptions::default()).unwrap().text;
 
         assert!(!result.is_empty());
         assert_eq!(
             result,
-            "Username; Identifier;First name;Last name
-booker12;9012;Rachel;Booker
-grey07;2070;Laura;Grey"
+            "Username,Identifier,First name,Last name
+booker12,9012,Rachel,Booker
+grey07,2070,Laura,Grey"
                 .to_string()
         );
     }
@@ -74,7 +199,7 @@ grey07;2070;Laura;Grey"
     #[test]
     fn parse_json_success() {
         let data = read_test_file("test_json_1.json");
-        let result = parse_text(&data).unwrap();
+        let result = parse_text(&data, &ParseOptions::default()).unwrap().text;
 
         assert!(!result.is_empty());
         assert_eq!(
@@ -87,4 +212,105 @@ grey07;2070;Laura;Grey"
             .to_string()
         );
     }
+
+    #[test]
+    fn parse_windows_1252_success() {
+        let data = read_test_file("test_txt_3.txt");
+        let result = parse_text(&data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            result.text,
+            "Bonjour, ceci est un fichier texte encodé en Windows-1252 pour l'API de parsing. \
+Ça fonctionne très bien, à bientôt !"
+        );
+        assert_eq!(result.metadata[METADATA_ENCODING], "windows-1252");
+    }
+
+    #[test]
+    fn parse_utf16_success() {
+        let data = read_test_file("test_txt_4.txt");
+        let result = parse_text(&data, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            result.text,
+            "Hello, this is a UTF-16 test txt for the parsing API."
+        );
+        assert_eq!(result.metadata[METADATA_ENCODING], "UTF-16LE");
+    }
+
+    #[test]
+    fn parse_encoding_override() {
+        let options = ParseOptions {
+            encoding: Some("shift_jis".to_string()),
+            ..ParseOptions::default()
+        };
+        let result = parse_text(b"\x93\xfa\x96\x7b\x8c\xea", &options).unwrap();
+
+        assert_eq!(result.text, "日本語");
+        assert_eq!(result.metadata[METADATA_ENCODING], "Shift_JIS");
+    }
+
+    #[test]
+    fn parse_tsv_from_file_name() {
+        let options = ParseOptions {
+            file_name: Some("export.TSV".to_string()),
+            ..ParseOptions::default()
+        };
+        let result = parse_text(b"name\tcity\nJohn\tParis, France", &options).unwrap();
+
+        assert_eq!(result.text, "name,city\nJohn,\"Paris, France\"");
+        assert_eq!(result.metadata["format"], "tsv");
+    }
+
+    #[test]
+    fn parse_txt_not_sniffed() {
+        let options = ParseOptions {
+            file_name: Some("notes.txt".to_string()),
+            ..ParseOptions::default()
+        };
+        let result = parse_text(b"a;b\nc;d", &options).unwrap();
+
+        assert_eq!(result.text, "a;b\nc;d");
+        assert!(!result.metadata.contains_key("format"));
+    }
+
+    #[test]
+    fn parse_yaml_from_file_name() {
+        let options = ParseOptions {
+            file_name: Some("config.yml".to_string()),
+            strict: true,
+            ..ParseOptions::default()
+        };
+        let result = parse_text(b"name: parser\nversion: 1", &options).unwrap();
+
+        assert_eq!(result.text, "name: parser\nversion: 1");
+        assert_eq!(result.metadata["format"], "yaml");
+        assert!(parse_text(b"name: [parser", &options).is_err());
+    }
+
+    #[test]
+    fn detect_format_success() {
+        assert_eq!(
+            detect_format("a;b\nc;d", Some("notes.txt")),
+            TextFormat::Plain
+        );
+        assert_eq!(
+            detect_format("a,b", Some("data.CSV")),
+            TextFormat::Delimited(b',')
+        );
+        assert_eq!(detect_format("<feed/>", Some("feed.rss")), TextFormat::Xml);
+        assert_eq!(
+            detect_format("# Title", Some("README.md")),
+            TextFormat::Markup(MarkupFormat::Markdown)
+        );
+        assert_eq!(
+            detect_format("{\"a\": 1}", Some("export.dat")),
+            TextFormat::Structured(StructuredFormat::Json)
+        );
+        assert_eq!(
+            detect_format("<?xml version=\"1.0\"?><root/>", None),
+            TextFormat::Xml
+        );
+        assert_eq!(detect_format("Hello, world!", None), TextFormat::Plain);
+    }
 }
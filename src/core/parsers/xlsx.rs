@@ -1,37 +1,48 @@
 //! XLSX parser module.
 //!
 //! This module provides functionality for extracting text from Microsoft Excel
-//! XLSX spreadsheet files using the calamine library. It converts spreadsheet
-//! content to a CSV-like text format.
+//! XLSX spreadsheet files using the calamine library. It renders spreadsheet
+//! content as CSV, Markdown tables or JSON records.
 
-use super::super::errors::ParserError;
+use super::super::{
+    document::{METADATA_FORMAT, ParsedDocument},
+    errors::ParserError,
+    options::{OutputFormat, ParseOptions, TableFormat},
+};
+use super::table::Table;
 use calamine::{Reader, Xlsx};
+use serde_json::{Map, Value};
 use std::io::Cursor;
 
-/// Parses an XLSX file and extracts text content as CSV.
+/// Parses an XLSX file and renders the content of its sheets.
 ///
 /// This function takes raw bytes of an XLSX spreadsheet and extracts all cell
-/// values as a comma-separated text representation, with support for multiple
-/// sheets.
+/// values, rendered in the table format requested in the options, with support
+/// for multiple sheets.
 ///
 /// # Arguments
 ///
 /// * `data` - A byte slice containing the XLSX data
+/// * `options` - The parsing options, of which the `table_format` and
+///   `output_format` are used
 ///
 /// # Returns
 ///
-/// * `Ok(String)` - The extracted text from the spreadsheet in CSV format
+/// * `Ok(ParsedDocument)` - The rendered content of the spreadsheet
 /// * `Err(ParserError)` - If an error occurs during XLSX parsing
 ///
 /// # Implementation Notes
 ///
 /// * Uses the calamine library for XLSX parsing
-/// * Converts each sheet to CSV format with comma-separated values
-/// * Adds sheet headers for multi-sheet workbooks
+/// * CSV and Markdown output add sheet headers for multi-sheet workbooks
+/// * JSON output is an object mapping each sheet name to its records
+/// * Markdown output renders each sheet as a Markdown table under a heading with
+///   its name, whatever the table format
 /// * Memory-efficient implementation using cursors instead of temporary files
-/// * TODO: Need proper logic to escape commas and quotes
-/// * TODO: Consider using the csv crate to convert each sheet and pass it through the `parse_text` function
-pub(crate) fn parse_xlsx(data: &[u8]) -> Result<String, ParserError> {
+pub(crate) fn parse_xlsx(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParserError> {
     // Create a cursor from the bytes for memory-based reading
     let cursor = Cursor::new(data);
 
@@ -39,33 +50,52 @@ pub(crate) fn parse_xlsx(data: &[u8]) -> Result<String, ParserError> {
     // This uses the standard Read trait and avoids temporary files
     let mut excel = Xlsx::new(cursor)?;
 
-    let mut csv_data = String::new();
-
     // Copy the sheet names to avoid borrowing issues
     let sheet_names = excel.sheet_names().clone();
 
+    let mut sheets = Vec::new();
     for name in sheet_names {
         if let Ok(range) = excel.worksheet_range(&name) {
-            if !csv_data.is_empty() {
-                csv_data.push_str("\n--- Sheet: ");
-                csv_data.push_str(&name);
-                csv_data.push_str(" ---\n");
-            }
-            let sheet_csv = range
+            let rows = range
                 .rows()
-                .map(|row| {
-                    row.iter()
-                        .map(std::string::ToString::to_string)
-                        .collect::<Vec<String>>()
-                        .join(",")
-                })
-                .collect::<Vec<String>>()
-                .join("\n");
-            csv_data.push_str(&sheet_csv);
+                .map(|row| row.iter().map(ToString::to_string).collect())
+                .collect();
+            sheets.push((name, Table::new(rows)));
         }
     }
 
-    Ok(csv_data)
+    let text = if options.output_format == OutputFormat::Markdown {
+        let mut sections = Vec::new();
+        for (name, table) in &sheets {
+            sections.push(format!(
+                "## {name}\n\n{}",
+                table.render(TableFormat::Markdown)?
+            ));
+        }
+        sections.join("\n\n")
+    } else if options.table_format == TableFormat::Json {
+        let workbook = sheets
+            .iter()
+            .map(|(name, table)| {
+                let records = table.to_records().into_iter().map(Value::Object).collect();
+                (name.clone(), Value::Array(records))
+            })
+            .collect::<Map<String, Value>>();
+        serde_json::to_string_pretty(&workbook)?
+    } else {
+        let mut text = String::new();
+        for (name, table) in &sheets {
+            if !text.is_empty() {
+                text.push_str("\n--- Sheet: ");
+                text.push_str(name);
+                text.push_str(" ---\n");
+            }
+            text.push_str(&table.render(options.table_format)?);
+        }
+        text
+    };
+
+    Ok(ParsedDocument::from(text).with_metadata(METADATA_FORMAT, "xlsx"))
 }
 
 #[cfg(test)]
@@ -84,7 +114,7 @@ mod tests {
     #[test]
     fn parse_xlsx_single_sheet_success() {
         let data = read_test_file("test_xlsx_1.xlsx");
-        let result = parse_xlsx(&data).unwrap();
+        let result = parse_xlsx(&data, &ParseOptions::default()).unwrap().text;
 
         assert!(!result.is_empty());
         assert_eq!(
@@ -99,7 +129,7 @@ alice23,8425,Alice"
     #[test]
     fn parse_xlsx_multiple_sheets_success() {
         let data = read_test_file("test_xlsx_2.xlsx");
-        let result = parse_xlsx(&data).unwrap();
+        let result = parse_xlsx(&data, &ParseOptions::default()).unwrap().text;
 
         assert!(!result.is_empty());
         assert_eq!(
@@ -112,4 +142,77 @@ johndoe123,4281,John"
                 .to_string()
         );
     }
+
+    #[test]
+    fn parse_xlsx_markdown_success() {
+        let data = read_test_file("test_xlsx_1.xlsx");
+        let options = ParseOptions {
+            table_format: TableFormat::Markdown,
+            ..ParseOptions::default()
+        };
+        let result = parse_xlsx(&data, &options).unwrap().text;
+
+        assert_eq!(
+            result,
+            "| username | identifier | first_name |
+| --- | --- | --- |
+| johndoe123 | 4281 | John |
+| alice23 | 8425 | Alice |"
+        );
+    }
+
+    #[test]
+    fn parse_xlsx_markdown_output_success() {
+        let data = read_test_file("test_xlsx_2.xlsx");
+        let options = ParseOptions {
+            output_format: OutputFormat::Markdown,
+            ..ParseOptions::default()
+        };
+        let result = parse_xlsx(&data, &options).unwrap().text;
+
+        assert_eq!(
+            result,
+            "## Sheet1
+
+| username | identifier | first_name |
+| --- | --- | --- |
+| alice23 | 8425 | Alice |
+
+## Sheet2
+
+| username | identifier | first_name |
+| --- | --- | --- |
+| johndoe123 | 4281 | John |"
+        );
+    }
+
+    #[test]
+    fn parse_xlsx_json_success() {
+        let data = read_test_file("test_xlsx_2.xlsx");
+        let options = ParseOptions {
+            table_format: TableFormat::Json,
+            ..ParseOptions::default()
+        };
+        let result = parse_xlsx(&data, &options).unwrap().text;
+
+        assert_eq!(
+            result,
+            r#"{
+  "Sheet1": [
+    {
+      "username": "alice23",
+      "identifier": "8425",
+      "first_name": "Alice"
+    }
+  ],
+  "Sheet2": [
+    {
+      "username": "johndoe123",
+      "identifier": "4281",
+      "first_name": "John"
+    }
+  ]
+}"#
+        );
+    }
 }
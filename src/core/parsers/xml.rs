@@ -0,0 +1,638 @@
+//! XML parser module.
+//!
+//! This module provides functionality for extracting text from generic XML
+//! documents using the `roxmltree` library. The extraction can be narrowed to
+//! elements selected by a simple XPath-like path, or by element names.
+
+use super::super::{
+    document::{METADATA_FORMAT, ParsedDocument},
+    errors::ParserError,
+    options::{ParseOptions, XmlOptions},
+};
+use super::html::collapse_whitespace;
+use roxmltree::{Document, Node, ParsingOptions};
+
+/// Deepest nesting of elements accepted, bounding the recursion of the XML parser
+/// and of the extraction
+pub(crate) const MAX_NESTING_DEPTH: usize = 256;
+
+/// Parses an XML document and extracts its text.
+///
+/// # Arguments
+///
+/// * `text` - The decoded content of the file
+/// * `options` - The parsing options, of which the `xml` options are used
+///
+/// # Returns
+///
+/// * `Ok(ParsedDocument)` - The text of the document, one line per element
+///   holding text
+/// * `Err(ParserError)` - If the XML is malformed or the selection path is invalid
+///
+/// # Implementation Notes
+///
+/// * Elements mixing text and child elements are extracted as a single line
+/// * Internal DTD entities are expanded, with protection against entity loops and
+///   exponential expansion ("billion laughs")
+/// * External entities are never resolved: they expand to nothing, so the document
+///   cannot reach the file system or the network
+/// * Comments and processing instructions are ignored
+/// * Documents nested deeper than 256 levels are rejected
+pub(crate) fn parse_xml(text: &str, options: &ParseOptions) -> Result<ParsedDocument, ParserError> {
+    // Never load external entities, but keep documents referencing them readable
+    let resolver = |_: Option<&str>, _: &str| -> Result<Option<&str>, String> { Ok(Some("")) };
+    let parsing_options = ParsingOptions {
+        allow_dtd: true,
+        entity_resolver: Some(&resolver),
+        ..ParsingOptions::default()
+    };
+    let text = text.trim_start_matches('\u{feff}');
+    check_nesting(text)?;
+    let document = Document::parse_with_options(text, parsing_options)?;
+
+    let extractor = Extractor {
+        options: &options.xml,
+    };
+    let lines = if let Some(path) = options.xml.select.as_deref() {
+        extractor.extract_selection(&document, &Selector::parse(path)?)
+    } else {
+        let mut lines = Vec::new();
+        extractor.collect_included(document.root_element(), &mut lines);
+        lines
+    };
+
+    Ok(ParsedDocument::from(lines.join("\n")).with_metadata(METADATA_FORMAT, "xml"))
+}
+
+/// Checks whether decoded text starts with an XML declaration.
+pub(crate) fn is_xml(text: &str) -> bool {
+    text.trim_start_matches('\u{feff}')
+        .trim_start()
+        .starts_with("<?xml")
+}
+
+/// Checks that the elements of an XML document are nested at most
+/// [`MAX_NESTING_DEPTH`] levels deep, before it is parsed.
+///
+/// The markup declared in entities counts as nested in every element, since it is
+/// parsed wherever the entities are referenced.
+///
+/// # Errors
+///
+/// Returns [`ParserError::ParseError`] if the document is nested too deeply.
+pub(crate) fn check_nesting(text: &str) -> Result<(), ParserError> {
+    let mut depth: usize = 0;
+    let mut entities = 0;
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        // Comments, CDATA sections and processing instructions hold no elements
+        let skipped = [("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>")]
+            .into_iter()
+            .find(|(open, _)| rest.starts_with(open))
+            .map(|(_, close)| rest.find(close).map_or(rest.len(), |end| end + close.len()));
+
+        let end = if let Some(end) = skipped {
+            end
+        } else if rest.starts_with("</") {
+            depth = depth.saturating_sub(1);
+            rest.find('>').map_or(rest.len(), |end| end + 1)
+        } else {
+            let declaration = rest.starts_with("<!");
+            let (end, markup) = scan_tag(rest, declaration);
+            entities += markup;
+            if !declaration && !rest[..end].ends_with("/>") {
+                depth += 1;
+            }
+            end
+        };
+
+        if depth + entities > MAX_NESTING_DEPTH {
+            return Err(ParserError::ParseError(format!(
+                "XML nested deeper than {MAX_NESTING_DEPTH} levels"
+            )));
+        }
+        rest = &rest[end.max(1)..];
+    }
+
+    Ok(())
+}
+
+/// Returns the length of a tag, up to its closing `>` (or the `[` opening the
+/// internal subset of a declaration), and for declarations the number of tags in
+/// their quoted values.
+fn scan_tag(tag: &str, declaration: bool) -> (usize, usize) {
+    let mut quote = None;
+    let mut markup = 0;
+
+    for (i, character) in tag.char_indices().skip(1) {
+        if let Some(open) = quote {
+            // Character references in entity values are markup once declared
+            if character == open {
+                quote = None;
+            } else if declaration && (character == '<' || is_less_than_reference(&tag[i..])) {
+                markup += 1;
+            }
+        } else if character == '"' || character == '\'' {
+            quote = Some(character);
+        } else if character == '>' || declaration && character == '[' {
+            return (i + 1, markup);
+        }
+    }
+
+    (tag.len(), markup)
+}
+
+/// Checks whether text starts with a character reference to `<`, e.g. `&#60;`.
+fn is_less_than_reference(text: &str) -> bool {
+    let Some(reference) = text.strip_prefix("&#") else {
+        return false;
+    };
+    let (digits, radix) = match reference.strip_prefix('x') {
+        Some(digits) => (digits, 16),
+        None => (reference, 10),
+    };
+    digits
+        .split_once(';')
+        .is_some_and(|(digits, _)| u32::from_str_radix(digits, radix) == Ok(u32::from('<')))
+}
+
+/// Extracts the text of XML elements according to the options.
+struct Extractor<'a> {
+    /// The XML options
+    options: &'a XmlOptions,
+}
+
+impl Extractor<'_> {
+    /// Extracts the text of the nodes selected by a path.
+    fn extract_selection(&self, document: &Document, selector: &Selector) -> Vec<String> {
+        let nodes = selector.select(document);
+        let mut lines = Vec::new();
+
+        for node in nodes {
+            match &selector.target {
+                Target::Elements => self.collect_included(node, &mut lines),
+                Target::Attribute(name) => lines.extend(
+                    node.attributes()
+                        .filter(|attribute| name == "*" || attribute.name() == name)
+                        .map(|attribute| collapse_whitespace(attribute.value()))
+                        .filter(|value| !value.is_empty()),
+                ),
+                Target::Text => {
+                    let text = node
+                        .children()
+                        .filter(Node::is_text)
+                        .filter_map(|child| child.text())
+                        .collect::<String>();
+                    let text = collapse_whitespace(&text);
+                    if !text.is_empty() {
+                        lines.push(text);
+                    }
+                }
+            }
+        }
+
+        lines
+    }
+
+    /// Collects the text of the included elements found in a subtree.
+    fn collect_included(&self, node: Node, lines: &mut Vec<String>) {
+        if self.is_excluded(node) {
+            return;
+        }
+        if self.options.include.is_empty() || has_name(&self.options.include, node) {
+            self.collect_text(node, lines);
+            return;
+        }
+        for child in node.children().filter(Node::is_element) {
+            self.collect_included(child, lines);
+        }
+    }
+
+    /// Collects the text of an element, one line per element holding text.
+    fn collect_text(&self, node: Node, lines: &mut Vec<String>) {
+        if self.is_excluded(node) {
+            return;
+        }
+
+        if self.options.attributes {
+            lines.extend(
+                node.attributes()
+                    .map(|attribute| collapse_whitespace(attribute.value()))
+                    .filter(|value| !value.is_empty()),
+            );
+        }
+
+        let has_text = node
+            .children()
+            .filter(Node::is_text)
+            .any(|child| child.text().is_some_and(|text| !text.trim().is_empty()));
+
+        if has_text {
+            let mut text = String::new();
+            self.inline_text(node, &mut text);
+            let text = collapse_whitespace(&text);
+            if !text.is_empty() {
+                lines.push(text);
+            }
+        } else {
+            for child in node.children().filter(Node::is_element) {
+                self.collect_text(child, lines);
+            }
+        }
+    }
+
+    /// Concatenates the text of a subtree, skipping the excluded elements.
+    fn inline_text(&self, node: Node, text: &mut String) {
+        for child in node.children() {
+            if child.is_text() {
+                text.push_str(child.text().unwrap_or_default());
+            } else if child.is_element() && !self.is_excluded(child) {
+                self.inline_text(child, text);
+            }
+        }
+    }
+
+    /// Checks whether an element is excluded along with its content.
+    fn is_excluded(&self, node: Node) -> bool {
+        has_name(&self.options.exclude, node)
+    }
+}
+
+/// Checks whether the local name of an element is in a list.
+fn has_name(names: &[String], node: Node) -> bool {
+    node.is_element() && names.iter().any(|name| name == node.tag_name().name())
+}
+
+/// A parsed XPath-like path.
+///
+/// The supported syntax is a subset of `XPath`: steps separated by `/` (child) or
+/// `//` (descendant), each step being an element name or `*`, optionally followed
+/// by a predicate (`[2]`, `[@id]` or `[@id='value']`). The last step may instead
+/// be `@name`, `@*` or `text()`. Relative paths match anywhere in the document.
+#[derive(Debug, PartialEq, Eq)]
+struct Selector {
+    /// Steps leading to the selected elements
+    steps: Vec<Step>,
+    /// What is extracted from the selected elements
+    target: Target,
+}
+
+/// A step of a path.
+#[derive(Debug, PartialEq, Eq)]
+struct Step {
+    /// Whether the step matches descendants rather than children only
+    descendant: bool,
+    /// Local name of the matched elements, or `*`
+    name: String,
+    /// Condition on the matched elements
+    predicate: Option<Predicate>,
+}
+
+/// A condition on the elements matched by a step.
+#[derive(Debug, PartialEq, Eq)]
+enum Predicate {
+    /// The element is the nth match among its siblings, counting from 1
+    Position(usize),
+    /// The element has the attribute, with the value if given
+    Attribute(String, Option<String>),
+}
+
+/// What is extracted from the selected elements.
+#[derive(Debug, PartialEq, Eq)]
+enum Target {
+    /// The text of the elements
+    Elements,
+    /// The value of an attribute, or of all attributes for `*`
+    Attribute(String),
+    /// The text directly inside the elements
+    Text,
+}
+
+impl Selector {
+    /// Parses a path.
+    fn parse(path: &str) -> Result<Self, ParserError> {
+        let invalid = || ParserError::InvalidFormat(format!("Invalid XML path: {path}"));
+
+        let path = path.trim();
+        let (mut descendant, rest) = if let Some(rest) = path.strip_prefix("//") {
+            (true, rest)
+        } else if let Some(rest) = path.strip_prefix('/') {
+            (false, rest)
+        } else {
+            // Relative paths match anywhere, as if they started with `//`
+            (true, path)
+        };
+
+        let mut steps = Vec::new();
+        let mut target = Target::Elements;
+        let segments = rest.split('/').collect::<Vec<&str>>();
+
+        for (i, segment) in segments.iter().enumerate() {
+            let last = i == segments.len() - 1;
+            let segment = segment.trim();
+
+            // An empty segment comes from `//`, making the next step a descendant one
+            if segment.is_empty() {
+                if descendant || last {
+                    return Err(invalid());
+                }
+                descendant = true;
+                continue;
+            }
+
+            if let Some(attribute) = segment.strip_prefix('@') {
+                if !last || !is_name(attribute) {
+                    return Err(invalid());
+                }
+                target = Target::Attribute(attribute.to_string());
+            } else if segment == "text()" {
+                if !last {
+                    return Err(invalid());
+                }
+                target = Target::Text;
+            } else {
+                steps.push(Step::parse(segment, descendant).ok_or_else(invalid)?);
+            }
+            descendant = false;
+        }
+
+        if steps.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self { steps, target })
+    }
+
+    /// Selects the elements matched by the path, in document order.
+    fn select<'a, 'input>(&self, document: &'a Document<'input>) -> Vec<Node<'a, 'input>> {
+        let mut nodes = vec![document.root()];
+
+        for step in &self.steps {
+            let mut matched = Vec::new();
+            for node in nodes {
+                if step.descendant {
+                    for descendant in node.descendants() {
+                        step.select_children(descendant, &mut matched);
+                    }
+                } else {
+                    step.select_children(node, &mut matched);
+                }
+            }
+
+            // Nested matches of descendant steps can produce duplicates
+            matched.sort_by_key(|node| node.id().get());
+            matched.dedup_by_key(|node| node.id());
+            nodes = matched;
+        }
+
+        nodes
+    }
+}
+
+impl Step {
+    /// Parses a step, such as `item`, `*` or `item[@id='1']`.
+    fn parse(segment: &str, descendant: bool) -> Option<Self> {
+        let (name, predicate) = match segment.split_once('[') {
+            Some((name, predicate)) => (name, Some(predicate.strip_suffix(']')?)),
+            None => (segment, None),
+        };
+        if !is_name(name) {
+            return None;
+        }
+
+        let predicate = match predicate {
+            None => None,
+            Some(predicate) => Some(Predicate::parse(predicate.trim())?),
+        };
+
+        Some(Self {
+            descendant,
+            name: name.to_string(),
+            predicate,
+        })
+    }
+
+    /// Adds the children of a node matched by the step.
+    fn select_children<'a, 'input>(
+        &self,
+        node: Node<'a, 'input>,
+        matched: &mut Vec<Node<'a, 'input>>,
+    ) {
+        let children = node
+            .children()
+            .filter(|child| {
+                child.is_element() && (self.name == "*" || child.tag_name().name() == self.name)
+            })
+            .enumerate()
+            .filter(|(i, child)| match &self.predicate {
+                None => true,
+                Some(Predicate::Position(position)) => i + 1 == *position,
+                Some(Predicate::Attribute(name, value)) => child
+                    .attributes()
+                    .find(|attribute| attribute.name() == name)
+                    .is_some_and(|attribute| {
+                        value
+                            .as_ref()
+                            .is_none_or(|value| attribute.value() == value)
+                    }),
+            })
+            .map(|(_, child)| child);
+        matched.extend(children);
+    }
+}
+
+impl Predicate {
+    /// Parses the content of a predicate, such as `2`, `@id` or `@id='1'`.
+    fn parse(predicate: &str) -> Option<Self> {
+        if let Ok(position) = predicate.parse::<usize>() {
+            return (position > 0).then_some(Self::Position(position));
+        }
+
+        let attribute = predicate.strip_prefix('@')?;
+        match attribute.split_once('=') {
+            Some((name, value)) => {
+                let value = value.trim();
+                let unquoted = value
+                    .strip_prefix('\'')
+                    .and_then(|value| value.strip_suffix('\''))
+                    .or_else(|| {
+                        value
+                            .strip_prefix('"')
+                            .and_then(|value| value.strip_suffix('"'))
+                    })?;
+                let name = name.trim();
+                is_name(name).then(|| Self::Attribute(name.to_string(), Some(unquoted.to_string())))
+            }
+            None => is_name(attribute).then(|| Self::Attribute(attribute.to_string(), None)),
+        }
+    }
+}
+
+/// Checks whether a path step is an element or attribute name, or `*`.
+fn is_name(name: &str) -> bool {
+    name == "*"
+        || (!name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> String {
+        std::fs::read_to_string(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    fn options(xml: XmlOptions) -> ParseOptions {
+        ParseOptions {
+            xml,
+            ..ParseOptions::default()
+        }
+    }
+
+    fn select(path: &str) -> String {
+        let xml = XmlOptions {
+            select: Some(path.to_string()),
+            ..XmlOptions::default()
+        };
+        parse_xml(&read_test_file("test_xml_1.xml"), &options(xml))
+            .unwrap()
+            .text
+    }
+
+    #[test]
+    fn parse_xml_success() {
+        let text = read_test_file("test_xml_1.xml");
+        let result = parse_xml(&text, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            result.text,
+            "Test XML Feed
+Hello, this is a test xml for the parsing API.
+First item
+The first description, with emphasis.
+Second item
+The second description."
+        );
+        assert_eq!(result.metadata[METADATA_FORMAT], "xml");
+    }
+
+    #[test]
+    fn parse_xml_with_selection() {
+        assert_eq!(
+            select("//item/description"),
+            "The first description, with emphasis.\nThe second description."
+        );
+        assert_eq!(select("/feed/item[2]/title"), "Second item");
+        assert_eq!(select("item[@id='1']/title"), "First item");
+        assert_eq!(select("//item/@id"), "1\n2");
+        assert_eq!(
+            select("//description/text()"),
+            "The first description, with .\nThe second description."
+        );
+    }
+
+    #[test]
+    fn parse_xml_include_exclude_attributes() {
+        let text = read_test_file("test_xml_1.xml");
+        let xml = XmlOptions {
+            include: vec!["item".to_string()],
+            exclude: vec!["description".to_string()],
+            attributes: true,
+            ..XmlOptions::default()
+        };
+        let result = parse_xml(&text, &options(xml)).unwrap();
+
+        assert_eq!(result.text, "1\nFirst item\n2\nSecond item");
+    }
+
+    #[test]
+    fn parse_xml_entities() {
+        let text = r#"<?xml version="1.0"?>
+<!DOCTYPE note [
+  <!ENTITY company "Example Corp">
+  <!ENTITY secret SYSTEM "file:///etc/passwd">
+]>
+<note>Sent by &company;.&secret;</note>"#;
+        let result = parse_xml(text, &ParseOptions::default()).unwrap();
+
+        assert_eq!(result.text, "Sent by Example Corp.");
+    }
+
+    #[test]
+    fn parse_xml_billion_laughs() {
+        let text = r#"<?xml version="1.0"?>
+<!DOCTYPE lolz [
+  <!ENTITY lol "lol">
+  <!ENTITY lol1 "&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;">
+  <!ENTITY lol2 "&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;">
+  <!ENTITY lol3 "&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;">
+]>
+<lolz>&lol3;</lolz>"#;
+
+        assert!(matches!(
+            parse_xml(text, &ParseOptions::default()),
+            Err(ParserError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn parse_xml_deeply_nested() {
+        let depth = 100_000;
+        let text = format!(
+            "<?xml version=\"1.0\"?><r><p>Top</p>{}deep{}</r>",
+            "<a>".repeat(depth),
+            "</a>".repeat(depth)
+        );
+
+        assert!(matches!(
+            parse_xml(&text, &ParseOptions::default()),
+            Err(ParserError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn check_nesting_success() {
+        let nested = |depth| format!("{}x{}", "<a>".repeat(depth), "</a>".repeat(depth));
+
+        assert!(check_nesting(&nested(MAX_NESTING_DEPTH)).is_ok());
+        assert!(check_nesting(&nested(MAX_NESTING_DEPTH + 1)).is_err());
+        // Siblings, empty elements and markup in comments, CDATA and attributes
+        let siblings = "<a/><a x='/>'></a><!-- <a> --><![CDATA[<a>]]>".repeat(1000);
+        assert!(check_nesting(&format!("<r>{siblings}</r>")).is_ok());
+        // Markup declared in entities, as text or character references
+        for value in ["<a>", "&#60;a>", "&#x3C;a>"] {
+            let entities = format!("<!ENTITY e '{value}'>").repeat(MAX_NESTING_DEPTH);
+            let text = format!("<!DOCTYPE r [{entities}]><r>&e;</r>");
+            assert!(check_nesting(&text).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn parse_xml_malformed() {
+        let result = parse_xml(
+            "<?xml version=\"1.0\"?><a><b></a>",
+            &ParseOptions::default(),
+        );
+
+        assert!(matches!(result, Err(ParserError::ParseError(_))));
+    }
+
+    #[test]
+    fn selector_parse_errors() {
+        for path in [
+            "", "/", "//", "a///b", "a/@id/b", "a[", "a[0]", "a b", "text()",
+        ] {
+            assert!(Selector::parse(path).is_err(), "{path}");
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! XPS parser module.
+//!
+//! This module provides functionality for extracting text from XPS and `OpenXPS`
+//! documents, as written by the Windows "Microsoft XPS Document Writer" printer. The
+//! fixed document sequence of the package gives its documents and their pages in
+//! reading order, whose `Glyphs` elements hold the text.
+
+use super::super::{
+    constants::{APPLICATION_OXPS, APPLICATION_XPS},
+    errors::ParserError,
+};
+use super::package::{Package, directory, has_name, open_package, read_part, resolve};
+use encoding_rs::UTF_8;
+use roxmltree::Document;
+
+/// Path of the relationships of the package
+const RELATIONSHIPS_PATH: &str = "_rels/.rels";
+
+/// Relationship type of the fixed document sequence of XPS documents
+const XPS_REPRESENTATION: &str = "http://schemas.microsoft.com/xps/2005/06/fixedrepresentation";
+
+/// Relationship type of the fixed document sequence of `OpenXPS` documents
+const OXPS_REPRESENTATION: &str = "http://schemas.openxps.org/oxps/v1.0/fixedrepresentation";
+
+/// Maximum distance between the baselines of glyph runs on the same line
+const LINE_TOLERANCE: f64 = 1.0;
+
+/// Returns the MIME type of an XPS or `OpenXPS` document, if a ZIP archive is one.
+pub(crate) fn xps_type(data: &[u8]) -> Option<&'static str> {
+    let mut package = open_package(data).ok()?;
+    let (kind, _) = fixed_representation(&mut package).ok()??;
+
+    Some(if kind == OXPS_REPRESENTATION {
+        APPLICATION_OXPS
+    } else {
+        APPLICATION_XPS
+    })
+}
+
+/// Parses an XPS or `OpenXPS` file and extracts its text.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the XPS data
+///
+/// # Returns
+///
+/// * `Ok(String)` - The text of the pages in order, with page separators
+/// * `Err(ParserError)` - If the package or one of its parts can't be read
+///
+/// # Implementation Notes
+///
+/// * Follows the relationships of the package to its `FixedDocumentSequence`, then
+///   to its `FixedDocument` parts and their `FixedPage` parts
+/// * Reads the `UnicodeString` of the `Glyphs` elements of each page, glyph runs
+///   sharing a baseline being joined on one line
+/// * Pages after the first start with a `--- Page <number> ---` separator
+pub(crate) fn parse_xps(data: &[u8]) -> Result<String, ParserError> {
+    let mut package = open_package(data)?;
+    let (_, sequence) = fixed_representation(&mut package)?.ok_or_else(|| {
+        ParserError::ParseError("Invalid XPS: missing fixed document sequence".to_string())
+    })?;
+
+    let mut pages = Vec::new();
+    for document in references(&mut package, &sequence, "DocumentReference")? {
+        pages.extend(references(&mut package, &document, "PageContent")?);
+    }
+
+    let mut lines = Vec::new();
+    for (index, page) in pages.iter().enumerate() {
+        if index > 0 {
+            lines.push(String::new());
+            lines.push(format!("--- Page {} ---", index + 1));
+        }
+        let content = read_xml_part(&mut package, page)?;
+        lines.extend(page_lines(&Document::parse(&content)?));
+    }
+
+    Ok(lines.join("\n").trim().to_string())
+}
+
+/// Finds the relationship of the package to its fixed document sequence, returning
+/// its type and the path of the sequence.
+fn fixed_representation(package: &mut Package) -> Result<Option<(String, String)>, ParserError> {
+    if package.index_for_name(RELATIONSHIPS_PATH).is_none() {
+        return Ok(None);
+    }
+    let relationships = read_xml_part(package, RELATIONSHIPS_PATH)?;
+
+    Ok(Document::parse(&relationships)?
+        .descendants()
+        .filter(|node| has_name(*node, "Relationship"))
+        .find_map(|node| {
+            let kind = node.attribute("Type")?;
+            let target = node.attribute("Target")?;
+            (kind == XPS_REPRESENTATION || kind == OXPS_REPRESENTATION)
+                .then(|| (kind.to_string(), resolve("", target)))
+        }))
+}
+
+/// Reads the paths of the parts referenced by the `Source` attribute of the elements
+/// of a part, relative to its directory.
+fn references(
+    package: &mut Package,
+    path: &str,
+    element: &str,
+) -> Result<Vec<String>, ParserError> {
+    let content = read_xml_part(package, path)?;
+
+    Ok(Document::parse(&content)?
+        .descendants()
+        .filter(|node| has_name(*node, element))
+        .filter_map(|node| node.attribute("Source"))
+        .map(|source| resolve(directory(path), source))
+        .collect())
+}
+
+/// Reads the lines of text of a fixed page, from its glyph runs in document order.
+fn page_lines(page: &Document) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut baseline: Option<f64> = None;
+
+    for glyphs in page.descendants().filter(|node| has_name(*node, "Glyphs")) {
+        // A leading `{}` escapes text starting with a brace
+        let text = glyphs.attribute("UnicodeString").unwrap_or_default();
+        let text = text.strip_prefix("{}").unwrap_or(text).trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let origin = glyphs
+            .attribute("OriginY")
+            .and_then(|origin| origin.trim().parse::<f64>().ok());
+        let same_line = matches!(
+            (baseline, origin),
+            (Some(baseline), Some(origin)) if (baseline - origin).abs() < LINE_TOLERANCE
+        );
+        match lines.last_mut() {
+            Some(line) if same_line => {
+                line.push(' ');
+                line.push_str(text);
+            }
+            _ => lines.push(text.to_string()),
+        }
+        baseline = origin;
+    }
+
+    lines
+}
+
+/// Reads an XML part of the package, in UTF-8 or UTF-16.
+fn read_xml_part(package: &mut Package, path: &str) -> Result<String, ParserError> {
+    let content = read_part(package, path)?;
+    let (text, _, _) = UTF_8.decode(&content);
+    Ok(text.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_xps_success() {
+        let text = parse_xps(&read_test_file("test_xps_1.xps")).unwrap();
+
+        assert_eq!(
+            text,
+            "Hello, this is a test xps for the parsing API.
+Second line & more
+
+--- Page 2 ---
+{Braced} second page"
+        );
+    }
+
+    #[test]
+    fn parse_oxps_success() {
+        let text = parse_xps(&read_test_file("test_oxps_1.oxps")).unwrap();
+
+        assert_eq!(text, "Hello, this is a test oxps for the parsing API.");
+    }
+
+    #[test]
+    fn xps_type_success() {
+        assert_eq!(
+            xps_type(&read_test_file("test_xps_1.xps")),
+            Some(APPLICATION_XPS)
+        );
+        assert_eq!(
+            xps_type(&read_test_file("test_oxps_1.oxps")),
+            Some(APPLICATION_OXPS)
+        );
+        assert_eq!(xps_type(&read_test_file("test_zip_1.zip")), None);
+    }
+}
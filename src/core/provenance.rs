@@ -0,0 +1,246 @@
+//! Provenance extraction module.
+//!
+//! This module provides functionality for extracting the text of a document as spans
+//! linked to where they come from: a page region, a slide shape, a spreadsheet cell
+//! or a paragraph. The result serializes to a versioned JSON document, meant to be
+//! stored and audited. Each located format is handled in its own submodule, other
+//! formats being returned as a single span locating the whole document.
+//!
+//! # Format
+//!
+//! Version 1 of the format is an object with the `version`, the `mime_type` of the
+//! document and its `spans`, each with its `text` and `location`. The `type` of a
+//! location tells its other fields:
+//!
+//! * `page`: the `page` number and the `bbox` of the line (PDF and OCR)
+//! * `slide`: the `slide` number, and the `shape_id` and `shape_name` of the shape
+//!   holding the paragraph (PPTX)
+//! * `cell`: the `sheet` name and `cell` reference, e.g. `B3` (XLSX)
+//! * `paragraph`: the `index` of the paragraph in the body and its `style` (DOCX)
+//! * `document`: the whole document, for formats without positions
+//!
+//! Fields are only ever added to a version, a changed or removed field bumping it.
+
+mod docx;
+mod image;
+mod pdf;
+mod pptx;
+mod xlsx;
+
+use self::{
+    docx::docx_spans, image::image_spans, pdf::pdf_spans, pptx::pptx_spans, xlsx::xlsx_spans,
+};
+
+use super::{
+    constants::{APPLICATION_DOCX, APPLICATION_PDF, APPLICATION_PPTX, APPLICATION_XLSX},
+    errors::ParserError,
+    options::ParseOptions,
+    parsers::{determine_mime_type_with_options, parse_with_options},
+};
+use mime::{IMAGE, IMAGE_SVG};
+use serde::{Deserialize, Serialize};
+
+/// Version of the provenance format produced by [`extract_provenance`]
+pub const PROVENANCE_VERSION: u32 = 1;
+
+/// The text of a document, as spans located in the document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Version of the format, [`PROVENANCE_VERSION`] when extracted
+    pub version: u32,
+    /// MIME type detected for the document
+    pub mime_type: String,
+    /// Spans of text in reading order
+    pub spans: Vec<Span>,
+}
+
+/// A span of text and where it was found in its document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Span {
+    /// Text of the span
+    pub text: String,
+    /// Where the text was found
+    pub location: Location,
+}
+
+/// Where a span of text was found in its document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Location {
+    /// A line of a page, of a PDF document or of an image recognised with OCR
+    Page {
+        /// Number of the page, from 1
+        page: usize,
+        /// Box enclosing the line, in points for PDF and pixels for images
+        bbox: BoundingBox,
+    },
+    /// A paragraph of a shape of a presentation slide
+    Slide {
+        /// Number of the slide in the presentation, from 1
+        slide: usize,
+        /// Identifier of the shape in the slide
+        shape_id: Option<u32>,
+        /// Name of the shape in the slide
+        shape_name: Option<String>,
+    },
+    /// A cell of a spreadsheet
+    Cell {
+        /// Name of the sheet
+        sheet: String,
+        /// Reference of the cell, e.g. `B3`
+        cell: String,
+    },
+    /// A paragraph of a text document
+    Paragraph {
+        /// Index of the paragraph among those of the body, from 0, table cell
+        /// paragraphs included
+        index: usize,
+        /// Identifier of the paragraph style
+        style: Option<String>,
+    },
+    /// The whole document
+    Document,
+}
+
+/// A rectangle from the top left corner of a page, rounded to hundredths.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    /// Distance from the left edge of the page
+    pub x: f64,
+    /// Distance from the top edge of the page
+    pub y: f64,
+    /// Width of the rectangle
+    pub width: f64,
+    /// Height of the rectangle
+    pub height: f64,
+}
+
+impl BoundingBox {
+    /// Creates a bounding box, its coordinates rounded to hundredths.
+    pub(crate) fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        let round = |value: f64| (value * 100.0).round() / 100.0;
+        Self {
+            x: round(x),
+            y: round(y),
+            width: round(width),
+            height: round(height),
+        }
+    }
+
+    /// Returns the smallest bounding box enclosing both boxes.
+    pub(crate) fn union(&self, other: &Self) -> Self {
+        let left = self.x.min(other.x);
+        let top = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+        Self::new(left, top, right - left, bottom - top)
+    }
+}
+
+/// Extracts the text of a document as spans located in the document.
+///
+/// This function detects the document format from the provided byte data and
+/// delegates the extraction to the appropriate specialized extractor.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the file data to be processed
+/// * `options` - The options used to parse formats without positions
+///
+/// # Returns
+///
+/// * `Ok(Provenance)` - The spans of text of the document
+/// * `Err(ParserError)` - If the file type is unsupported, unrecognized, or an error occurs during extraction
+///
+/// # Supported Formats
+///
+/// * PDF: lines with their page and bounding box
+/// * Images: lines recognised with OCR, with their bounding box on page 1
+/// * PPTX: paragraphs with their slide and shape
+/// * XLSX: cells with their sheet and reference
+/// * DOCX: paragraphs with their index and style
+/// * Other formats: their parsed text as a single span
+///
+/// # Examples
+///
+/// ```no_run
+/// use parser::{Location, ParseOptions, extract_provenance};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("report.pdf")?;
+/// let provenance = extract_provenance(&data, &ParseOptions::default())?;
+/// for span in &provenance.spans {
+///     if let Location::Page { page, bbox } = &span.location {
+///         println!("page {page} at ({}, {}): {}", bbox.x, bbox.y, span.text);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ParserError::InvalidFormat`] if the file type is unsupported or unrecognized.
+/// May return other [`ParserError`] variants if an error occurs during extraction.
+pub fn extract_provenance(data: &[u8], options: &ParseOptions) -> Result<Provenance, ParserError> {
+    let mime = determine_mime_type_with_options(data, options);
+
+    let spans = match &mime {
+        Some(mime) if *mime == APPLICATION_PDF => pdf_spans(data)?,
+        Some(mime) if *mime == APPLICATION_DOCX => docx_spans(data)?,
+        Some(mime) if *mime == APPLICATION_XLSX => xlsx_spans(data)?,
+        Some(mime) if *mime == APPLICATION_PPTX => pptx_spans(data)?,
+        Some(mime) if mime.type_() == IMAGE && *mime != IMAGE_SVG => image_spans(data)?,
+        _ => {
+            let text = parse_with_options(data, options)?.text;
+            if text.is_empty() {
+                Vec::new()
+            } else {
+                vec![Span {
+                    text,
+                    location: Location::Document,
+                }]
+            }
+        }
+    };
+
+    Ok(Provenance {
+        version: PROVENANCE_VERSION,
+        mime_type: mime.map(|mime| mime.to_string()).unwrap_or_default(),
+        spans,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_provenance_document() {
+        let provenance = extract_provenance(b"Hello, world!", &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&provenance).unwrap(),
+            r#"{"version":1,"mime_type":"text/plain","spans":[{"text":"Hello, world!","location":{"type":"document"}}]}"#
+        );
+    }
+
+    #[test]
+    fn extract_provenance_unsupported() {
+        let result =
+            extract_provenance(b"ID3\x04\x00\x00\x00\x00\x00\x00", &ParseOptions::default());
+
+        assert!(matches!(result, Err(ParserError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn bounding_box_union() {
+        let first = BoundingBox::new(10.0, 20.0, 30.0, 10.0);
+        let second = BoundingBox::new(35.0, 18.004, 15.0, 10.0);
+
+        assert_eq!(
+            first.union(&second),
+            BoundingBox::new(10.0, 18.0, 40.0, 12.0)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! DOCX provenance module.
+//!
+//! This module provides functionality for locating the paragraphs of Microsoft Word
+//! DOCX documents, read with the `docx_rs` library, by their index and style.
+
+use super::super::errors::ParserError;
+use super::{Location, Span};
+use docx_rs::{
+    DocumentChild, Paragraph, Table, TableCellContent, TableChild, TableRowChild, read_docx,
+};
+
+/// Extracts the paragraphs of a DOCX document with their index and style.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the DOCX data
+///
+/// # Returns
+///
+/// * `Ok(Vec<Span>)` - The non-empty paragraphs of the document in order
+/// * `Err(ParserError)` - If an error occurs during DOCX parsing
+///
+/// # Implementation Notes
+///
+/// * Paragraphs are indexed in document order, empty paragraphs and those of table
+///   cells included, so that indexes match the body of the document
+/// * The style is the identifier of the paragraph style, e.g. `Heading1`
+pub(super) fn docx_spans(data: &[u8]) -> Result<Vec<Span>, ParserError> {
+    let docx = read_docx(data)?;
+
+    let mut paragraphs = Vec::new();
+    for child in &docx.document.children {
+        match child {
+            DocumentChild::Paragraph(paragraph) => paragraphs.push(paragraph.as_ref()),
+            DocumentChild::Table(table) => collect_table(table, &mut paragraphs),
+            _ => {}
+        }
+    }
+
+    Ok(paragraphs
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, paragraph)| {
+            let text = paragraph.raw_text().trim().to_string();
+            (!text.is_empty()).then(|| Span {
+                text,
+                location: Location::Paragraph {
+                    index,
+                    style: paragraph
+                        .property
+                        .style
+                        .as_ref()
+                        .map(|style| style.val.clone()),
+                },
+            })
+        })
+        .collect())
+}
+
+/// Collects the paragraphs of the cells of a table, row by row, nested tables
+/// included.
+fn collect_table<'a>(table: &'a Table, paragraphs: &mut Vec<&'a Paragraph>) {
+    for TableChild::TableRow(row) in &table.rows {
+        for TableRowChild::TableCell(cell) in &row.cells {
+            for content in &cell.children {
+                match content {
+                    TableCellContent::Paragraph(paragraph) => paragraphs.push(paragraph),
+                    TableCellContent::Table(table) => collect_table(table, paragraphs),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn docx_spans_success() {
+        let spans = docx_spans(&read_test_file("test_docx_4.docx")).unwrap();
+
+        let located = spans
+            .iter()
+            .map(|span| match &span.location {
+                Location::Paragraph { index, style } => {
+                    (span.text.as_str(), *index, style.as_deref())
+                }
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(located[0], ("Test Document", 0, Some("Title")));
+        assert_eq!(located[1], ("Introduction", 1, Some("Heading1")));
+        assert_eq!(
+            located[2],
+            ("Hello, this is a test docx with structure.", 2, None)
+        );
+        assert_eq!(located[9], ("name", 9, None));
+        assert_eq!(located[15], ("The end.", 15, None));
+    }
+}
@@ -0,0 +1,31 @@
+//! Image provenance module.
+//!
+//! This module provides functionality for locating the lines of text recognised in
+//! images by the OCR engine.
+
+use super::super::{errors::ParserError, parsers::image::recognize_lines};
+use super::{Location, Span};
+
+/// Extracts the lines of text recognised in an image with their bounding box.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the image data (PNG, JPEG, WebP, etc.)
+///
+/// # Returns
+///
+/// * `Ok(Vec<Span>)` - The lines recognised in the image, on page 1
+/// * `Err(ParserError)` - If an error occurs during image processing or OCR
+///
+/// # Implementation Notes
+///
+/// * Bounding boxes are in pixels from the top left corner of the image
+pub(super) fn image_spans(data: &[u8]) -> Result<Vec<Span>, ParserError> {
+    Ok(recognize_lines(data)?
+        .into_iter()
+        .map(|(text, bbox)| Span {
+            text,
+            location: Location::Page { page: 1, bbox },
+        })
+        .collect())
+}
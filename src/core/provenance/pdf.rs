@@ -0,0 +1,72 @@
+//! PDF provenance module.
+//!
+//! This module provides functionality for locating the lines of text of PDF
+//! documents, laid out by the PDF parser, on their pages.
+
+use super::super::{errors::ParserError, parsers::pdf::read_lines};
+use super::{Location, Span};
+
+/// Extracts the lines of text of a PDF document with their page and bounding box.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the PDF data
+///
+/// # Returns
+///
+/// * `Ok(Vec<Span>)` - The lines of the document, page by page
+/// * `Err(ParserError)` - If an error occurs during PDF parsing
+///
+/// # Implementation Notes
+///
+/// * Bounding boxes are in points from the top left corner of the page, their
+///   height being the font size of the line
+pub(super) fn pdf_spans(data: &[u8]) -> Result<Vec<Span>, ParserError> {
+    Ok(read_lines(data)?
+        .into_iter()
+        .zip(1..)
+        .flat_map(|(lines, page)| {
+            lines.into_iter().map(move |(text, bbox)| Span {
+                text,
+                location: Location::Page { page, bbox },
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::BoundingBox;
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn pdf_spans_success() {
+        let spans = pdf_spans(&read_test_file("test_pdf_4.pdf")).unwrap();
+
+        assert_eq!(spans.len(), 6);
+        assert_eq!(spans[0].text, "Test PDF Document");
+        assert!(matches!(
+            spans[0].location,
+            Location::Page {
+                page: 1,
+                bbox: BoundingBox {
+                    x: 72.0,
+                    y: 48.0,
+                    height: 24.0,
+                    ..
+                }
+            }
+        ));
+        assert_eq!(spans[4].text, "Conclusion");
+        assert!(matches!(spans[4].location, Location::Page { page: 2, .. }));
+    }
+}
@@ -0,0 +1,107 @@
+//! PPTX provenance module.
+//!
+//! This module provides functionality for locating the paragraphs of Microsoft
+//! `PowerPoint` PPTX presentations by their slide and shape. Slides are ordered as in
+//! the presentation, and read with roxmltree.
+
+use super::super::{
+    errors::ParserError,
+    parsers::{
+        package::{has_name, open_package, read_text_part},
+        pptx::slide_paths,
+    },
+};
+use super::{Location, Span};
+use roxmltree::Document;
+
+/// Extracts the paragraphs of a PPTX presentation with their slide and shape.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the PPTX data
+///
+/// # Returns
+///
+/// * `Ok(Vec<Span>)` - The non-empty paragraphs of each slide, in document order
+/// * `Err(ParserError)` - If an error occurs while reading the package
+///
+/// # Implementation Notes
+///
+/// * Slides are numbered as in the text of the presentation, in the order of its
+///   slide list, or of the numbers of their parts when it is missing
+/// * The shape of a paragraph is its closest shape or graphic frame (e.g. a table),
+///   identified by the `id` and `name` of its non-visual properties
+pub(super) fn pptx_spans(data: &[u8]) -> Result<Vec<Span>, ParserError> {
+    let mut package = open_package(data)?;
+
+    let mut spans = Vec::new();
+    for (path, slide) in slide_paths(&mut package)?.iter().zip(1..) {
+        let content = read_text_part(&mut package, path)?;
+        let document = Document::parse(&content)?;
+
+        for paragraph in document.descendants().filter(|node| has_name(*node, "p")) {
+            let text = paragraph
+                .descendants()
+                .filter(|node| has_name(*node, "t"))
+                .filter_map(|node| node.text())
+                .collect::<String>();
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            let properties = paragraph
+                .ancestors()
+                .find(|node| has_name(*node, "sp") || has_name(*node, "graphicFrame"))
+                .and_then(|shape| shape.descendants().find(|node| has_name(*node, "cNvPr")));
+            spans.push(Span {
+                text: text.trim().to_string(),
+                location: Location::Slide {
+                    slide,
+                    shape_id: properties
+                        .and_then(|node| node.attribute("id"))
+                        .and_then(|id| id.parse().ok()),
+                    shape_name: properties
+                        .and_then(|node| node.attribute("name"))
+                        .map(str::to_string),
+                },
+            });
+        }
+    }
+
+    Ok(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn pptx_spans_success() {
+        let spans = pptx_spans(&read_test_file("test_pptx_1.pptx")).unwrap();
+
+        assert_eq!(spans.len(), 4);
+        assert_eq!(spans[0].text, "This is the title");
+        assert_eq!(
+            spans[0].location,
+            Location::Slide {
+                slide: 1,
+                shape_id: Some(2),
+                shape_name: Some("Title 1".to_string())
+            }
+        );
+        assert_eq!(spans[3].text, "This is the text of the second slide");
+        assert!(matches!(
+            spans[3].location,
+            Location::Slide { slide: 2, .. }
+        ));
+    }
+}
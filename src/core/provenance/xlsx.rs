@@ -0,0 +1,116 @@
+//! XLSX provenance module.
+//!
+//! This module provides functionality for locating the cells of Microsoft Excel
+//! XLSX spreadsheets, read with the calamine library, by their sheet and reference.
+
+use super::super::errors::ParserError;
+use super::{Location, Span};
+use calamine::{Reader, Xlsx};
+use std::io::Cursor;
+
+/// Extracts the non-empty cells of an XLSX spreadsheet with their sheet and
+/// reference.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the XLSX data
+///
+/// # Returns
+///
+/// * `Ok(Vec<Span>)` - The cells of each sheet, row by row
+/// * `Err(ParserError)` - If an error occurs during XLSX parsing
+///
+/// # Implementation Notes
+///
+/// * Cells are rendered as by the XLSX parser, and referenced in the A1 style
+/// * Sheets that can't be read, such as chart sheets, are skipped
+pub(super) fn xlsx_spans(data: &[u8]) -> Result<Vec<Span>, ParserError> {
+    let mut excel = Xlsx::new(Cursor::new(data))?;
+    let sheet_names = excel.sheet_names().clone();
+
+    let mut spans = Vec::new();
+    for name in sheet_names {
+        let Ok(range) = excel.worksheet_range(&name) else {
+            continue;
+        };
+        let (first_row, first_column) = range.start().unwrap_or_default();
+        for (row, column, cell) in range.used_cells() {
+            let text = cell.to_string();
+            if text.trim().is_empty() {
+                continue;
+            }
+            // Offsets in a range always fit in the u32 positions of the sheet
+            let row = first_row + u32::try_from(row).unwrap_or_default();
+            let column = first_column + u32::try_from(column).unwrap_or_default();
+            spans.push(Span {
+                text,
+                location: Location::Cell {
+                    sheet: name.clone(),
+                    cell: cell_reference(row, column),
+                },
+            });
+        }
+    }
+
+    Ok(spans)
+}
+
+/// Formats the A1 style reference of a cell from its zero-based position.
+fn cell_reference(row: u32, column: u32) -> String {
+    let mut letters = Vec::new();
+    let mut column = column + 1;
+    while column > 0 {
+        let digit = u8::try_from((column - 1) % 26).unwrap_or_default();
+        letters.push(char::from(b'A' + digit));
+        column = (column - 1) / 26;
+    }
+
+    letters.iter().rev().collect::<String>() + &(row + 1).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn xlsx_spans_success() {
+        let spans = xlsx_spans(&read_test_file("test_xlsx_2.xlsx")).unwrap();
+
+        assert_eq!(spans.len(), 12);
+        assert_eq!(spans[0].text, "username");
+        assert_eq!(
+            spans[0].location,
+            Location::Cell {
+                sheet: "Sheet1".to_string(),
+                cell: "A1".to_string()
+            }
+        );
+        assert_eq!(spans[5].text, "Alice");
+        assert_eq!(
+            spans[11].location,
+            Location::Cell {
+                sheet: "Sheet2".to_string(),
+                cell: "C2".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn cell_reference_success() {
+        assert_eq!(cell_reference(0, 0), "A1");
+        assert_eq!(cell_reference(2, 1), "B3");
+        assert_eq!(cell_reference(9, 25), "Z10");
+        assert_eq!(cell_reference(0, 26), "AA1");
+        assert_eq!(cell_reference(99, 701), "ZZ100");
+        assert_eq!(cell_reference(0, 702), "AAA1");
+    }
+}
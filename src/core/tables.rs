@@ -0,0 +1,378 @@
+//! Table extraction module.
+//!
+//! This module provides functionality for extracting the tables of documents as
+//! rows of cells, keeping the row and column spans of merged cells and whether the
+//! first row is a header. Each format is handled in its own submodule, PDF tables
+//! being inferred from the layout of the text.
+
+mod csv;
+mod docx;
+mod html;
+mod pdf;
+mod pptx;
+mod xlsx;
+
+use self::{
+    csv::csv_tables, docx::docx_tables, html::html_tables, pdf::pdf_tables, pptx::pptx_tables,
+    xlsx::xlsx_tables,
+};
+
+use super::{
+    constants::{APPLICATION_DOCX, APPLICATION_PDF, APPLICATION_PPTX, APPLICATION_XLSX},
+    encoding::decode_text,
+    errors::ParserError,
+    options::{ParseOptions, TableFormat},
+    parsers::{
+        csv::sniff_delimiter,
+        determine_mime_type_with_options,
+        html::{decode_html, is_xhtml},
+        table::{Table as TextTable, detect_header},
+    },
+};
+use mime::{TEXT, TEXT_HTML};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Largest number of columns a cell spans, as in browsers
+const MAX_COL_SPAN: usize = 1000;
+
+/// Largest number of rows a cell spans, as in browsers
+const MAX_ROW_SPAN: usize = 65534;
+
+/// Largest number of grid positions covered by the merged cells of a table
+const MAX_MERGED_AREA: usize = 1_000_000;
+
+/// A table of a document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Table {
+    /// Where the table was found in the document (a page, a slide, a sheet or the
+    /// number of the table)
+    pub position: String,
+    /// Whether the first row holds the column headers
+    pub has_header: bool,
+    /// Cells of the table, row by row, merged cells appearing once at their top left
+    /// position
+    pub rows: Vec<Vec<TableCell>>,
+}
+
+/// A cell of a table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableCell {
+    /// Text of the cell
+    pub text: String,
+    /// Number of rows the cell spans, 1 unless merged
+    pub row_span: usize,
+    /// Number of columns the cell spans, 1 unless merged
+    pub col_span: usize,
+}
+
+impl TableCell {
+    /// Creates a cell spanning a single row and column.
+    fn new(text: String) -> Self {
+        Self::with_spans(text, 1, 1)
+    }
+
+    /// Creates a cell spanning rows and columns, its spans being clamped between 1
+    /// and the spans browsers allow.
+    fn with_spans(text: String, row_span: usize, col_span: usize) -> Self {
+        Self {
+            text,
+            row_span: row_span.clamp(1, MAX_ROW_SPAN),
+            col_span: col_span.clamp(1, MAX_COL_SPAN),
+        }
+    }
+}
+
+impl Table {
+    /// Creates a table, detecting whether its first row is a header unless told.
+    fn new(position: String, rows: Vec<Vec<TableCell>>, has_header: Option<bool>) -> Self {
+        let mut table = Self {
+            position,
+            has_header: false,
+            rows,
+        };
+        table.has_header = has_header.unwrap_or_else(|| detect_header(&table.to_grid()));
+        table
+    }
+
+    /// Creates a table of cells without spans, detecting whether its first row is a
+    /// header.
+    fn from_texts(position: String, rows: Vec<Vec<String>>) -> Self {
+        let rows = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(TableCell::new).collect())
+            .collect();
+        Self::new(position, rows, None)
+    }
+
+    /// Lays the cells out on a rectangular grid.
+    ///
+    /// The text of a merged cell is placed at its top left position, the other
+    /// positions it covers being left empty, as are the positions of missing cells.
+    ///
+    /// Spans are clamped as by browsers, rows spans ending at the last row, and
+    /// cells are laid out unmerged once merged cells cover a million positions,
+    /// counting the positions padding the rows when they widen the grid. The grid is
+    /// as wide as its longest row.
+    #[must_use]
+    pub fn to_grid(&self) -> Vec<Vec<String>> {
+        let mut grid: Vec<Vec<Option<String>>> = vec![Vec::new(); self.rows.len()];
+        let mut merged_area = 0;
+        let mut width = 0;
+
+        for (index, row) in self.rows.iter().enumerate() {
+            let mut column = 0;
+            for cell in row {
+                // Skip the positions covered by cells spanning from previous rows
+                while grid[index].get(column).is_some_and(Option::is_some) {
+                    column += 1;
+                }
+                let mut row_span = cell.row_span.clamp(1, MAX_ROW_SPAN).min(grid.len() - index);
+                let mut col_span = cell.col_span.clamp(1, MAX_COL_SPAN);
+                if row_span * col_span > 1 {
+                    // Widening the grid pads every row
+                    let widening = (column + col_span).saturating_sub(width.max(column + 1));
+                    merged_area += row_span * col_span + widening * grid.len();
+                    if merged_area > MAX_MERGED_AREA {
+                        (row_span, col_span) = (1, 1);
+                    }
+                }
+                for row_offset in 0..row_span {
+                    let target = &mut grid[index + row_offset];
+                    for column_offset in 0..col_span {
+                        if target.len() <= column + column_offset {
+                            target.resize(column + column_offset + 1, None);
+                        }
+                        target[column + column_offset] =
+                            Some(if row_offset == 0 && column_offset == 0 {
+                                cell.text.clone()
+                            } else {
+                                String::new()
+                            });
+                    }
+                }
+                column += col_span;
+                width = width.max(column);
+            }
+        }
+
+        grid.into_iter()
+            .map(|mut row| {
+                row.resize(width, None);
+                row.into_iter().map(Option::unwrap_or_default).collect()
+            })
+            .collect()
+    }
+
+    /// Renders the table as normalized CSV, a Markdown table or JSON records, laid
+    /// out with [`Table::to_grid`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParserError`] if the CSV or JSON serialization fails.
+    pub fn render(&self, format: TableFormat) -> Result<String, ParserError> {
+        TextTable {
+            rows: self.to_grid(),
+            has_header: self.has_header,
+        }
+        .render(format)
+    }
+}
+
+/// Extracts the tables of the given data.
+///
+/// This function detects the document format from the provided byte data and
+/// delegates the extraction to the appropriate specialized extractor.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the file data to be processed
+/// * `options` - The parsing options, of which the `encoding` override and the
+///   `file_name` hint are used
+///
+/// # Returns
+///
+/// * `Ok(Vec<Table>)` - The tables found in the file in document order, possibly empty
+/// * `Err(ParserError)` - If the file type is unsupported, unrecognized, or an error occurs during extraction
+///
+/// # Supported Formats
+///
+/// * DOCX: the tables of the body, with their merged cells
+/// * PPTX: the tables of each slide, with their merged cells and header row
+/// * XLSX: the used range of each sheet, with its merged cells
+/// * HTML: the `table` elements, with their spans and `th` header row
+/// * CSV and other delimited text: the whole file, TSV files and HTML files being
+///   recognised from their extension, or sniffed from their content
+/// * PDF: runs of lines split in columns by wide gaps, aligned on a common grid
+///
+/// Header rows are detected from the cells when the format does not tell.
+///
+/// # Examples
+///
+/// ```no_run
+/// use parser::{ParseOptions, TableFormat, extract_tables};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("report.docx")?;
+/// for table in extract_tables(&data, &ParseOptions::default())? {
+///     println!("{}:\n{}", table.position, table.render(TableFormat::Markdown)?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ParserError::InvalidFormat`] if the file type cannot hold tables or is unrecognized.
+/// May return other [`ParserError`] variants if an error occurs during extraction.
+pub fn extract_tables(data: &[u8], options: &ParseOptions) -> Result<Vec<Table>, ParserError> {
+    let mime = determine_mime_type_with_options(data, options);
+    let encoding = options.encoding.as_deref();
+
+    match mime {
+        Some(mime) if mime == APPLICATION_PDF => pdf_tables(data),
+        Some(mime) if mime == APPLICATION_DOCX => docx_tables(data),
+        Some(mime) if mime == APPLICATION_PPTX => pptx_tables(data),
+        Some(mime) if mime == APPLICATION_XLSX => xlsx_tables(data),
+        Some(mime) if mime == TEXT_HTML => Ok(html_tables(&decode_html(data, encoding)?.0)),
+        Some(mime) if mime.type_() == TEXT => {
+            let (text, _) = decode_text(data, encoding)?;
+            text_tables(&text, options.file_name.as_deref())
+        }
+        Some(mime) => Err(ParserError::InvalidFormat(format!(
+            "Unsupported file type for table extraction: {mime}"
+        ))),
+        None => Err(ParserError::InvalidFormat(
+            "Could not determine file type.".to_string(),
+        )),
+    }
+}
+
+/// Extracts the tables of a text file, recognised as HTML or delimited text from its
+/// extension, or from its content.
+fn text_tables(text: &str, file_name: Option<&str>) -> Result<Vec<Table>, ParserError> {
+    let extension = file_name
+        .and_then(|name| Path::new(name).extension())
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some("tsv" | "tab") => csv_tables(text, Some(b'\t')),
+        Some("csv") => csv_tables(text, Some(sniff_delimiter(text).unwrap_or(b','))),
+        Some("html" | "htm" | "xhtml" | "xht") => Ok(html_tables(text)),
+        _ if is_xhtml(text) => Ok(html_tables(text)),
+        _ => csv_tables(text, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(text: &str, row_span: usize, col_span: usize) -> TableCell {
+        TableCell {
+            text: text.to_string(),
+            row_span,
+            col_span,
+        }
+    }
+
+    #[test]
+    fn to_grid_with_spans() {
+        let table = Table::new(
+            "table 1".to_string(),
+            vec![
+                vec![cell("a", 2, 1), cell("b", 1, 2)],
+                vec![cell("c", 1, 1), cell("d", 1, 1)],
+                vec![cell("e", 1, 1)],
+            ],
+            Some(false),
+        );
+
+        assert_eq!(
+            table.to_grid(),
+            vec![vec!["a", "b", ""], vec!["", "c", "d"], vec!["e", "", ""]]
+        );
+    }
+
+    #[test]
+    fn to_grid_with_huge_spans() {
+        let table = Table::new(
+            "table 1".to_string(),
+            vec![
+                vec![cell("a", usize::MAX, usize::MAX), cell("b", 1, 1)],
+                vec![cell("c", 1, 1)],
+            ],
+            Some(false),
+        );
+
+        let grid = table.to_grid();
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid[0].len(), MAX_COL_SPAN + 1);
+        assert_eq!(
+            (grid[0][0].as_str(), grid[0][MAX_COL_SPAN].as_str()),
+            ("a", "b")
+        );
+        assert_eq!(grid[1][MAX_COL_SPAN], "c");
+
+        let mut rows = vec![vec![cell("x", 1, 1)]; 20_000];
+        rows[0] = vec![cell("a", 1, 45), cell("b", 1, 45), cell("c", 1, 1)];
+        let grid = Table::new("table 2".to_string(), rows, Some(false)).to_grid();
+        // Widening the 20,000 rows by 45 columns twice pads more than a million
+        // positions, so the second cell is laid out unmerged
+        assert_eq!(grid.len(), 20_000);
+        assert_eq!(grid[0].len(), 47);
+        assert_eq!(
+            (
+                grid[0][0].as_str(),
+                grid[0][45].as_str(),
+                grid[0][46].as_str()
+            ),
+            ("a", "b", "c")
+        );
+        assert_eq!(
+            grid[19_999],
+            [vec!["x".to_string()], vec![String::new(); 46]].concat()
+        );
+    }
+
+    #[test]
+    fn extract_tables_csv() {
+        let tables =
+            extract_tables(b"name,age\nJohn,30\nJane,28", &ParseOptions::default()).unwrap();
+
+        assert_eq!(tables.len(), 1);
+        assert!(tables[0].has_header);
+        assert_eq!(
+            tables[0].render(TableFormat::Markdown).unwrap(),
+            "| name | age |\n| --- | --- |\n| John | 30 |\n| Jane | 28 |"
+        );
+    }
+
+    #[test]
+    fn extract_tables_tsv_from_file_name() {
+        let options = ParseOptions {
+            file_name: Some("export.tsv".to_string()),
+            ..ParseOptions::default()
+        };
+        let tables = extract_tables(b"name\tnote\nJohn\tfirst, second", &options).unwrap();
+
+        assert_eq!(
+            tables[0].to_grid(),
+            vec![vec!["name", "note"], vec!["John", "first, second"]]
+        );
+    }
+
+    #[test]
+    fn extract_tables_plain_text() {
+        let tables = extract_tables(b"Hello, world!", &ParseOptions::default()).unwrap();
+
+        assert!(tables.is_empty());
+    }
+
+    #[test]
+    fn extract_tables_unsupported() {
+        let result = extract_tables(b"ID3\x04\x00\x00\x00\x00\x00\x00", &ParseOptions::default());
+
+        assert!(matches!(result, Err(ParserError::InvalidFormat(_))));
+    }
+}
@@ -0,0 +1,64 @@
+//! CSV table extraction module.
+//!
+//! This module provides functionality for extracting delimited text files (CSV,
+//! TSV, semicolon or pipe separated) as a single table.
+
+use super::super::{
+    errors::ParserError,
+    parsers::csv::{read_records, sniff_delimiter},
+};
+use super::Table;
+
+/// Extracts the table of delimited text.
+///
+/// # Arguments
+///
+/// * `text` - The decoded content of the file
+/// * `delimiter` - The delimiter of the records, sniffed from the text when not given
+///
+/// # Returns
+///
+/// * `Ok(Vec<Table>)` - The rows of the file as a single table positioned at
+///   `document`, or no table if the text does not look like delimited data
+/// * `Err(ParserError)` - If an error occurs during CSV parsing
+pub(super) fn csv_tables(text: &str, delimiter: Option<u8>) -> Result<Vec<Table>, ParserError> {
+    let Some(delimiter) = delimiter.or_else(|| sniff_delimiter(text)) else {
+        return Ok(Vec::new());
+    };
+
+    Ok(vec![Table::from_texts(
+        "document".to_string(),
+        read_records(text, delimiter)?,
+    )])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> String {
+        std::fs::read_to_string(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn csv_tables_success() {
+        let tables = csv_tables(&read_test_file("test_csv_1.csv"), None).unwrap();
+
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].position, "document");
+        assert!(tables[0].has_header);
+        assert_eq!(
+            tables[0].to_grid(),
+            vec![
+                vec!["Username", "Identifier", "First name", "Last name"],
+                vec!["booker12", "9012", "Rachel", "Booker"],
+                vec!["grey07", "2070", "Laura", "Grey"],
+            ]
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! DOCX table extraction module.
+//!
+//! This module provides functionality for extracting the tables of Microsoft Word
+//! DOCX documents, read with the `docx_rs` library.
+
+use super::super::errors::ParserError;
+use super::{MAX_COL_SPAN, Table, TableCell};
+use docx_rs::{DocumentChild, TableCellContent, TableChild, TableRowChild, read_docx};
+use std::collections::HashMap;
+
+/// Extracts the tables of the body of a DOCX document.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the DOCX data
+///
+/// # Returns
+///
+/// * `Ok(Vec<Table>)` - The tables in document order, positioned by their number,
+///   e.g. `table 2`
+/// * `Err(ParserError)` - If an error occurs during DOCX parsing
+///
+/// # Implementation Notes
+///
+/// * The text of a cell is the text of its paragraphs, one per line
+/// * Horizontally merged cells span the columns of their `gridSpan`, clamped as in
+///   HTML tables, and a cell
+///   restarting a vertical merge spans the rows of the cells continuing it
+/// * The header row is detected from the cells, as `docx_rs` does not read the
+///   `tblHeader` row property
+pub(super) fn docx_tables(data: &[u8]) -> Result<Vec<Table>, ParserError> {
+    let docx = read_docx(data)?;
+
+    let mut tables = Vec::new();
+    for child in &docx.document.children {
+        if let DocumentChild::Table(table) = child {
+            let rows = read_rows(table)?;
+            if !rows.is_empty() {
+                let position = format!("table {}", tables.len() + 1);
+                tables.push(Table::new(position, rows, None));
+            }
+        }
+    }
+
+    Ok(tables)
+}
+
+/// Reads the rows of a table, cells continuing a vertical merge being folded into
+/// the cell restarting it.
+fn read_rows(table: &docx_rs::Table) -> Result<Vec<Vec<TableCell>>, ParserError> {
+    let mut rows: Vec<Vec<TableCell>> = Vec::new();
+    // Cell restarting the vertical merge open in each column, by row and index
+    let mut merges: HashMap<usize, (usize, usize)> = HashMap::new();
+
+    for TableChild::TableRow(row) in &table.rows {
+        let index = rows.len();
+        let mut cells = Vec::new();
+        let mut column = 0;
+
+        for TableRowChild::TableCell(cell) in &row.cells {
+            // The merge properties are private, but serialized
+            let property = serde_json::to_value(&cell.property)?;
+            let col_span = property["gridSpan"]
+                .as_u64()
+                .and_then(|span| usize::try_from(span).ok())
+                .map_or(1, |span| span.clamp(1, MAX_COL_SPAN));
+
+            match property["verticalMerge"].as_str() {
+                Some("continue") => {
+                    if let Some(&(row, cell)) = merges.get(&column) {
+                        rows[row][cell].row_span += 1;
+                    }
+                }
+                merge => {
+                    if merge == Some("restart") {
+                        merges.insert(column, (index, cells.len()));
+                    } else {
+                        merges.remove(&column);
+                    }
+                    cells.push(TableCell::with_spans(
+                        cell_text(&cell.children),
+                        1,
+                        col_span,
+                    ));
+                }
+            }
+            column += col_span;
+        }
+        rows.push(cells);
+    }
+
+    Ok(rows)
+}
+
+/// Reads the text of the paragraphs of a cell, one per line.
+fn cell_text(children: &[TableCellContent]) -> String {
+    children
+        .iter()
+        .filter_map(|content| match content {
+            TableCellContent::Paragraph(paragraph) => Some(paragraph.raw_text()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use docx_rs::{Docx, Paragraph, Run, TableRow, VMergeType};
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn docx_tables_success() {
+        let tables = docx_tables(&read_test_file("test_docx_4.docx")).unwrap();
+
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].position, "table 1");
+        assert_eq!(tables[0].to_grid()[0], vec!["name", "value"]);
+    }
+
+    #[test]
+    fn docx_tables_with_merged_cells() {
+        let cell = |text: &str| {
+            docx_rs::TableCell::new()
+                .add_paragraph(Paragraph::new().add_run(Run::new().add_text(text)))
+        };
+        let table = docx_rs::Table::new(vec![
+            TableRow::new(vec![cell("Product"), cell("Sales").grid_span(2)]),
+            TableRow::new(vec![
+                cell("Widgets").vertical_merge(VMergeType::Restart),
+                cell("10"),
+                cell("12"),
+            ]),
+            TableRow::new(vec![
+                cell("").vertical_merge(VMergeType::Continue),
+                cell("11"),
+                cell("13"),
+            ]),
+        ]);
+        let mut data = std::io::Cursor::new(Vec::new());
+        Docx::new()
+            .add_table(table)
+            .build()
+            .pack(&mut data)
+            .unwrap();
+
+        let tables = docx_tables(data.get_ref()).unwrap();
+
+        assert_eq!(tables[0].rows[0][1].col_span, 2);
+        assert_eq!(tables[0].rows[1][0].row_span, 2);
+        assert_eq!(tables[0].rows[2].len(), 2);
+        assert_eq!(
+            tables[0].to_grid(),
+            vec![
+                vec!["Product", "Sales", ""],
+                vec!["Widgets", "10", "12"],
+                vec!["", "11", "13"]
+            ]
+        );
+    }
+}
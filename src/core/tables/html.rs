@@ -0,0 +1,149 @@
+//! HTML table extraction module.
+//!
+//! This module provides functionality for extracting the `table` elements of HTML
+//! and XHTML documents using the `scraper` library.
+
+use super::super::parsers::html::collapse_whitespace;
+use super::{Table, TableCell};
+use scraper::{ElementRef, Html};
+
+/// Extracts the tables of an HTML document.
+///
+/// # Arguments
+///
+/// * `text` - The decoded content of the document
+///
+/// # Returns
+///
+/// * `Vec<Table>` - The non-empty tables in document order, positioned by their
+///   number, e.g. `table 2`
+///
+/// # Implementation Notes
+///
+/// * Rows are read from the table and its `thead`, `tbody` and `tfoot` sections
+/// * Spans come from the `rowspan` and `colspan` attributes, clamped as by browsers
+/// * The first row is a header when it is in a `thead` or made of `th` cells only
+/// * Nested tables are extracted on their own, their text also being part of the
+///   cell holding them
+pub(super) fn html_tables(text: &str) -> Vec<Table> {
+    let html = Html::parse_document(text);
+
+    html.root_element()
+        .descendent_elements()
+        .filter(|element| element.value().name() == "table")
+        .map(read_rows)
+        .filter(|rows| !rows.is_empty())
+        .zip(1..)
+        .map(|(rows, number)| {
+            let has_header = rows.first().is_some_and(|(in_head, cells)| {
+                *in_head || cells.iter().all(|cell| cell.value().name() == "th")
+            });
+            let rows = rows
+                .iter()
+                .map(|(_, cells)| cells.iter().map(|cell| read_cell(*cell)).collect())
+                .collect();
+            Table::new(format!("table {number}"), rows, Some(has_header))
+        })
+        .collect()
+}
+
+/// Reads the non-empty rows of a table, with whether they are in its `thead`.
+fn read_rows(table: ElementRef<'_>) -> Vec<(bool, Vec<ElementRef<'_>>)> {
+    table
+        .child_elements()
+        .flat_map(|child| match child.value().name() {
+            "thead" | "tbody" | "tfoot" => child
+                .child_elements()
+                .map(|row| (child.value().name() == "thead", row))
+                .collect::<Vec<_>>(),
+            _ => vec![(false, child)],
+        })
+        .filter(|(_, row)| row.value().name() == "tr")
+        .map(|(in_head, row)| {
+            let cells = row
+                .child_elements()
+                .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                .collect::<Vec<_>>();
+            (in_head, cells)
+        })
+        .filter(|(_, cells)| !cells.is_empty())
+        .collect()
+}
+
+/// Reads the text and spans of a `td` or `th` cell.
+fn read_cell(cell: ElementRef<'_>) -> TableCell {
+    let span = |name: &str| {
+        cell.value()
+            .attr(name)
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .unwrap_or(1)
+    };
+
+    TableCell::with_spans(
+        collapse_whitespace(&cell.text().collect::<String>()),
+        span("rowspan"),
+        span("colspan"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> String {
+        std::fs::read_to_string(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn html_tables_success() {
+        let tables = html_tables(&read_test_file("test_html_1.html"));
+
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].position, "table 1");
+        assert!(tables[0].has_header);
+        assert_eq!(
+            tables[0].to_grid(),
+            vec![vec!["Name", "Value"], vec!["alpha", "1"], vec!["beta", "2"]]
+        );
+    }
+
+    #[test]
+    fn html_tables_with_spans() {
+        let tables = html_tables(
+            "<table><thead><tr><td>Product</td><td colspan=\"2\">Sales</td></tr></thead>
+            <tbody><tr><td rowspan=\"2\">Widgets</td><td>10</td><td>12</td></tr>
+            <tr><td>11</td><td>13</td></tr></tbody></table>",
+        );
+
+        assert!(tables[0].has_header);
+        assert_eq!(tables[0].rows[0][1].col_span, 2);
+        assert_eq!(tables[0].rows[1][0].row_span, 2);
+        assert_eq!(
+            tables[0].to_grid(),
+            vec![
+                vec!["Product", "Sales", ""],
+                vec!["Widgets", "10", "12"],
+                vec!["", "11", "13"]
+            ]
+        );
+    }
+
+    #[test]
+    fn html_tables_with_huge_spans() {
+        let tables = html_tables(
+            "<table><tr><td colspan=\"4000000000\" rowspan=\"4000000000\">a</td></tr>
+            <tr><td>b</td></tr></table>",
+        );
+
+        assert_eq!(tables[0].rows[0][0].col_span, 1000);
+        assert_eq!(tables[0].rows[0][0].row_span, 65534);
+        let grid = tables[0].to_grid();
+        assert_eq!((grid.len(), grid[0].len()), (2, 1001));
+        assert_eq!(grid[1][1000], "b");
+    }
+}
@@ -0,0 +1,127 @@
+//! PDF table extraction module.
+//!
+//! This module provides functionality for inferring the tables of PDF documents,
+//! which hold no table structure, from the layout of their lines of text.
+
+use super::super::{errors::ParserError, parsers::pdf::read_segments};
+use super::Table;
+
+/// Minimum number of consecutive lines of several segments making a table
+const MIN_ROWS: usize = 2;
+
+/// Maximum horizontal distance, in points, between segments of the same column
+const COLUMN_TOLERANCE: f64 = 5.0;
+
+/// Extracts the tables inferred from the layout of the lines of a PDF document.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the PDF data
+///
+/// # Returns
+///
+/// * `Ok(Vec<Table>)` - The tables of each page in document order, positioned by
+///   their page, e.g. `page 2`
+/// * `Err(ParserError)` - If an error occurs during PDF parsing
+///
+/// # Implementation Notes
+///
+/// * Lines are split in segments at horizontal gaps wider than their font size
+/// * A table is a run of consecutive lines of at least two segments, broken by
+///   vertical gaps
+/// * Columns are the left edges shared by the segments of the run, so that cells of
+///   right aligned columns may be misplaced
+pub(super) fn pdf_tables(data: &[u8]) -> Result<Vec<Table>, ParserError> {
+    let mut tables = Vec::new();
+
+    for (lines, page) in read_segments(data)?.into_iter().zip(1..) {
+        let mut run: Vec<Vec<(f64, String)>> = Vec::new();
+        for (gap, segments) in lines {
+            if segments.len() < 2 || gap {
+                push_table(&mut tables, std::mem::take(&mut run), page);
+            }
+            if segments.len() >= 2 {
+                run.push(segments);
+            }
+        }
+        push_table(&mut tables, run, page);
+    }
+
+    Ok(tables)
+}
+
+/// Lays a run of lines out in columns and adds it to the tables if it is long enough.
+fn push_table(tables: &mut Vec<Table>, run: Vec<Vec<(f64, String)>>, page: usize) {
+    if run.len() < MIN_ROWS {
+        return;
+    }
+
+    // Group the left edges of the segments in columns of close positions
+    let mut edges = run.iter().flatten().map(|(x, _)| *x).collect::<Vec<f64>>();
+    edges.sort_by(f64::total_cmp);
+    let mut columns: Vec<(f64, f64)> = Vec::new();
+    for x in edges {
+        match columns.last_mut() {
+            Some((_, right)) if x - *right <= COLUMN_TOLERANCE => *right = x,
+            _ => columns.push((x, x)),
+        }
+    }
+
+    let rows = run
+        .into_iter()
+        .map(|segments| {
+            let mut row = vec![String::new(); columns.len()];
+            for (x, text) in segments {
+                let column = columns
+                    .iter()
+                    .position(|(_, right)| x <= *right)
+                    .unwrap_or_default();
+                if !row[column].is_empty() {
+                    row[column].push(' ');
+                }
+                row[column].push_str(&text);
+            }
+            row
+        })
+        .collect();
+
+    tables.push(Table::from_texts(format!("page {page}"), rows));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn pdf_tables_success() {
+        let tables = pdf_tables(&read_test_file("test_pdf_5.pdf")).unwrap();
+
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].position, "page 1");
+        assert!(tables[0].has_header);
+        assert_eq!(
+            tables[0].to_grid(),
+            vec![
+                vec!["Region", "Units", "Revenue"],
+                vec!["North", "120", "1500.50"],
+                vec!["South", "95", "1210.00"]
+            ]
+        );
+    }
+
+    #[test]
+    fn pdf_tables_without_tables() {
+        let tables = pdf_tables(&read_test_file("test_pdf_4.pdf")).unwrap();
+
+        assert!(tables.is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+//! PPTX table extraction module.
+//!
+//! This module provides functionality for extracting the `a:tbl` tables of the
+//! slides of Microsoft `PowerPoint` PPTX presentations, read with roxmltree.
+
+use super::super::{
+    errors::ParserError,
+    parsers::{
+        package::{has_name, open_package, read_text_part},
+        pptx::slide_paths,
+    },
+};
+use super::{Table, TableCell};
+use roxmltree::{Document, Node};
+
+/// Extracts the tables of the slides of a PPTX presentation.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the PPTX data
+///
+/// # Returns
+///
+/// * `Ok(Vec<Table>)` - The tables of each slide in document order, positioned by
+///   their slide, e.g. `slide 3`
+/// * `Err(ParserError)` - If an error occurs while reading the package
+///
+/// # Implementation Notes
+///
+/// * Slides are ordered as in the presentation
+/// * The text of a cell is the text of its paragraphs, one per line
+/// * Cells span the rows and columns of their `rowSpan` and `gridSpan` attributes,
+///   clamped as in HTML tables, the cells they cover, marked `vMerge` or `hMerge`,
+///   being dropped
+/// * The first row is a header when the table style options mark it (`firstRow`)
+pub(super) fn pptx_tables(data: &[u8]) -> Result<Vec<Table>, ParserError> {
+    let mut package = open_package(data)?;
+
+    let mut tables = Vec::new();
+    for (path, slide) in slide_paths(&mut package)?.iter().zip(1..) {
+        let content = read_text_part(&mut package, path)?;
+        let document = Document::parse(&content)?;
+
+        for table in document.descendants().filter(|node| has_name(*node, "tbl")) {
+            let has_header = table
+                .children()
+                .find(|node| has_name(*node, "tblPr"))
+                .and_then(|properties| properties.attribute("firstRow"))
+                .is_some_and(is_true);
+            let rows = table
+                .children()
+                .filter(|node| has_name(*node, "tr"))
+                .map(|row| {
+                    row.children()
+                        .filter(|node| has_name(*node, "tc"))
+                        .filter(|cell| !is_set(*cell, "hMerge") && !is_set(*cell, "vMerge"))
+                        .map(read_cell)
+                        .collect()
+                })
+                .collect::<Vec<Vec<TableCell>>>();
+            if !rows.is_empty() {
+                tables.push(Table::new(format!("slide {slide}"), rows, Some(has_header)));
+            }
+        }
+    }
+
+    Ok(tables)
+}
+
+/// Reads the text and spans of an `a:tc` cell.
+fn read_cell(cell: Node) -> TableCell {
+    let span = |name: &str| {
+        cell.attribute(name)
+            .and_then(|value| value.parse::<usize>().ok())
+            .unwrap_or(1)
+    };
+    let text = cell
+        .descendants()
+        .filter(|node| has_name(*node, "p"))
+        .map(|paragraph| {
+            paragraph
+                .descendants()
+                .filter(|node| has_name(*node, "t"))
+                .filter_map(|node| node.text())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    TableCell::with_spans(text.trim().to_string(), span("rowSpan"), span("gridSpan"))
+}
+
+/// Checks whether a boolean attribute of an element is set.
+fn is_set(node: Node, name: &str) -> bool {
+    node.attribute(name).is_some_and(is_true)
+}
+
+/// Checks whether an XML boolean value is true.
+fn is_true(value: &str) -> bool {
+    matches!(value, "1" | "true")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn pptx_tables_success() {
+        let tables = pptx_tables(&read_test_file("test_pptx_2.pptx")).unwrap();
+
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].position, "slide 1");
+        assert!(tables[0].has_header);
+        assert_eq!(tables[0].rows[1][0].row_span, 2);
+        assert_eq!(tables[0].rows[3][0].col_span, 2);
+        assert_eq!(
+            tables[0].to_grid(),
+            vec![
+                vec!["Product", "Q1", "Q2"],
+                vec!["Widgets", "10", "12"],
+                vec!["", "11", "13"],
+                vec!["Total", "", "46"]
+            ]
+        );
+    }
+
+    #[test]
+    fn pptx_tables_without_tables() {
+        let tables = pptx_tables(&read_test_file("test_pptx_1.pptx")).unwrap();
+
+        assert!(tables.is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+//! XLSX table extraction module.
+//!
+//! This module provides functionality for extracting the sheets of Microsoft Excel
+//! XLSX spreadsheets as tables, read with the calamine library.
+
+use super::super::errors::ParserError;
+use super::{Table, TableCell};
+use calamine::{Reader, Xlsx};
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+
+/// Extracts the used range of each sheet of an XLSX spreadsheet as a table.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice containing the XLSX data
+///
+/// # Returns
+///
+/// * `Ok(Vec<Table>)` - The non-empty sheets in workbook order, positioned by their
+///   name, e.g. `sheet Sheet1`
+/// * `Err(ParserError)` - If an error occurs during XLSX parsing
+///
+/// # Implementation Notes
+///
+/// * Cells are rendered as by the XLSX parser
+/// * Merged regions span their rows and columns within the used range, the cells
+///   they cover being dropped
+/// * Sheets that can't be read, such as chart sheets, are skipped
+pub(super) fn xlsx_tables(data: &[u8]) -> Result<Vec<Table>, ParserError> {
+    let mut excel = Xlsx::new(Cursor::new(data))?;
+    excel.load_merged_regions()?;
+    let sheet_names = excel.sheet_names().clone();
+
+    let mut tables = Vec::new();
+    for name in sheet_names {
+        let Ok(range) = excel.worksheet_range(&name) else {
+            continue;
+        };
+        let (Some((first_row, first_column)), Some((last_row, last_column))) =
+            (range.start(), range.end())
+        else {
+            continue;
+        };
+
+        // Spans of the regions by their top left cell, and the cells they cover, the
+        // regions being bounded by the used range
+        let mut spans = HashMap::new();
+        let mut covered = HashSet::new();
+        for (_, _, region) in excel.merged_regions_by_sheet(&name) {
+            let (top, left) = (
+                region.start.0.max(first_row),
+                region.start.1.max(first_column),
+            );
+            let (bottom, right) = (region.end.0.min(last_row), region.end.1.min(last_column));
+            if top > bottom || left > right {
+                continue;
+            }
+            for row in top..=bottom {
+                for column in left..=right {
+                    covered.insert((row, column));
+                }
+            }
+            covered.remove(&(top, left));
+            spans.insert((top, left), (bottom - top + 1, right - left + 1));
+        }
+
+        let rows = (first_row..)
+            .zip(range.rows())
+            .map(|(row, cells)| {
+                (first_column..)
+                    .zip(cells)
+                    .filter(|(column, _)| !covered.contains(&(row, *column)))
+                    .map(|(column, cell)| {
+                        let (row_span, col_span) =
+                            spans.get(&(row, column)).copied().unwrap_or((1, 1));
+                        // Spans of a sheet always fit in memory sizes
+                        TableCell::with_spans(
+                            cell.to_string(),
+                            usize::try_from(row_span).unwrap_or(1),
+                            usize::try_from(col_span).unwrap_or(1),
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+        tables.push(Table::new(format!("sheet {name}"), rows, None));
+    }
+
+    Ok(tables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    fn read_test_file(filename: &str) -> Vec<u8> {
+        std::fs::read(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/assets")
+                .join(filename),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn xlsx_tables_success() {
+        let tables = xlsx_tables(&read_test_file("test_xlsx_2.xlsx")).unwrap();
+
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[0].position, "sheet Sheet1");
+        assert!(tables[0].has_header);
+        assert_eq!(
+            tables[0].rows[0]
+                .iter()
+                .map(|cell| cell.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["username", "identifier", "first_name"]
+        );
+        assert_eq!(tables[1].position, "sheet Sheet2");
+    }
+
+    /// Builds a workbook of one sheet, with the given cells and merged regions.
+    fn build_workbook(cells: &str, merges: &str) -> Vec<u8> {
+        let parts = [
+            (
+                "[Content_Types].xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#.to_string(),
+            ),
+            (
+                "_rels/.rels",
+                r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#.to_string(),
+            ),
+            (
+                "xl/workbook.xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Data" sheetId="1" r:id="rId1"/></sheets></workbook>"#.to_string(),
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#.to_string(),
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>{cells}</sheetData>{merges}</worksheet>"#
+                ),
+            ),
+        ];
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in parts {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn xlsx_tables_with_merged_regions() {
+        let data = build_workbook(
+            r#"<row r="1"><c r="A1" t="inlineStr"><is><t>Total</t></is></c><c r="C1"><v>3</v></c></row><row r="2"><c r="A2"><v>1</v></c><c r="B2"><v>2</v></c><c r="C2"><v>4</v></c></row>"#,
+            r#"<mergeCells count="2"><mergeCell ref="A1:B1"/><mergeCell ref="C2:XFD1048576"/></mergeCells>"#,
+        );
+        let tables = xlsx_tables(&data).unwrap();
+
+        assert_eq!(tables[0].position, "sheet Data");
+        assert_eq!(tables[0].rows[0][0].col_span, 2);
+        assert_eq!(tables[0].rows[1].len(), 3);
+        assert_eq!(
+            (tables[0].rows[1][2].row_span, tables[0].rows[1][2].col_span),
+            (1, 1)
+        );
+        assert_eq!(
+            tables[0].to_grid(),
+            vec![vec!["Total", "", "3"], vec!["1", "2", "4"]]
+        );
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! This crate provides functionality for parsing and extracting text content from
 //! different file formats including PDFs, Office documents (DOCX, XLSX, PPTX),
-//! text files, and images (using OCR).
+//! text files, and images (using OCR). It can also extract the images and files
+//! embedded in those documents with [`extract_assets`], locate their text with
+//! [`extract_provenance`], and extract their tables with [`extract_tables`].
 //!
 //! # Example
 //!
@@ -19,5 +21,18 @@
 
 mod core;
 
+pub use core::assets::{Asset, extract_assets};
+pub use core::document::ParsedDocument;
 pub use core::errors::ParserError;
-pub use core::parsers::parse;
+pub use core::options::{
+    ArchiveOptions, CodeMode, MathMode, Nesting, NotebookOptions, OutputFormat, ParseOptions,
+    StructuredMode, TableFormat, XmlOptions,
+};
+pub use core::parsers::{
+    ArchiveMember, CalendarEvent, Contact, Cue, MailboxMessage, MboxMessages, parse, parse_archive,
+    parse_contacts, parse_events, parse_mbox, parse_subtitles, parse_with_options,
+};
+pub use core::provenance::{
+    BoundingBox, Location, PROVENANCE_VERSION, Provenance, Span, extract_provenance,
+};
+pub use core::tables::{Table, TableCell, extract_tables};
@@ -6,10 +6,9 @@ use dotenvy::dotenv;
 use env_logger::Env;
 use std::{env, io::Result};
 
-mod core;
 mod web;
 
-use web::{parse_file, serve_files};
+use web::{extract_file_assets, extract_file_tables, locate_file_text, parse_file, serve_files};
 
 #[actix_web::main]
 async fn main() -> Result<()> {
@@ -26,6 +25,9 @@ async fn main() -> Result<()> {
             .wrap(Compress::default())
             .wrap(Logger::default())
             .service(parse_file)
+            .service(extract_file_assets)
+            .service(locate_file_text)
+            .service(extract_file_tables)
             .service(serve_files)
     })
     .bind(("0.0.0.0", port))?
@@ -2,5 +2,8 @@
 
 pub mod errors;
 mod routes;
+mod upload;
 
-pub use routes::{parse_file, serve_files};
+pub use routes::{
+    extract_file_assets, extract_file_tables, locate_file_text, parse_file, serve_files,
+};
@@ -75,8 +75,20 @@ impl From<actix_multipart::MultipartError> for ApiError {
     }
 }
 
-impl From<crate::core::errors::ParserError> for ApiError {
-    fn from(err: crate::core::errors::ParserError) -> Self {
+impl From<parser::ParserError> for ApiError {
+    fn from(err: parser::ParserError) -> Self {
+        ApiError::InternalError(err.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for ApiError {
+    fn from(err: zip::result::ZipError) -> Self {
+        ApiError::InternalError(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for ApiError {
+    fn from(err: serde_json::Error) -> Self {
         ApiError::InternalError(err.to_string())
     }
 }
@@ -1,7 +1,13 @@
 //! Routes module for the web server.
 
+mod assets;
 mod parse;
+mod provenance;
 mod static_files;
+mod tables;
 
+pub use assets::extract_file_assets;
 pub use parse::parse_file;
+pub use provenance::locate_file_text;
 pub use static_files::serve_files;
+pub use tables::extract_file_tables;
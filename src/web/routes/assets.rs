@@ -0,0 +1,125 @@
+//! Routes for extracting embedded assets from documents.
+
+use crate::web::{errors::ApiError, upload::read_files};
+use actix_multipart::Multipart;
+use actix_web::{HttpResponse, http::header::ContentDisposition, post};
+use parser::{Asset, ParserError, extract_assets};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io::{Cursor, Write};
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+/// Name of the archive entry describing the extracted assets
+const MANIFEST_NAME: &str = "manifest.json";
+
+/// Manifest entry describing an extracted asset
+#[derive(Serialize)]
+struct ManifestEntry {
+    /// Path of the asset in the returned archive
+    path: String,
+    /// Index of the uploaded file the asset comes from
+    file: usize,
+    /// Original name of the asset
+    name: String,
+    /// MIME type of the asset
+    mime_type: String,
+    /// Where the asset was found in the document
+    position: String,
+}
+
+/// Extracts the embedded images and attachments of the documents as a zip archive.
+///
+/// Assets of each uploaded file are stored in a folder named after the index of
+/// the file, next to a `manifest.json` describing every asset.
+#[post("/extract-assets")]
+async fn extract_file_assets(payload: Multipart) -> Result<HttpResponse, ApiError> {
+    let files = read_files(payload).await?;
+
+    // Process files in parallel
+    let assets = files
+        .par_iter()
+        .map(|file| extract_assets(&file.data))
+        .collect::<Result<Vec<Vec<Asset>>, ParserError>>()?;
+
+    let archive = build_archive(assets)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header(ContentDisposition::attachment("assets.zip"))
+        .body(archive))
+}
+
+/// Packs the assets of every file into a zip archive with its manifest.
+fn build_archive(assets: Vec<Vec<Asset>>) -> Result<Vec<u8>, ApiError> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+
+    let mut manifest = Vec::new();
+    let mut used_paths = HashSet::new();
+
+    for (file, file_assets) in assets.into_iter().enumerate() {
+        for asset in file_assets {
+            let path = unique_path(&mut used_paths, file, &asset.name);
+
+            writer.start_file(path.as_str(), options)?;
+            writer.write_all(&asset.data)?;
+
+            manifest.push(ManifestEntry {
+                path,
+                file,
+                name: asset.name,
+                mime_type: asset.mime_type,
+                position: asset.position,
+            });
+        }
+    }
+
+    writer.start_file(MANIFEST_NAME, options)?;
+    writer.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+
+    Ok(writer.finish()?.into_inner())
+}
+
+/// Builds a path for an asset that does not collide with the previous ones.
+fn unique_path(used_paths: &mut HashSet<String>, file: usize, name: &str) -> String {
+    // Keep only the file name to avoid path traversal in the archive
+    let name = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let name = if name.is_empty() { "asset" } else { name };
+
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{extension}")),
+        _ => (name, String::new()),
+    };
+
+    let mut path = format!("{file}/{name}");
+    let mut counter = 1;
+    while !used_paths.insert(path.clone()) {
+        path = format!("{file}/{stem}_{counter}{extension}");
+        counter += 1;
+    }
+
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_path_success() {
+        let mut used_paths = HashSet::new();
+
+        assert_eq!(unique_path(&mut used_paths, 0, "image.png"), "0/image.png");
+        assert_eq!(
+            unique_path(&mut used_paths, 0, "image.png"),
+            "0/image_1.png"
+        );
+        assert_eq!(unique_path(&mut used_paths, 1, "image.png"), "1/image.png");
+        assert_eq!(
+            unique_path(&mut used_paths, 0, "../../etc/passwd"),
+            "0/passwd"
+        );
+        assert_eq!(unique_path(&mut used_paths, 0, ""), "0/asset");
+    }
+}
@@ -1,19 +1,138 @@
 //! Routes for parsing documents.
 
-use crate::core::errors::ParserError;
-use crate::core::parsers::parse;
-use crate::web::errors::ApiError;
+use crate::web::{errors::ApiError, upload::read_files};
 use actix_multipart::Multipart;
-use actix_web::{HttpRequest, HttpResponse, Responder, body::BoxBody, post};
-use futures_util::TryStreamExt;
+use actix_web::{HttpRequest, HttpResponse, Responder, body::BoxBody, post, web::Query};
+use parser::{
+    ArchiveOptions, CodeMode, MathMode, NotebookOptions, OutputFormat, ParseOptions,
+    ParsedDocument, ParserError, StructuredMode, TableFormat, XmlOptions, parse_with_options,
+};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Query parameters accepted when parsing documents
+#[derive(Deserialize)]
+#[allow(clippy::struct_excessive_bools)] // Each flag is an independent query parameter
+pub(super) struct ParseQuery {
+    /// Character encoding of text files, overriding the automatic detection
+    encoding: Option<String>,
+    /// Rendering of tabular content: `csv` (default), `markdown` or `json`
+    #[serde(default)]
+    table_format: TableFormat,
+    /// Rendering of structured data: `pretty` (default), `flatten` or `strings`
+    #[serde(default)]
+    structured_mode: StructuredMode,
+    /// Whether invalid structured data is rejected rather than returned as is
+    #[serde(default)]
+    strict: bool,
+    /// Syntax of the extracted text: `text` (default) or `markdown`
+    #[serde(default)]
+    format: OutputFormat,
+    /// Whether link targets are kept next to the link text
+    #[serde(default)]
+    keep_links: bool,
+    /// Whether code blocks are dropped from markup documents
+    #[serde(default)]
+    skip_code_blocks: bool,
+    /// Whether subtitle paragraphs start with their timestamp
+    #[serde(default)]
+    timestamps: bool,
+    /// Extraction of source code: `full` (default), `comments` or `strip`
+    #[serde(default)]
+    code_mode: CodeMode,
+    /// Rendering of LaTeX formulas: `raw` (default) or `placeholder`
+    #[serde(default)]
+    math_mode: MathMode,
+    /// Whether text is recognised in rendered SVG images
+    #[serde(default)]
+    svg_ocr: bool,
+    /// XPath-like path selecting the elements extracted from XML documents
+    xml_select: Option<String>,
+    /// Comma-separated names of the XML elements to extract
+    xml_include: Option<String>,
+    /// Comma-separated names of the XML elements to skip
+    xml_exclude: Option<String>,
+    /// Whether XML attribute values are extracted
+    #[serde(default)]
+    xml_attributes: bool,
+    /// Whether the text outputs of notebook cells are extracted
+    #[serde(default)]
+    notebook_outputs: bool,
+    /// Whether text is recognised in the image outputs of notebook cells
+    #[serde(default)]
+    notebook_ocr: bool,
+    /// Maximum nesting level of archives, at most the default
+    archive_max_depth: Option<usize>,
+    /// Maximum number of archive members, at most the default
+    archive_max_members: Option<usize>,
+    /// Maximum number of bytes decompressed from archives, at most the default
+    archive_max_size: Option<u64>,
+}
+
+impl From<ParseQuery> for ParseOptions {
+    fn from(query: ParseQuery) -> Self {
+        let defaults = ArchiveOptions::default();
+        ParseOptions {
+            encoding: query.encoding,
+            table_format: query.table_format,
+            structured_mode: query.structured_mode,
+            strict: query.strict,
+            output_format: query.format,
+            keep_links: query.keep_links,
+            skip_code_blocks: query.skip_code_blocks,
+            timestamps: query.timestamps,
+            code_mode: query.code_mode,
+            math_mode: query.math_mode,
+            svg_ocr: query.svg_ocr,
+            xml: XmlOptions {
+                select: query.xml_select,
+                include: split_list(query.xml_include.as_deref()),
+                exclude: split_list(query.xml_exclude.as_deref()),
+                attributes: query.xml_attributes,
+            },
+            notebook: NotebookOptions {
+                outputs: query.notebook_outputs,
+                ocr_images: query.notebook_ocr,
+            },
+            // Clients may lower the archive limits, never raise them
+            archive: ArchiveOptions {
+                max_depth: query
+                    .archive_max_depth
+                    .map_or(defaults.max_depth, |depth| depth.min(defaults.max_depth)),
+                max_members: query
+                    .archive_max_members
+                    .map_or(defaults.max_members, |count| {
+                        count.min(defaults.max_members)
+                    }),
+                max_size: query
+                    .archive_max_size
+                    .map_or(defaults.max_size, |size| size.min(defaults.max_size)),
+            },
+            ..ParseOptions::default()
+        }
+    }
+}
+
+/// Splits a comma-separated query parameter into its non-empty items.
+fn split_list(list: Option<&str>) -> Vec<String> {
+    list.map(|list| {
+        list.split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    })
+    .unwrap_or_default()
+}
 
 /// Response type for parsed texts
 #[derive(Serialize, Deserialize)]
 struct ParseResponse {
     /// Parsed text from the documents
     texts: Vec<String>,
+    /// Metadata of the documents, such as the detected encoding of text files
+    metadata: Vec<BTreeMap<String, String>>,
 }
 
 impl Responder for ParseResponse {
@@ -26,36 +145,49 @@ impl Responder for ParseResponse {
 
 /// Parses various document formats into plain text.
 #[post("/parse")]
-async fn parse_file(mut payload: Multipart) -> Result<ParseResponse, ApiError> {
-    let mut files = Vec::new();
+async fn parse_file(
+    query: Query<ParseQuery>,
+    payload: Multipart,
+) -> Result<ParseResponse, ApiError> {
+    let options = ParseOptions::from(query.into_inner());
+    let files = read_files(payload).await?;
 
-    // Process each field in the multipart payload
-    while let Some(mut field) = payload.try_next().await? {
-        // Buffer to store the file data
-        let mut buffer = Vec::new();
+    // Process files in parallel
+    let documents = files
+        .par_iter()
+        .map(|file| {
+            // The file name hints at formats that content alone cannot tell apart
+            let options = ParseOptions {
+                file_name: file.name.clone(),
+                ..options.clone()
+            };
+            parse_with_options(&file.data, &options)
+        })
+        .collect::<Result<Vec<ParsedDocument>, ParserError>>()?;
 
-        // Stream chunks directly into buffer
-        while let Some(chunk) = field.try_next().await? {
-            buffer.extend_from_slice(&chunk);
-        }
+    let (texts, metadata) = documents
+        .into_iter()
+        .map(|document| (document.text, document.metadata))
+        .unzip();
 
-        // Only add non-empty files
-        if !buffer.is_empty() {
-            files.push(buffer);
-        }
-    }
+    Ok(ParseResponse { texts, metadata })
+}
 
-    if files.is_empty() {
-        return Err(ApiError::BadRequest("No files provided".to_string()));
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Process files in parallel
-    let parsed_text = files
-        .par_iter()
-        .map(|data| parse(data))
-        .collect::<Result<Vec<String>, ParserError>>();
+    #[test]
+    fn parse_query_caps_archive_limits() {
+        let query = Query::<ParseQuery>::from_query(
+            "archive_max_depth=1000&archive_max_members=2&archive_max_size=18446744073709551615",
+        )
+        .unwrap();
+        let options = ParseOptions::from(query.into_inner());
+        let defaults = ArchiveOptions::default();
 
-    Ok(ParseResponse {
-        texts: parsed_text?,
-    })
+        assert_eq!(options.archive.max_depth, defaults.max_depth);
+        assert_eq!(options.archive.max_members, 2);
+        assert_eq!(options.archive.max_size, defaults.max_size);
+    }
 }
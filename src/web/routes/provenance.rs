@@ -0,0 +1,51 @@
+//! Routes for locating the text of documents.
+
+use super::parse::ParseQuery;
+use crate::web::{errors::ApiError, upload::read_files};
+use actix_multipart::Multipart;
+use actix_web::{HttpRequest, HttpResponse, Responder, body::BoxBody, post, web::Query};
+use parser::{ParseOptions, ParserError, Provenance, extract_provenance};
+use rayon::prelude::*;
+use serde::Serialize;
+
+/// Response type for located texts
+#[derive(Serialize)]
+struct ProvenanceResponse {
+    /// Spans of text of the documents, with where they were found
+    documents: Vec<Provenance>,
+}
+
+impl Responder for ProvenanceResponse {
+    type Body = BoxBody;
+
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+        HttpResponse::Ok().json(self)
+    }
+}
+
+/// Extracts the text of the documents as spans located in each document.
+///
+/// Accepts the query parameters of `/parse`, used for formats without positions.
+#[post("/provenance")]
+async fn locate_file_text(
+    query: Query<ParseQuery>,
+    payload: Multipart,
+) -> Result<ProvenanceResponse, ApiError> {
+    let options = ParseOptions::from(query.into_inner());
+    let files = read_files(payload).await?;
+
+    // Process files in parallel
+    let documents = files
+        .par_iter()
+        .map(|file| {
+            // The file name hints at formats that content alone cannot tell apart
+            let options = ParseOptions {
+                file_name: file.name.clone(),
+                ..options.clone()
+            };
+            extract_provenance(&file.data, &options)
+        })
+        .collect::<Result<Vec<Provenance>, ParserError>>()?;
+
+    Ok(ProvenanceResponse { documents })
+}
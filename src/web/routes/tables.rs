@@ -0,0 +1,65 @@
+//! Routes for extracting the tables of documents.
+
+use crate::web::{errors::ApiError, upload::read_files};
+use actix_multipart::Multipart;
+use actix_web::{HttpResponse, post, web::Query};
+use parser::{ParseOptions, ParserError, Table, TableFormat, extract_tables};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Query parameters accepted when extracting tables
+#[derive(Deserialize)]
+struct TablesQuery {
+    /// Rendering of the tables: cells as JSON (default), or `csv` or `markdown` text
+    format: Option<TableFormat>,
+}
+
+/// Response type for extracted tables
+#[derive(Serialize)]
+struct TablesResponse {
+    /// Tables of each document, with their cells
+    tables: Vec<Vec<Table>>,
+}
+
+/// Extracts the tables of the documents.
+///
+/// Tables are returned as JSON, with their cells and spans, unless a `csv` or
+/// `markdown` format is asked for, in which case they are rendered one after the
+/// other, separated by blank lines.
+#[post("/tables")]
+async fn extract_file_tables(
+    query: Query<TablesQuery>,
+    payload: Multipart,
+) -> Result<HttpResponse, ApiError> {
+    let files = read_files(payload).await?;
+
+    // Process files in parallel
+    let tables = files
+        .par_iter()
+        .map(|file| {
+            // The file name hints at formats that content alone cannot tell apart
+            let options = ParseOptions {
+                file_name: file.name.clone(),
+                ..ParseOptions::default()
+            };
+            extract_tables(&file.data, &options)
+        })
+        .collect::<Result<Vec<Vec<Table>>, ParserError>>()?;
+
+    let (format, content_type) = match query.format {
+        None | Some(TableFormat::Json) => {
+            return Ok(HttpResponse::Ok().json(TablesResponse { tables }));
+        }
+        Some(TableFormat::Csv) => (TableFormat::Csv, "text/csv; charset=utf-8"),
+        Some(TableFormat::Markdown) => (TableFormat::Markdown, "text/markdown; charset=utf-8"),
+    };
+    let rendered = tables
+        .iter()
+        .flatten()
+        .map(|table| table.render(format))
+        .collect::<Result<Vec<String>, ParserError>>()?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(content_type)
+        .body(rendered.join("\n\n")))
+}
@@ -0,0 +1,50 @@
+//! Multipart upload handling shared by the routes.
+
+use crate::web::errors::ApiError;
+use actix_multipart::Multipart;
+use futures_util::TryStreamExt;
+
+/// File read from a multipart payload
+pub struct UploadedFile {
+    /// Name of the file given by the client, if any
+    pub name: Option<String>,
+    /// Content of the file
+    pub data: Vec<u8>,
+}
+
+/// Reads every non-empty file of a multipart payload into memory.
+///
+/// # Errors
+///
+/// Returns [`ApiError::BadRequest`] if the payload contains no file, or
+/// [`ApiError::InternalError`] if the payload cannot be read.
+pub async fn read_files(mut payload: Multipart) -> Result<Vec<UploadedFile>, ApiError> {
+    let mut files = Vec::new();
+
+    // Process each field in the multipart payload
+    while let Some(mut field) = payload.try_next().await? {
+        let name = field
+            .content_disposition()
+            .and_then(|disposition| disposition.get_filename())
+            .map(str::to_string);
+
+        // Buffer to store the file data
+        let mut buffer = Vec::new();
+
+        // Stream chunks directly into buffer
+        while let Some(chunk) = field.try_next().await? {
+            buffer.extend_from_slice(&chunk);
+        }
+
+        // Only add non-empty files
+        if !buffer.is_empty() {
+            files.push(UploadedFile { name, data: buffer });
+        }
+    }
+
+    if files.is_empty() {
+        return Err(ApiError::BadRequest("No files provided".to_string()));
+    }
+
+    Ok(files)
+}
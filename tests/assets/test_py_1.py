@@ -0,0 +1,16 @@
+#!/usr/bin/env python3
+"""Hello, this is a test py for the parsing API."""
+
+import math
+
+# Radius of the unit circle
+RADIUS = 1  # in meters
+
+
+def area(radius):
+    """Return the area of a circle.
+
+    The radius must be positive.
+    """
+    url = "http://example.com/#anchor"
+    return math.pi * radius**2  # pi r squared
\ No newline at end of file
@@ -7,12 +7,49 @@ use std::path::PathBuf;
 fn test_file_paths_exist() {
     let file_names = vec![
         "test_pdf_1.pdf",
+        "test_pdf_3.pdf",
+        "test_pdf_4.pdf",
+        "test_pdf_5.pdf",
         "test_docx_1.docx",
+        "test_docx_3.docx",
+        "test_docx_4.docx",
         "test_xlsx_1.xlsx",
         "test_pptx_1.pptx",
+        "test_pptx_2.pptx",
+        "test_odt_1.odt",
+        "test_odp_1.odp",
+        "test_doc_1.doc",
+        "test_ppt_1.ppt",
+        "test_epub_1.epub",
+        "test_pages_1.pages",
+        "test_key_1.key",
+        "test_numbers_1.numbers",
+        "test_xps_1.xps",
+        "test_oxps_1.oxps",
         "test_txt_1.txt",
+        "test_txt_3.txt",
+        "test_txt_4.txt",
         "test_csv_1.csv",
         "test_json_1.json",
+        "test_html_1.html",
+        "test_xml_1.xml",
+        "test_md_1.md",
+        "test_rtf_1.rtf",
+        "test_eml_1.eml",
+        "test_msg_1.msg",
+        "test_mbox_1.mbox",
+        "test_zip_1.zip",
+        "test_tar_1.tar.gz",
+        "test_7z_1.7z",
+        "test_ics_1.ics",
+        "test_vcf_1.vcf",
+        "test_srt_1.srt",
+        "test_vtt_1.vtt",
+        "test_ipynb_1.ipynb",
+        "test_py_1.py",
+        "test_tex_1.tex",
+        "test_bib_1.bib",
+        "test_svg_1.svg",
         "test_png_1.png",
         "test_jpg_1.jpg",
         "test_webp_1.webp",
@@ -3,19 +3,56 @@ use std::{fs, path::PathBuf};
 use parser::parse;
 use rayon::prelude::*;
 
+#[allow(clippy::too_many_lines)] // One entry per test file
 fn get_test_data() -> (Vec<&'static str>, Vec<String>) {
     let file_names = vec![
         "test_pdf_1.pdf",
         "test_pdf_2.pdf",
+        "test_pdf_3.pdf",
+        "test_pdf_4.pdf",
+        "test_pdf_5.pdf",
         "test_docx_1.docx",
         "test_docx_2.docx",
+        "test_docx_3.docx",
+        "test_docx_4.docx",
         "test_xlsx_1.xlsx",
         "test_xlsx_2.xlsx",
         "test_pptx_1.pptx",
+        "test_pptx_2.pptx",
+        "test_odt_1.odt",
+        "test_odp_1.odp",
+        "test_doc_1.doc",
+        "test_ppt_1.ppt",
+        "test_epub_1.epub",
+        "test_pages_1.pages",
+        "test_key_1.key",
+        "test_numbers_1.numbers",
+        "test_xps_1.xps",
+        "test_oxps_1.oxps",
         "test_txt_1.txt",
         "test_txt_2.txt",
+        "test_txt_3.txt",
+        "test_txt_4.txt",
         "test_csv_1.csv",
         "test_json_1.json",
+        "test_html_1.html",
+        "test_xml_1.xml",
+        "test_rtf_1.rtf",
+        "test_eml_1.eml",
+        "test_msg_1.msg",
+        "test_mbox_1.mbox",
+        "test_zip_1.zip",
+        "test_tar_1.tar.gz",
+        "test_7z_1.7z",
+        "test_ics_1.ics",
+        "test_vcf_1.vcf",
+        "test_srt_1.srt",
+        "test_vtt_1.vtt",
+        "test_ipynb_1.ipynb",
+        "test_py_1.py",
+        "test_tex_1.tex",
+        "test_bib_1.bib",
+        "test_svg_1.svg",
         "test_png_1.png",
         "test_jpg_1.jpg",
         "test_webp_1.webp",
@@ -24,8 +61,40 @@ fn get_test_data() -> (Vec<&'static str>, Vec<String>) {
     let expected_texts = vec![
         "Hello, this is a test pdf for the parsing API.".to_string(),
         "Hello, this is another test pdf for the parsing API.".to_string(),
+        "Hello, this is a test pdf with an image for the parsing API.".to_string(),
+        "Test PDF Document
+
+Introduction
+
+Hello, this is a test pdf with headings
+for the parsing API.
+
+Conclusion
+
+The second page ends the document."
+            .to_string(),
+        "Quarterly results by region:
+
+Region Units Revenue
+North 120 1500.50
+South 95 1210.00
+
+Figures are provisional."
+            .to_string(),
         "Hello, this is a test docx for the parsing API.".to_string(),
         "Hello, this is another test docx for the parsing API.".to_string(),
+        "Hello, this is a test docx with an image for the parsing API.".to_string(),
+        "Test Document
+Introduction
+Hello, this is a test docx with structure.
+First item
+Nested item
+Second item
+Details
+First step
+Second step
+The end."
+            .to_string(),
         "username,identifier,first_name
 johndoe123,4281,John
 alice23,8425,Alice"
@@ -43,11 +112,87 @@ This is the subtitle
 This is the title of the second slide
 This is the text of the second slide"
             .to_string(),
+        "Sales by quarter
+Product
+Q1
+Q2
+Widgets
+10
+12
+11
+13
+Total
+46"
+        .to_string(),
+        "Test Document
+Hello, this is a test odt for the parsing API.
+- First item
+  - Nested item
+- Second item
+A footnote.
+Name\tValue
+alpha\t1"
+            .to_string(),
+        "This is the title
+This is the subtitle
+
+--- Slide 2 ---
+This is the title of the second slide
+This is the text of the second slide
+--- Notes ---
+These are the speaker notes"
+            .to_string(),
+        "Hello, this is a test doc for the parsing API.\nCafé crème, €5\nName\tValue\nalpha\t1"
+            .to_string(),
+        "This is the title
+This is the subtitle
+
+--- Slide 2 ---
+This is the title of the second slide
+This is the text of the second slide
+--- Notes ---
+These are the speaker notes"
+            .to_string(),
+        "--- Chapter: Introduction ---
+Hello, this is a test epub for the parsing API.
+
+--- Chapter: The Second Chapter ---
+The second chapter, with emphasis.
+
+- First item
+- Second item"
+            .to_string(),
+        "Test Pages Document
+Hello, this is a test pages for the parsing API.
+A line break
+and an attachment.
+Page header"
+            .to_string(),
+        "Test Keynote
+Hello, this is a test key for the parsing API.
+--- Notes ---
+Speaker notes
+
+--- Slide 2 ---
+Second slide"
+            .to_string(),
+        "Hello, this is a test numbers for the parsing API.".to_string(),
+        "Hello, this is a test xps for the parsing API.
+Second line & more
+
+--- Page 2 ---
+{Braced} second page"
+            .to_string(),
+        "Hello, this is a test oxps for the parsing API.".to_string(),
         "Hello, this is a test txt for the parsing API.".to_string(),
         "Hello, this is another test txt for the parsing API.".to_string(),
-        "Username; Identifier;First name;Last name
-booker12;9012;Rachel;Booker
-grey07;2070;Laura;Grey"
+        "Bonjour, ceci est un fichier texte encodé en Windows-1252 pour l'API de parsing. \
+Ça fonctionne très bien, à bientôt !"
+            .to_string(),
+        "Hello, this is a UTF-16 test txt for the parsing API.".to_string(),
+        "Username,Identifier,First name,Last name
+booker12,9012,Rachel,Booker
+grey07,2070,Laura,Grey"
             .to_string(),
         r#"{
     "name": "John Doe",
@@ -55,6 +200,190 @@ grey07;2070;Laura;Grey"
     "email": "john@example.com"
 }"#
         .to_string(),
+        "Test Page
+
+Hello, this is a test html for the parsing API.
+
+Features:
+
+- Scripts & styles are removed
+- Entities are decoded
+
+Name,Value
+alpha,1
+beta,2
+
+Visit the website for more."
+            .to_string(),
+        "Test XML Feed
+Hello, this is a test xml for the parsing API.
+First item
+The first description, with emphasis.
+Second item
+The second description."
+            .to_string(),
+        "Hello, this is a test rtf for the parsing API.\nCafé — 10 €".to_string(),
+        "From: Jane Doe <jane@example.com>
+To: John Smith <john@example.com>
+Date: Mon, 15 Jan 2024 10:30:00 +0000
+Subject: Café meeting notes
+
+Hello, this is a test eml for the parsing API.
+Prices start at 5 €.
+
+--- Attachment: notes.txt ---
+These are the attachment notes."
+            .to_string(),
+        "From: Jane Doe <jane@example.com>
+To: John Smith
+Date: Mon, 15 Jan 2024 10:30:00 +0000
+Subject: Café meeting notes
+
+Hello, this is a test msg for the parsing API.
+
+--- Attachment: notes.txt ---
+These are the attachment notes."
+            .to_string(),
+        "From: Jane Doe <jane@example.com>
+To: John Smith <john@example.com>
+Date: Mon, 15 Jan 2024 10:30:00 +0000
+Subject: First message
+
+Hello, this is a test mbox for the parsing API.
+From the start, quoted lines are unquoted.
+>From here, one level remains.
+
+--- Message 2 ---
+From: John Smith <john@example.com>
+To: Jane Doe <jane@example.com>
+Date: Mon, 15 Jan 2024 11:00:00 +0000
+Subject: Second message
+
+Thanks, the café is booked."
+            .to_string(),
+        "--- File: readme.txt ---
+Hello, this is a test zip for the parsing API.
+
+--- File: docs/notes.md ---
+Notes
+
+Archives are unpacked recursively.
+
+--- File: docs/nested.tar.gz/inner/hello.txt ---
+Hello from a nested archive.
+
+--- File: docs/report.txt ---
+A compressed report."
+            .to_string(),
+        "--- File: report.txt ---
+Hello, this is a test tar for the parsing API.
+
+--- File: data.csv ---
+name,value
+alpha,1
+beta,2"
+            .to_string(),
+        "--- File: hello.txt ---\nHello, this is a test 7z for the parsing API.".to_string(),
+        "Summary: Project kickoff, phase 1
+Start: 2024-01-15 10:30 Europe/Paris
+End: 2024-01-15 12:00 Europe/Paris
+Location: Room 4; Building A
+Attendee: Jane Doe <jane@example.com>
+Attendee: john@example.com
+
+Hello, this is a test ics for the parsing API.
+The agenda follows.
+
+--- Event 2 ---
+Summary: Review
+Start: 2024-01-22"
+            .to_string(),
+        "Name: Jane Doe
+Organization: Example, Inc., Sales
+Email: jane@example.com
+Email: jane.doe@home.example
+Phone: +1 555 0100
+
+--- Contact 2 ---
+Name: François Martin
+Email: francois@example.fr
+Phone: +33 1 23 45 67 89"
+            .to_string(),
+        "Hello, this is a test srt for the parsing API. Cues are merged into paragraphs.
+
+After a pause, a new paragraph starts."
+            .to_string(),
+        "Hello, this is a test vtt for the parsing API. Speakers & tags are removed.
+
+Rolling captions are not repeated."
+            .to_string(),
+        "Analysis
+
+Hello, this is a test ipynb for the parsing API.
+
+--- Code: python ---
+import math
+print(math.pi)
+
+--- Code: python ---
+values = [1, 2, 3]
+sum(values)
+
+--- Code: python ---
+1 / 0"
+            .to_string(),
+        r#"#!/usr/bin/env python3
+"""Hello, this is a test py for the parsing API."""
+
+import math
+
+# Radius of the unit circle
+RADIUS = 1  # in meters
+
+
+def area(radius):
+    """Return the area of a circle.
+
+    The radius must be positive.
+    """
+    url = "http://example.com/#anchor"
+    return math.pi * radius**2  # pi r squared"#
+            .to_string(),
+        "Test LaTeX Document
+
+Jane Doe
+
+Abstract
+
+Hello, this is a test tex for the parsing API.
+
+Introduction
+
+Text with bold and emphasis, a citation [doe2020, martin2019] and a formula E = mc^2. (A footnote.)
+
+- First item
+- Second item
+
+Results
+
+\\int_0^1 x^2 \\, dx = \\frac{1}{3}
+
+Name,Value
+alpha,1
+
+Caf\u{e9} \u{2014} na\u{ef}ve & done."
+            .to_string(),
+        "[doe2020] Jane Doe and John Smith (2020). Hello, this is a test bib for the parsing API. \
+         Journal of Computational Parsing, 12(3), pp. 45\u{2013}67. doi:10.1000/xyz123
+[martin2019] Fran\u{e7}ois Martin (2019). Parsing Documents. Example Press."
+            .to_string(),
+        "Test SVG
+A test svg for the parsing API.
+Hello, this is a test svg for the parsing API.
+First line
+Second line
+Link & text"
+            .to_string(),
         "Hello World! This is an OCR test.\n123456789\n0.123 | 45.67 | 890".to_string(),
         "Hello World! This is an OCR test.\n123456789\n0.123 | 45.67 | 890".to_string(),
         "Hello World! This is an OCR test.\n123456789\n0.123 | 45.67 | 890".to_string(),